@@ -1,8 +1,13 @@
+use std::net::IpAddr;
 use std::path::PathBuf;
 
 use crate::common::{DeploymentState, OutputFormat, OutputMode};
+use crate::config::TagFilter;
+use crate::domain::{FilterExpr, Profile, ServiceDetails, named_filter_reference};
+use axum::http::HeaderValue;
 use clap::{Parser, Subcommand};
 use regex::Regex;
+use reqwest::Url;
 
 const NOT_PROVIDED: &str = "<not provided>";
 
@@ -24,15 +29,28 @@ pub enum EcscopeCommand {
     /// List ECS deployments
     #[command(name = "deps")]
     Deployments {
-        /// Profile to use
+        /// Profile to use (falls back to ECSCOPE_PROFILE)
         #[arg(value_name = "PROFILE")]
-        profile_name: String,
+        profile_name: Option<String>,
+        /// Profile file to use (instead of looking up a profile by name)
+        #[arg(
+            long = "profile-file",
+            value_name = "PATH",
+            conflicts_with = "profile_name"
+        )]
+        profile_file: Option<PathBuf>,
         /// Filtration query for service names
         #[arg(short = 's', long = "service-filter", value_name = "REGEX", value_parser=validate_filter_query)]
         service_name_filter: Option<Regex>,
         /// Filtration query for cluster keys
         #[arg(short = 'k', long = "key-filter", value_name = "REGEX", value_parser=validate_filter_query)]
         key_filter: Option<Regex>,
+        /// Only include services with this tag (can be repeated; all must match)
+        #[arg(short = 't', long = "tag", value_name = "KEY=VALUE")]
+        tag_filters: Vec<TagFilter>,
+        /// Filter results, eg. "key=prod and failed>0" (or "@<name>" for a filter from the profile)
+        #[arg(short = 'w', long = "where", value_name = "EXPR", value_parser=validate_where_filter)]
+        where_filter: Option<String>,
         /// Deployment state to query for
         #[arg(short = 'S', long = "state", value_name = "STRING")]
         state: Option<DeploymentState>,
@@ -44,6 +62,17 @@ pub enum EcscopeCommand {
             default_value = "json"
         )]
         format: OutputFormat,
+        /// File to write results to, instead of stdout (when --mode=default)
+        #[arg(short = 'o', long = "output", value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Post results to this Slack incoming webhook, instead of printing them (needs --format slack)
+        #[arg(
+            long = "post-to",
+            value_name = "WEBHOOK_URL",
+            value_parser = validate_webhook_url,
+            conflicts_with = "output"
+        )]
+        post_to: Option<Url>,
         /// Output mode
         #[arg(
             short = 'm',
@@ -55,6 +84,16 @@ pub enum EcscopeCommand {
         /// Whether to skip opening web results in browser (when --mode=web)
         #[arg(long = "web-skip-opening")]
         web_skip_opening: bool,
+        /// How long results are served from the cache for before they're refreshed (when --mode=web)
+        #[arg(long = "web-cache-ttl", value_name = "SECONDS", default_value_t = 5)]
+        web_cache_ttl: u64,
+        /// How long stale results can still be served for while they're refreshed (when --mode=web)
+        #[arg(
+            long = "web-cache-max-stale",
+            value_name = "SECONDS",
+            default_value_t = 30
+        )]
+        web_cache_max_stale: u64,
     },
     /// Manage ecscope's profiles
     Profiles {
@@ -63,15 +102,123 @@ pub enum EcscopeCommand {
     },
     /// Open monitoring TUI
     Monitor {
-        /// Profile to use
+        /// Profile to use (falls back to ECSCOPE_PROFILE)
         #[arg(value_name = "PROFILE")]
-        profile_name: String,
+        profile_name: Option<String>,
+        /// Profile file to use (instead of looking up a profile by name)
+        #[arg(
+            long = "profile-file",
+            value_name = "PATH",
+            conflicts_with = "profile_name"
+        )]
+        profile_file: Option<PathBuf>,
         /// Filtration query for service names
         #[arg(short = 's', long = "service-filter", value_name = "REGEX", value_parser=validate_filter_query)]
         service_name_filter: Option<Regex>,
         /// Filtration query for cluster keys
         #[arg(short = 'k', long = "key-filter", value_name = "REGEX", value_parser=validate_filter_query)]
         key_filter: Option<Regex>,
+        /// Only include services with this tag (can be repeated; all must match)
+        #[arg(short = 't', long = "tag", value_name = "KEY=VALUE")]
+        tag_filters: Vec<TagFilter>,
+        /// Filter results, eg. "key=prod and running<desired" (or "@<name>" for a filter from the profile)
+        #[arg(short = 'w', long = "where", value_name = "EXPR", value_parser=validate_services_where_filter)]
+        where_filter: Option<String>,
+        /// Start afresh, ignoring the state saved from the last session
+        #[arg(long = "fresh")]
+        fresh: bool,
+    },
+    /// Serve the deployments of one or more profiles over HTTP
+    Serve {
+        /// Profiles to serve
+        #[arg(value_name = "PROFILE", required = true, value_parser = validate_profile_name)]
+        profile_names: Vec<String>,
+        /// Address to bind to
+        #[arg(
+            short = 'b',
+            long = "bind",
+            value_name = "ADDRESS",
+            default_value = "127.0.0.1"
+        )]
+        bind: IpAddr,
+        /// Port to listen on
+        #[arg(
+            short = 'p',
+            long = "port",
+            value_name = "PORT",
+            default_value_t = 4500
+        )]
+        port: u16,
+        /// How long results are served from the cache for before they're refreshed
+        #[arg(long = "cache-ttl", value_name = "SECONDS", default_value_t = 5)]
+        cache_ttl: u64,
+        /// How long stale results can still be served for while they're refreshed
+        #[arg(long = "cache-max-stale", value_name = "SECONDS", default_value_t = 30)]
+        cache_max_stale: u64,
+        /// The least time between calls to ECS made for a profile
+        #[arg(
+            long = "min-refresh-interval",
+            value_name = "SECONDS",
+            default_value_t = 1
+        )]
+        min_refresh_interval: u64,
+        /// How many requests to the API a client can make per minute
+        #[arg(
+            long = "rate-limit",
+            value_name = "REQUESTS",
+            default_value_t = 300,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        rate_limit: u32,
+        /// Require a bearer token, read from this file (falls back to ECSCOPE_SERVE_TOKEN)
+        #[arg(long = "token-file", value_name = "PATH", group = "auth")]
+        token_file: Option<PathBuf>,
+        /// Require HTTP basic auth, against this htpasswd file (with bcrypt entries)
+        #[arg(long = "htpasswd", value_name = "PATH", group = "auth")]
+        htpasswd_file: Option<PathBuf>,
+        /// Trust a reverse proxy to authenticate requests, and to pass on the user in this header
+        #[arg(long = "trusted-user-header", value_name = "HEADER", group = "auth")]
+        trusted_user_header: Option<String>,
+        /// Allow cross-origin requests from this origin (can be repeated)
+        #[arg(long = "cors-origin", value_name = "ORIGIN", value_parser = validate_cors_origin)]
+        cors_origins: Vec<HeaderValue>,
+        /// Leave ARNs out of responses (falls back to ECSCOPE_REDACT)
+        #[arg(long = "redact")]
+        redact: bool,
+    },
+    /// Send changes in deployments to webhooks, as they happen
+    Notify {
+        /// Profile to use (falls back to ECSCOPE_PROFILE)
+        #[arg(value_name = "PROFILE")]
+        profile_name: Option<String>,
+        /// Profile file to use (instead of looking up a profile by name)
+        #[arg(
+            long = "profile-file",
+            value_name = "PATH",
+            conflicts_with = "profile_name"
+        )]
+        profile_file: Option<PathBuf>,
+        /// Filtration query for service names
+        #[arg(short = 's', long = "service-filter", value_name = "REGEX", value_parser=validate_filter_query)]
+        service_name_filter: Option<Regex>,
+        /// Filtration query for cluster keys
+        #[arg(short = 'k', long = "key-filter", value_name = "REGEX", value_parser=validate_filter_query)]
+        key_filter: Option<Regex>,
+        /// Only include services with this tag (can be repeated; all must match)
+        #[arg(short = 't', long = "tag", value_name = "KEY=VALUE")]
+        tag_filters: Vec<TagFilter>,
+        /// File to read webhooks from (instead of notify.toml in the config directory)
+        #[arg(long = "notify-file", value_name = "PATH")]
+        notify_file: Option<PathBuf>,
+        /// How often deployments are polled
+        #[arg(
+            short = 'i',
+            long = "interval",
+            value_name = "SECONDS",
+            default_value_t = 30,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        interval: u64,
     },
 }
 
@@ -85,6 +232,8 @@ pub enum ProfilesCommand {
     },
     /// List profiles
     List,
+    /// Print the JSON Schema for profile files
+    Schema,
 }
 
 impl std::fmt::Display for Args {
@@ -92,32 +241,59 @@ impl std::fmt::Display for Args {
         let output = match &self.command {
             EcscopeCommand::Deployments {
                 profile_name,
+                profile_file,
                 service_name_filter,
                 key_filter,
+                tag_filters,
+                where_filter,
                 state,
                 format,
+                output,
+                post_to,
                 mode,
                 web_skip_opening,
+                web_cache_ttl,
+                web_cache_max_stale,
             } => format!(
                 r#"
 command:                     List Deployments
 profile:                     {}
+profile file:                {}
 service name filter:         {}
 key filter:                  {}
+tag filters:                 {}
+where:                       {}
 state:                       {}
 format:                      {}
+output file:                 {}
+post to:                     {}
 mode:                        {}
 skip opening web results:    {}
+web cache ttl:               {}s
+web cache max stale:         {}s
 "#,
-                profile_name,
+                profile_name.as_deref().unwrap_or(NOT_PROVIDED),
+                profile_file.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
                 service_name_filter
                     .as_ref()
                     .map_or(NOT_PROVIDED, |s| s.as_str()),
                 key_filter.as_ref().map_or(NOT_PROVIDED, |r| r.as_str()),
+                display_tag_filters(tag_filters),
+                where_filter.as_deref().unwrap_or(NOT_PROVIDED),
                 state.as_ref().map_or(NOT_PROVIDED, |s| s.as_ref()),
                 format,
+                output.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
+                post_to
+                    .as_ref()
+                    .map_or(NOT_PROVIDED.to_string(), display_webhook_url),
                 mode,
                 web_skip_opening,
+                web_cache_ttl,
+                web_cache_max_stale,
             ),
             EcscopeCommand::Profiles { profiles_command } => match profiles_command {
                 ProfilesCommand::Add { name } => format!(
@@ -128,21 +304,36 @@ name:       {name}
                 ),
                 ProfilesCommand::List => r#"
 command:    List Profiles
+"#
+                .to_string(),
+                ProfilesCommand::Schema => r#"
+command:    Show Profile Schema
 "#
                 .to_string(),
             },
             EcscopeCommand::Monitor {
                 profile_name,
+                profile_file,
                 service_name_filter,
                 key_filter,
+                tag_filters,
+                where_filter,
+                fresh,
             } => format!(
                 r#"
 command:                Monitor resources
 profile:                {}
+profile file:           {}
 service name filter:    {}
 key filter:             {}
+tag filters:            {}
+where:                  {}
+fresh session:          {}
 "#,
-                profile_name,
+                profile_name.as_deref().unwrap_or(NOT_PROVIDED),
+                profile_file.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
                 service_name_filter
                     .as_ref()
                     .map(|r| r.to_string())
@@ -151,6 +342,90 @@ key filter:             {}
                     .as_ref()
                     .map(|r| r.to_string())
                     .unwrap_or(NOT_PROVIDED.to_string()),
+                display_tag_filters(tag_filters),
+                where_filter.as_deref().unwrap_or(NOT_PROVIDED),
+                fresh,
+            ),
+            EcscopeCommand::Serve {
+                profile_names,
+                bind,
+                port,
+                cache_ttl,
+                cache_max_stale,
+                min_refresh_interval,
+                rate_limit,
+                token_file,
+                htpasswd_file,
+                trusted_user_header,
+                cors_origins,
+                redact,
+            } => format!(
+                r#"
+command:                Serve deployments
+profiles:               {}
+bind address:           {bind}
+port:                   {port}
+cache ttl:              {cache_ttl}s
+cache max stale:        {cache_max_stale}s
+min refresh interval:   {min_refresh_interval}s
+rate limit:             {rate_limit} requests/minute
+token file:             {}
+htpasswd file:          {}
+trusted user header:    {}
+cors origins:           {}
+redact:                 {redact}
+"#,
+                profile_names.join(", "),
+                token_file.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
+                htpasswd_file
+                    .as_ref()
+                    .map_or(NOT_PROVIDED.to_string(), |p| p
+                        .to_string_lossy()
+                        .to_string()),
+                trusted_user_header.as_deref().unwrap_or(NOT_PROVIDED),
+                if cors_origins.is_empty() {
+                    NOT_PROVIDED.to_string()
+                } else {
+                    cors_origins
+                        .iter()
+                        .map(|o| o.to_str().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            ),
+            EcscopeCommand::Notify {
+                profile_name,
+                profile_file,
+                service_name_filter,
+                key_filter,
+                tag_filters,
+                notify_file,
+                interval,
+            } => format!(
+                r#"
+command:                Notify about deployments
+profile:                {}
+profile file:           {}
+service name filter:    {}
+key filter:             {}
+tag filters:            {}
+notify file:            {}
+interval:               {interval}s
+"#,
+                profile_name.as_deref().unwrap_or(NOT_PROVIDED),
+                profile_file.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
+                service_name_filter
+                    .as_ref()
+                    .map_or(NOT_PROVIDED, |s| s.as_str()),
+                key_filter.as_ref().map_or(NOT_PROVIDED, |r| r.as_str()),
+                display_tag_filters(tag_filters),
+                notify_file.as_ref().map_or(NOT_PROVIDED.to_string(), |p| p
+                    .to_string_lossy()
+                    .to_string()),
             ),
         };
 
@@ -161,3 +436,63 @@ key filter:             {}
 fn validate_filter_query(value: &str) -> Result<Regex, String> {
     Regex::new(value).map_err(|e| format!("query \"{value}\" is not valid regex: {e}"))
 }
+
+fn validate_profile_name(value: &str) -> Result<String, String> {
+    // profile names end up in paths
+    Profile::try_from(value).map(|p| p.name().to_string())
+}
+
+fn validate_cors_origin(value: &str) -> Result<HeaderValue, String> {
+    if !(value.starts_with("http://") || value.starts_with("https://")) || value.ends_with('/') {
+        return Err(format!(
+            "origin \"{value}\" is not valid; it should look like https://tools.example.com"
+        ));
+    }
+
+    HeaderValue::from_str(value).map_err(|e| format!("origin \"{value}\" is not valid: {e}"))
+}
+
+fn validate_webhook_url(value: &str) -> Result<Url, String> {
+    let url = Url::parse(value).map_err(|e| format!("webhook URL is not valid: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("webhook URL is not valid; it should be an http(s) URL".to_string());
+    }
+
+    Ok(url)
+}
+
+fn validate_where_filter(value: &str) -> Result<String, String> {
+    // named filters can only be resolved once the profile has been read
+    if named_filter_reference(value).is_none() {
+        FilterExpr::parse(value).map_err(|e| format!("filter \"{value}\" is not valid: {e}"))?;
+    }
+
+    Ok(value.to_string())
+}
+
+fn validate_services_where_filter(value: &str) -> Result<String, String> {
+    if named_filter_reference(value).is_none() {
+        FilterExpr::parse(value)
+            .and_then(FilterExpr::supported_by::<ServiceDetails>)
+            .map_err(|e| format!("filter \"{value}\" is not valid: {e}"))?;
+    }
+
+    Ok(value.to_string())
+}
+
+// webhook URLs hold secrets, so only their origins are shown
+fn display_webhook_url(url: &Url) -> String {
+    format!("{}/<redacted>", url.origin().ascii_serialization())
+}
+
+fn display_tag_filters(tag_filters: &[TagFilter]) -> String {
+    if tag_filters.is_empty() {
+        return NOT_PROVIDED.to_string();
+    }
+
+    tag_filters
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,23 +1,25 @@
 use crate::config::ConfigSource;
-use aws_config::SdkConfig;
 use aws_config::profile::ProfileFileCredentialsProvider;
 use aws_config::sts::AssumeRoleProvider;
+use aws_config::{ConfigLoader, Region, SdkConfig};
 
-pub async fn get_sdk_config(config_source: &ConfigSource) -> SdkConfig {
+pub async fn get_sdk_config(config_source: &ConfigSource, region: Option<&str>) -> SdkConfig {
     match config_source {
         ConfigSource::AssumeRole { role_arn } => {
-            let provider = AssumeRoleProvider::builder(role_arn)
-                .session_name("escope-session")
-                .build()
-                .await;
-            aws_config::from_env()
+            let mut provider_builder =
+                AssumeRoleProvider::builder(role_arn).session_name("escope-session");
+            if let Some(r) = region {
+                provider_builder = provider_builder.region(Region::new(r.to_string()));
+            }
+            let provider = provider_builder.build().await;
+            with_region(aws_config::from_env(), region)
                 .credentials_provider(provider)
                 .load()
                 .await
         }
-        ConfigSource::Env => aws_config::load_from_env().await,
+        ConfigSource::Env => with_region(aws_config::from_env(), region).load().await,
         ConfigSource::Profile { name } => {
-            aws_config::from_env()
+            with_region(aws_config::from_env(), region)
                 .credentials_provider(
                     ProfileFileCredentialsProvider::builder()
                         .profile_name(name)
@@ -28,3 +30,10 @@ pub async fn get_sdk_config(config_source: &ConfigSource) -> SdkConfig {
         }
     }
 }
+
+fn with_region(loader: ConfigLoader, region: Option<&str>) -> ConfigLoader {
+    match region {
+        Some(r) => loader.region(Region::new(r.to_string())),
+        None => loader,
+    }
+}
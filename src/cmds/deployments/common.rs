@@ -0,0 +1,45 @@
+use crate::domain::DeploymentDetails;
+use aws_sdk_ecs::primitives::{DateTime, DateTimeFormat};
+
+/// What the web view's legend calls out about a deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Legend {
+    Failing,
+    BeingReplaced,
+    Upcoming,
+    Draining,
+}
+
+impl Legend {
+    /// Classifies a deployment the same way the web view colour-codes its row; deployments that
+    /// are done and healthy aren't called out.
+    pub(super) fn of(deployment: &DeploymentDetails) -> Option<Self> {
+        if deployment.failed_count > 0 {
+            Some(Legend::Failing)
+        } else if deployment.status == "ACTIVE" {
+            Some(Legend::BeingReplaced)
+        } else if deployment.running_count != deployment.desired_count {
+            Some(Legend::Upcoming)
+        } else if deployment.status == "DRAINING" {
+            Some(Legend::Draining)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Legend::Failing => "failing",
+            Legend::BeingReplaced => "being replaced",
+            Legend::Upcoming => "upcoming",
+            Legend::Draining => "draining",
+        }
+    }
+}
+
+/// Formats when results were fetched, to the second, eg. `2026-10-19T07:00:00Z`.
+pub(super) fn format_timestamp(time: DateTime) -> String {
+    DateTime::from_secs(time.secs())
+        .fmt(DateTimeFormat::DateTime)
+        .unwrap_or_else(|_| time.secs().to_string())
+}
@@ -0,0 +1,344 @@
+use super::common::{Legend, format_timestamp};
+use crate::domain::{DeploymentDetails, DeploymentError};
+use aws_sdk_ecs::primitives::DateTime;
+use std::fmt::Write;
+
+// the report is styled the same way as the web view, but has to work without a server
+const DEPS_CSS: &str = include_str!("../../server/deployments/client/priv/static/deps.css");
+const DEPS_CUSTOM_CSS: &str =
+    include_str!("../../server/deployments/client/priv/static/custom.css");
+
+// keep this up to date with num_custom_colors in the web view's utils.gleam
+const NUM_CUSTOM_COLORS: f64 = 45.0;
+
+/// Renders deployments, and the errors encountered while fetching them, as a self-contained HTML
+/// page.
+pub(super) fn render_html_report(
+    deployments: &[DeploymentDetails],
+    errors: &[DeploymentError],
+    profile_name: &str,
+    generated_at: DateTime,
+) -> String {
+    let profile_name = escape(profile_name);
+    let body = report_body(deployments, errors, &profile_name, generated_at);
+
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+    <title>ecscope: {profile_name}</title>
+    <style>
+{DEPS_CSS}
+    </style>
+    <style>
+{DEPS_CUSTOM_CSS}
+    </style>
+  </head>
+  <body style="background-color:#282828;">
+{body}
+  </body>
+</html>
+"#
+    )
+}
+
+fn report_body(
+    deployments: &[DeploymentDetails],
+    errors: &[DeploymentError],
+    profile_name: &str,
+    generated_at: DateTime,
+) -> String {
+    let generated_at = format_timestamp(generated_at);
+
+    let mut body = String::new();
+    body.push_str(r#"    <div class="w-2/3 mx-auto bg-[#282828] text-[#ebdbb2] mt-8">"#);
+    body.push_str("\n      <h1 class=\"text-3xl font-bold mb-4\">ecscope</h1>");
+    let _ = write!(
+        body,
+        r#"
+      <p class="mb-8">Deployments for profile <span class="font-semibold">{profile_name}</span>, as of {generated_at}</p>
+      <div id="deployment-results">"#
+    );
+
+    if deployments.is_empty() && errors.is_empty() {
+        body.push_str(
+            r#"
+        <div id="no-deployment-results">
+          <h2 class="text-xl font-bold mb-6 text-[#83a598]">No Deployments found</h2>
+        </div>"#,
+        );
+    }
+
+    if !deployments.is_empty() {
+        body.push_str(&deployments_div(deployments));
+    }
+
+    if !deployments.is_empty() && !errors.is_empty() {
+        body.push_str(
+            r#"
+        <hr class="border-y-2 my-10 border-[#504945]" id="results-divider" />"#,
+        );
+    }
+
+    if !errors.is_empty() {
+        body.push_str(&errors_div(errors));
+    }
+
+    body.push_str("\n      </div>\n    </div>");
+
+    body
+}
+
+fn deployments_div(deployments: &[DeploymentDetails]) -> String {
+    let mut div = String::from(
+        r#"
+        <div id="deployment-details">
+          <h2 class="text-xl font-bold mb-6 text-[#83a598]">Deployments</h2>
+          <div class="legend mb-4 p-4">
+            <p class="p-1 m-1 font-semibold">Legend: </p>
+            <p class="legend-upcoming py-1 px-2 m-1 font-semibold">upcoming</p>
+            <p class="legend-active py-1 px-2 m-1 font-semibold">being replaced</p>
+            <p class="legend-draining py-1 px-2 m-1 font-semibold">draining</p>
+            <p class="legend-failing py-1 px-2 m-1 font-semibold">failing</p>
+          </div>
+          <table class="table-auto w-full px-4 py-2" id="deployment-details-table">
+            <thead>
+              <tr><th>Service</th><th>Keys</th><th>Status</th><th>Running</th><th>Desired</th><th>Pending</th><th>Failed</th></tr>
+            </thead>
+            <tbody>"#,
+    );
+
+    for deployment in deployments {
+        let (row_class, service_class) = match row_class(deployment) {
+            Some(class) => (class, "font-semibold".to_string()),
+            None => (
+                "",
+                color_class(&deployment.service_name).map_or("font-semibold".to_string(), |c| {
+                    format!("font-semibold {c}")
+                }),
+            ),
+        };
+        let _ = write!(
+            div,
+            r#"
+              <tr class="{row_class}"><td class="{service_class}">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            escape(&deployment.service_label()),
+            escape(&deployment.keys),
+            escape(&deployment.status),
+            deployment.running_count,
+            deployment.desired_count,
+            deployment.pending_count,
+            deployment.failed_count,
+        );
+    }
+
+    div.push_str("\n            </tbody>\n          </table>\n        </div>");
+
+    div
+}
+
+fn errors_div(errors: &[DeploymentError]) -> String {
+    let mut div = String::from(
+        r#"
+        <div id="deployment-errors">
+          <h2 class="text-xl font-bold mb-6 text-[#fb4934]">Errors</h2>
+          <table class="table-auto w-full px-4 py-2" id="deployment-errors-table">
+            <thead>
+              <tr><th>Service</th><th>Keys</th><th>Error</th></tr>
+            </thead>
+            <tbody>"#,
+    );
+
+    for error in errors {
+        let service_class = color_class(&error.service_name)
+            .map_or("font-semibold".to_string(), |c| {
+                format!("font-semibold {c}")
+            });
+        let lines = error
+            .error
+            .split('\n')
+            .map(|line| format!("<div>{}</div>", escape(line)))
+            .collect::<String>();
+        let _ = write!(
+            div,
+            r#"
+              <tr class="border-y-2 border-[#504945]"><td class="{service_class}">{}</td><td>{}</td><td>{lines}</td></tr>"#,
+            escape(&error.service_label()),
+            escape(&error.keys),
+        );
+    }
+
+    div.push_str("\n            </tbody>\n          </table>\n        </div>");
+
+    div
+}
+
+/// The class the web view colour-codes a deployment's row with, if any.
+fn row_class(deployment: &DeploymentDetails) -> Option<&'static str> {
+    Legend::of(deployment).map(|legend| match legend {
+        Legend::Failing => "row-failing",
+        Legend::BeingReplaced => "row-being-replaced",
+        Legend::Upcoming => "row-upcoming",
+        Legend::Draining => "row-draining",
+    })
+}
+
+/// The class the web view colours a service's name with. The web view hashes names with
+/// JavaScript's numbers, so this does the same with floats, for names to get the same colours in
+/// both.
+fn color_class(service_name: &str) -> Option<String> {
+    let hash = service_name
+        .chars()
+        .fold(0.0_f64, |hash, c| hash * 31.0 + f64::from(u32::from(c)));
+    if !hash.is_finite() {
+        return None;
+    }
+
+    Some(format!("text-color{}", hash % NUM_CUSTOM_COLORS))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn deployment(service_name: &str, status: &str, counts: [i32; 4]) -> DeploymentDetails {
+        let [running_count, desired_count, pending_count, failed_count] = counts;
+        DeploymentDetails {
+            service_name: service_name.to_string(),
+            keys: "prod".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            deployment_id: "ecs-svc/1".to_string(),
+            status: status.to_string(),
+            running_count,
+            desired_count,
+            pending_count,
+            failed_count,
+            service_alias: None,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn report_shows_deployments_and_errors() {
+        // GIVEN
+        let deployments = vec![
+            deployment("auth-api", "PRIMARY", [2, 2, 0, 0]),
+            deployment("payments-api", "PRIMARY", [1, 3, 2, 0]),
+            deployment("payments-api", "ACTIVE", [3, 3, 0, 0]),
+            deployment("search-api", "PRIMARY", [0, 2, 0, 4]),
+        ];
+        let errors = vec![DeploymentError {
+            service_name: "billing-api".to_string(),
+            error: "ServiceNotFoundException\nservice <billing-api> not found".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            keys: "prod".to_string(),
+            service_alias: None,
+        }];
+
+        // WHEN
+        let body = report_body(
+            &deployments,
+            &errors,
+            "prod",
+            DateTime::from_secs(1_792_393_200),
+        );
+
+        // THEN
+        assert_snapshot!(body, @r#"
+            <div class="w-2/3 mx-auto bg-[#282828] text-[#ebdbb2] mt-8">
+              <h1 class="text-3xl font-bold mb-4">ecscope</h1>
+              <p class="mb-8">Deployments for profile <span class="font-semibold">prod</span>, as of 2026-10-19T07:00:00Z</p>
+              <div id="deployment-results">
+                <div id="deployment-details">
+                  <h2 class="text-xl font-bold mb-6 text-[#83a598]">Deployments</h2>
+                  <div class="legend mb-4 p-4">
+                    <p class="p-1 m-1 font-semibold">Legend: </p>
+                    <p class="legend-upcoming py-1 px-2 m-1 font-semibold">upcoming</p>
+                    <p class="legend-active py-1 px-2 m-1 font-semibold">being replaced</p>
+                    <p class="legend-draining py-1 px-2 m-1 font-semibold">draining</p>
+                    <p class="legend-failing py-1 px-2 m-1 font-semibold">failing</p>
+                  </div>
+                  <table class="table-auto w-full px-4 py-2" id="deployment-details-table">
+                    <thead>
+                      <tr><th>Service</th><th>Keys</th><th>Status</th><th>Running</th><th>Desired</th><th>Pending</th><th>Failed</th></tr>
+                    </thead>
+                    <tbody>
+                      <tr class=""><td class="font-semibold text-color13">auth-api</td><td>prod</td><td>PRIMARY</td><td>2</td><td>2</td><td>0</td><td>0</td></tr>
+                      <tr class="row-upcoming"><td class="font-semibold">payments-api</td><td>prod</td><td>PRIMARY</td><td>1</td><td>3</td><td>2</td><td>0</td></tr>
+                      <tr class="row-being-replaced"><td class="font-semibold">payments-api</td><td>prod</td><td>ACTIVE</td><td>3</td><td>3</td><td>0</td><td>0</td></tr>
+                      <tr class="row-failing"><td class="font-semibold">search-api</td><td>prod</td><td>PRIMARY</td><td>0</td><td>2</td><td>0</td><td>4</td></tr>
+                    </tbody>
+                  </table>
+                </div>
+                <hr class="border-y-2 my-10 border-[#504945]" id="results-divider" />
+                <div id="deployment-errors">
+                  <h2 class="text-xl font-bold mb-6 text-[#fb4934]">Errors</h2>
+                  <table class="table-auto w-full px-4 py-2" id="deployment-errors-table">
+                    <thead>
+                      <tr><th>Service</th><th>Keys</th><th>Error</th></tr>
+                    </thead>
+                    <tbody>
+                      <tr class="border-y-2 border-[#504945]"><td class="font-semibold text-color9">billing-api</td><td>prod</td><td><div>ServiceNotFoundException</div><div>service &lt;billing-api&gt; not found</div></td></tr>
+                    </tbody>
+                  </table>
+                </div>
+              </div>
+            </div>
+        "#);
+    }
+
+    #[test]
+    fn report_is_self_contained() {
+        // GIVEN
+        let deployments = vec![deployment("auth-api", "PRIMARY", [2, 2, 0, 0])];
+
+        // WHEN
+        let report = render_html_report(&deployments, &[], "prod", DateTime::from_secs(0));
+
+        // THEN
+        assert!(report.contains(".row-failing {"));
+        assert!(report.contains(".text-color0 {"));
+        assert!(!report.contains("<link"));
+        assert!(!report.contains("<script"));
+    }
+
+    #[test]
+    fn service_names_get_the_same_colours_as_in_the_web_view() {
+        // GIVEN
+        // as computed by the web view's color_for_string
+        let cases = [
+            ("auth-api", "text-color13"),
+            ("a-service-with-a-rather-long-name", "text-color3"),
+        ];
+
+        for (service_name, expected) in cases {
+            // WHEN
+            let class = color_class(service_name);
+
+            // THEN
+            assert_eq!(class.as_deref(), Some(expected), "service: {service_name}");
+        }
+    }
+}
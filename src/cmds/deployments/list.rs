@@ -1,9 +1,19 @@
+use super::html::render_html_report;
+use super::markdown::render_markdown;
+use super::slack::{post_to_webhook, slack_message};
 use crate::common::{DeploymentState, OutputFormat};
-use crate::config::{ClusterConfig, ConfigSource};
+use crate::config::{ClientKey, ClusterConfig};
+use crate::domain::FilterExpr;
 use crate::service::get_deployments;
+use crate::webhook::PostToWebhookError;
 use aws_sdk_ecs::Client as ECSClient;
+use aws_sdk_ecs::primitives::DateTime;
+use reqwest::Url;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ListDeploymentsError {
@@ -15,40 +25,87 @@ pub enum ListDeploymentsError {
     FlushResultsToCSVWriter(#[from] std::io::Error),
     #[error("something unexpected happended: {0}")]
     Unexpected(String),
+    #[error("couldn't write results to {0}: {1}")]
+    CouldntWriteToFile(PathBuf, std::io::Error),
+    #[error("results can only be posted to a webhook with --format slack")]
+    PostingNeedsSlackFormat,
+    #[error("results can only be posted to a webhook with --mode default")]
+    PostingNeedsDefaultMode,
+    #[error(transparent)]
+    PostToWebhook(#[from] PostToWebhookError),
+}
+
+/// How, and where to, results are output.
+#[derive(Debug)]
+pub struct ListOutput {
+    pub format: OutputFormat,
+    /// File to write results to, instead of stdout
+    pub file: Option<PathBuf>,
+    /// Slack incoming webhook to post results to, instead of writing them out
+    pub post_to: Option<Url>,
 }
 
 pub async fn list_deployments(
     clusters: Vec<ClusterConfig>,
-    clients_map: Arc<HashMap<ConfigSource, ECSClient>>,
+    clients_map: Arc<HashMap<ClientKey, ECSClient>>,
     state: Option<DeploymentState>,
-    format: OutputFormat,
+    filter: Option<FilterExpr>,
+    output: ListOutput,
+    profile_name: &str,
 ) -> Result<(), ListDeploymentsError> {
     if clusters.is_empty() {
         return Ok(());
     }
 
-    let (deployments, errors) = get_deployments(clusters, clients_map, state)
+    let (mut deployments, errors) = get_deployments(clusters, clients_map, state)
         .await
         .map_err(ListDeploymentsError::Unexpected)?;
 
-    if !deployments.is_empty() {
-        match format {
-            OutputFormat::Delimited => {
-                let mut wtr = csv::Writer::from_writer(std::io::stdout());
-                for dep in deployments {
-                    wtr.serialize(dep)?;
-                }
-                wtr.flush()?;
+    if let Some(f) = &filter {
+        deployments.retain(|d| f.matches(d));
+    }
+
+    let generated_at = DateTime::from(SystemTime::now());
+    let mut results = Vec::new();
+    match output.format {
+        // these are for sharing, so they're rendered even if there's nothing to show
+        OutputFormat::Html => results.extend_from_slice(
+            render_html_report(&deployments, &errors, profile_name, generated_at).as_bytes(),
+        ),
+        OutputFormat::Markdown => results.extend_from_slice(
+            render_markdown(&deployments, &errors, profile_name, generated_at).as_bytes(),
+        ),
+        OutputFormat::Slack => {
+            let message = slack_message(&deployments, &errors, profile_name, generated_at);
+            match &output.post_to {
+                Some(url) => post_to_webhook(url, &message).await?,
+                None => writeln!(results, "{}", serde_json::to_string_pretty(&message)?)?,
+            }
+        }
+        _ if deployments.is_empty() => {}
+        OutputFormat::Delimited => {
+            let mut wtr = csv::Writer::from_writer(&mut results);
+            for dep in deployments {
+                wtr.serialize(dep)?;
             }
-            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&deployments)?),
-            OutputFormat::Plain => {
-                for dep in deployments {
-                    println!("{dep}");
-                }
+            wtr.flush()?;
+        }
+        OutputFormat::Json => {
+            writeln!(results, "{}", serde_json::to_string_pretty(&deployments)?)?;
+        }
+        OutputFormat::Plain => {
+            for dep in deployments {
+                writeln!(results, "{dep}")?;
             }
         }
     }
 
+    match &output.file {
+        Some(path) => std::fs::write(path, &results)
+            .map_err(|e| ListDeploymentsError::CouldntWriteToFile(path.clone(), e))?,
+        None => std::io::stdout().write_all(&results)?,
+    }
+
     if !errors.is_empty() {
         eprintln!(
             r#"
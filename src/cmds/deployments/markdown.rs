@@ -0,0 +1,176 @@
+use super::common::{Legend, format_timestamp};
+use crate::domain::{DeploymentDetails, DeploymentError};
+use aws_sdk_ecs::primitives::DateTime;
+use std::fmt::Write;
+
+/// Renders deployments, and the errors encountered while fetching them, as GitHub-flavoured
+/// markdown tables.
+pub(super) fn render_markdown(
+    deployments: &[DeploymentDetails],
+    errors: &[DeploymentError],
+    profile_name: &str,
+    generated_at: DateTime,
+) -> String {
+    let mut markdown = format!(
+        "Deployments for profile `{}`, as of {}\n",
+        profile_name.replace('`', ""),
+        format_timestamp(generated_at)
+    );
+
+    if deployments.is_empty() && errors.is_empty() {
+        markdown.push_str("\nNo deployments found\n");
+    }
+
+    if !deployments.is_empty() {
+        markdown.push_str(
+            r#"
+### Deployments
+
+| Service | Keys | Status | Running | Desired | Pending | Failed | Notes |
+|---------|------|--------|--------:|--------:|--------:|-------:|-------|
+"#,
+        );
+        for deployment in deployments {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                escape(&deployment.service_label()),
+                escape(&deployment.keys),
+                escape(&deployment.status),
+                deployment.running_count,
+                deployment.desired_count,
+                deployment.pending_count,
+                deployment.failed_count,
+                Legend::of(deployment).map_or("", Legend::label),
+            );
+        }
+    }
+
+    if !errors.is_empty() {
+        markdown.push_str(
+            r#"
+### Errors
+
+| Service | Keys | Error |
+|---------|------|-------|
+"#,
+        );
+        for error in errors {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} |",
+                escape(&error.service_label()),
+                escape(&error.keys),
+                escape(&error.error),
+            );
+        }
+    }
+
+    markdown
+}
+
+/// Keeps text from breaking out of a table cell.
+fn escape(text: &str) -> String {
+    text.trim()
+        .replace('|', r"\|")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn deployment(service_name: &str, status: &str, counts: [i32; 4]) -> DeploymentDetails {
+        let [running_count, desired_count, pending_count, failed_count] = counts;
+        DeploymentDetails {
+            service_name: service_name.to_string(),
+            keys: "prod".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            deployment_id: "ecs-svc/1".to_string(),
+            status: status.to_string(),
+            running_count,
+            desired_count,
+            pending_count,
+            failed_count,
+            service_alias: None,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn markdown_shows_deployments_and_errors_with_aliases() {
+        // GIVEN
+        let deployments = vec![
+            deployment("auth-api", "PRIMARY", [2, 2, 0, 0]),
+            DeploymentDetails {
+                service_alias: Some("payments".to_string()),
+                ..deployment("payments-api", "PRIMARY", [1, 3, 2, 0])
+            },
+            deployment("payments-api", "ACTIVE", [3, 3, 0, 0]),
+            deployment("search-api", "PRIMARY", [0, 2, 0, 4]),
+        ];
+        let errors = vec![DeploymentError {
+            service_name: "billing-api".to_string(),
+            error: "ServiceNotFoundException\nservice <billing-api> not found | gone".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            keys: "prod".to_string(),
+            service_alias: Some("billing".to_string()),
+        }];
+
+        // WHEN
+        let markdown = render_markdown(
+            &deployments,
+            &errors,
+            "prod",
+            DateTime::from_secs(1_792_393_200),
+        );
+
+        // THEN
+        assert_snapshot!(markdown, @r"
+        Deployments for profile `prod`, as of 2026-10-19T07:00:00Z
+
+        ### Deployments
+
+        | Service | Keys | Status | Running | Desired | Pending | Failed | Notes |
+        |---------|------|--------|--------:|--------:|--------:|-------:|-------|
+        | auth-api | prod | PRIMARY | 2 | 2 | 0 | 0 |  |
+        | payments (payments-api) | prod | PRIMARY | 1 | 3 | 2 | 0 | upcoming |
+        | payments-api | prod | ACTIVE | 3 | 3 | 0 | 0 | being replaced |
+        | search-api | prod | PRIMARY | 0 | 2 | 0 | 4 | failing |
+
+        ### Errors
+
+        | Service | Keys | Error |
+        |---------|------|-------|
+        | billing (billing-api) | prod | ServiceNotFoundException<br>service &lt;billing-api&gt; not found \| gone |
+        ");
+    }
+
+    #[test]
+    fn markdown_says_when_nothing_was_found() {
+        // GIVEN
+        let deployments: Vec<DeploymentDetails> = vec![];
+        let errors: Vec<DeploymentError> = vec![];
+
+        // WHEN
+        let markdown = render_markdown(
+            &deployments,
+            &errors,
+            "prod",
+            DateTime::from_secs(1_792_393_200),
+        );
+
+        // THEN
+        assert_snapshot!(markdown, @r"
+        Deployments for profile `prod`, as of 2026-10-19T07:00:00Z
+
+        No deployments found
+        ");
+    }
+}
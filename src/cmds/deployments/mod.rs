@@ -1,3 +1,7 @@
+mod common;
+mod html;
 mod list;
+mod markdown;
+mod slack;
 
 pub use list::*;
@@ -0,0 +1,456 @@
+use super::common::{Legend, format_timestamp};
+use crate::domain::{DeploymentDetails, DeploymentError};
+use crate::webhook::{PostToWebhookError, send_to_webhook, webhook_client};
+use aws_sdk_ecs::primitives::DateTime;
+use reqwest::Url;
+
+// https://docs.slack.dev/reference/block-kit/blocks
+const MAX_BLOCKS: usize = 50;
+const MAX_SECTION_LEN: usize = 3000;
+const MAX_HEADER_LEN: usize = 150;
+const MAX_ERROR_LEN: usize = 300;
+
+/// A message for a Slack incoming webhook, laid out using Block Kit.
+#[derive(Debug, serde::Serialize)]
+pub(super) struct SlackMessage {
+    /// Shown in notifications, and by clients that can't show blocks
+    text: String,
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Block {
+    Header { text: Text },
+    Section { text: Text },
+    Context { elements: Vec<Text> },
+    Divider,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Text {
+    PlainText { text: String },
+    Mrkdwn { text: String },
+}
+
+/// Lays out deployments, and the errors encountered while fetching them, as a Slack message.
+pub(super) fn slack_message(
+    deployments: &[DeploymentDetails],
+    errors: &[DeploymentError],
+    profile_name: &str,
+    generated_at: DateTime,
+) -> SlackMessage {
+    let failing = deployments
+        .iter()
+        .filter(|d| Legend::of(d) == Some(Legend::Failing))
+        .count();
+    let summary = format!(
+        "{}, {} failing, {}",
+        counted(deployments.len(), "deployment"),
+        failing,
+        counted(errors.len(), "error"),
+    );
+
+    let mut blocks = vec![
+        Block::Header {
+            text: Text::PlainText {
+                text: truncated(&format!("Deployments: {profile_name}"), MAX_HEADER_LEN),
+            },
+        },
+        Block::Context {
+            elements: vec![Text::Mrkdwn {
+                text: format!("As of {} · {summary}", format_timestamp(generated_at)),
+            }],
+        },
+    ];
+
+    if deployments.is_empty() && errors.is_empty() {
+        blocks.push(mrkdwn_section("No deployments found".to_string()));
+    }
+
+    blocks.extend(
+        sections(deployments.iter().map(deployment_line))
+            .into_iter()
+            .map(mrkdwn_section),
+    );
+
+    if !errors.is_empty() {
+        blocks.push(Block::Divider);
+        blocks.push(mrkdwn_section("*Errors*".to_string()));
+        blocks.extend(
+            sections(errors.iter().map(error_line))
+                .into_iter()
+                .map(mrkdwn_section),
+        );
+    }
+
+    if blocks.len() > MAX_BLOCKS {
+        blocks.truncate(MAX_BLOCKS - 1);
+        blocks.push(Block::Context {
+            elements: vec![Text::Mrkdwn {
+                text: "Some results were left out, as they don't fit in one message".to_string(),
+            }],
+        });
+    }
+
+    // unlike the header's plain text, this is formatted as mrkdwn
+    SlackMessage {
+        text: format!(
+            "Deployments for profile {}: {summary}",
+            escape(profile_name)
+        ),
+        blocks,
+    }
+}
+
+/// Sends a message to a Slack incoming webhook.
+pub(super) async fn post_to_webhook(
+    url: &Url,
+    message: &SlackMessage,
+) -> Result<(), PostToWebhookError> {
+    let client = webhook_client()?;
+
+    send_to_webhook(client.post(url.clone()).json(message)).await
+}
+
+fn deployment_line(deployment: &DeploymentDetails) -> String {
+    let legend = Legend::of(deployment);
+    let emoji = match legend {
+        Some(Legend::Failing) => ":red_circle:",
+        Some(Legend::BeingReplaced) => ":large_orange_circle:",
+        Some(Legend::Upcoming) => ":large_blue_circle:",
+        Some(Legend::Draining) => ":white_circle:",
+        None => ":large_green_circle:",
+    };
+
+    let mut line = format!(
+        "{emoji} *{}* ({}) · {} · {}/{} running, {} pending, {} failed",
+        escape(&deployment.service_label()),
+        escape(&deployment.keys),
+        escape(&deployment.status),
+        deployment.running_count,
+        deployment.desired_count,
+        deployment.pending_count,
+        deployment.failed_count,
+    );
+    if let Some(legend) = legend {
+        line.push_str(&format!(" · _{}_", legend.label()));
+    }
+
+    line
+}
+
+fn error_line(error: &DeploymentError) -> String {
+    let message = error.error.trim().lines().next().unwrap_or_default();
+
+    format!(
+        ":warning: *{}* ({}): {}",
+        escape(&error.service_label()),
+        escape(&error.keys),
+        escape(&truncated(message, MAX_ERROR_LEN)),
+    )
+}
+
+/// Groups lines into as few sections as Slack allows.
+fn sections(lines: impl Iterator<Item = String>) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + 1 + line.len() > MAX_SECTION_LEN {
+            sections.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+fn mrkdwn_section(text: String) -> Block {
+    Block::Section {
+        text: Text::Mrkdwn { text },
+    }
+}
+
+fn counted(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        n => format!("{n} {noun}s"),
+    }
+}
+
+fn truncated(text: &str, max_len: usize) -> String {
+    if text.chars().count() <= max_len {
+        return text.to_string();
+    }
+
+    let mut truncated = text.chars().take(max_len - 1).collect::<String>();
+    truncated.push('…');
+
+    truncated
+}
+
+// https://docs.slack.dev/messaging/formatting-message-text#escaping
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use insta::assert_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    fn deployment(service_name: &str, status: &str, counts: [i32; 4]) -> DeploymentDetails {
+        let [running_count, desired_count, pending_count, failed_count] = counts;
+        DeploymentDetails {
+            service_name: service_name.to_string(),
+            keys: "prod".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            deployment_id: "ecs-svc/1".to_string(),
+            status: status.to_string(),
+            running_count,
+            desired_count,
+            pending_count,
+            failed_count,
+            service_alias: None,
+        }
+    }
+
+    /// Serves a stand-in for a webhook on a local port, which responds with `status` and `body`,
+    /// and keeps the last message it received.
+    async fn webhook_stand_in(
+        status: StatusCode,
+        body: &'static str,
+    ) -> (Url, Arc<Mutex<Option<String>>>) {
+        let received = Arc::new(Mutex::new(None));
+        let router = Router::new().route(
+            "/services/T000/B000/XXXX",
+            post({
+                let received = Arc::clone(&received);
+                move |message: String| async move {
+                    *received.lock().expect("lock should've been acquired") = Some(message);
+                    (status, body)
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should've been bound");
+        let address = listener
+            .local_addr()
+            .expect("listener should've had an address");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let url = Url::parse(&format!("http://{address}/services/T000/B000/XXXX"))
+            .expect("url should've been valid");
+
+        (url, received)
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn slack_message_shows_deployments_and_errors() {
+        // GIVEN
+        let deployments = vec![
+            deployment("auth-api", "PRIMARY", [2, 2, 0, 0]),
+            deployment("payments-api", "PRIMARY", [1, 3, 2, 0]),
+            deployment("search-api", "PRIMARY", [0, 2, 0, 4]),
+        ];
+        let errors = vec![DeploymentError {
+            service_name: "billing-api".to_string(),
+            error: "ServiceNotFoundException\nservice <billing-api> not found".to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            keys: "prod".to_string(),
+            service_alias: None,
+        }];
+
+        // WHEN
+        let message = slack_message(
+            &deployments,
+            &errors,
+            "prod",
+            DateTime::from_secs(1_792_393_200),
+        );
+
+        // THEN
+        let json =
+            serde_json::to_string_pretty(&message).expect("message should've been serialized");
+        assert_snapshot!(json, @r#"
+        {
+          "text": "Deployments for profile prod: 3 deployments, 1 failing, 1 error",
+          "blocks": [
+            {
+              "type": "header",
+              "text": {
+                "type": "plain_text",
+                "text": "Deployments: prod"
+              }
+            },
+            {
+              "type": "context",
+              "elements": [
+                {
+                  "type": "mrkdwn",
+                  "text": "As of 2026-10-19T07:00:00Z · 3 deployments, 1 failing, 1 error"
+                }
+              ]
+            },
+            {
+              "type": "section",
+              "text": {
+                "type": "mrkdwn",
+                "text": ":large_green_circle: *auth-api* (prod) · PRIMARY · 2/2 running, 0 pending, 0 failed\n:large_blue_circle: *payments-api* (prod) · PRIMARY · 1/3 running, 2 pending, 0 failed · _upcoming_\n:red_circle: *search-api* (prod) · PRIMARY · 0/2 running, 0 pending, 4 failed · _failing_"
+              }
+            },
+            {
+              "type": "divider"
+            },
+            {
+              "type": "section",
+              "text": {
+                "type": "mrkdwn",
+                "text": "*Errors*"
+              }
+            },
+            {
+              "type": "section",
+              "text": {
+                "type": "mrkdwn",
+                "text": ":warning: *billing-api* (prod): ServiceNotFoundException"
+              }
+            }
+          ]
+        }
+        "#);
+    }
+
+    #[test]
+    fn long_results_are_kept_within_slacks_limits() {
+        // GIVEN
+        let deployments = (0..2000)
+            .map(|i| deployment(&format!("service-{i:04}"), "PRIMARY", [1, 1, 0, 0]))
+            .collect::<Vec<_>>();
+
+        // WHEN
+        let message = slack_message(&deployments, &[], "prod", DateTime::from_secs(0));
+
+        // THEN
+        assert_eq!(message.blocks.len(), MAX_BLOCKS);
+        for block in &message.blocks {
+            if let Block::Section {
+                text: Text::Mrkdwn { text },
+            } = block
+            {
+                assert!(text.len() <= MAX_SECTION_LEN);
+            }
+        }
+        assert!(matches!(
+            message.blocks.last(),
+            Some(Block::Context { elements }) if matches!(&elements[..], [Text::Mrkdwn { text }] if text.starts_with("Some results were left out"))
+        ));
+    }
+
+    #[test]
+    fn profile_names_are_escaped_where_formatted() {
+        // GIVEN
+        let profile_name = "prod <!channel> & co";
+
+        // WHEN
+        let message = slack_message(&[], &[], profile_name, DateTime::from_secs(0));
+
+        // THEN
+        assert_eq!(
+            message.text,
+            "Deployments for profile prod &lt;!channel&gt; &amp; co: 0 deployments, 0 failing, 0 errors"
+        );
+        assert!(matches!(
+            message.blocks.first(),
+            Some(Block::Header { text: Text::PlainText { text } }) if text == "Deployments: prod <!channel> & co"
+        ));
+    }
+
+    #[tokio::test]
+    async fn messages_are_posted_to_the_webhook() {
+        // GIVEN
+        let (url, received) = webhook_stand_in(StatusCode::OK, "ok").await;
+        let message = slack_message(
+            &[deployment("auth-api", "PRIMARY", [2, 2, 0, 0])],
+            &[],
+            "prod",
+            DateTime::from_secs(1_792_393_200),
+        );
+
+        // WHEN
+        let result = post_to_webhook(&url, &message).await;
+
+        // THEN
+        assert!(result.is_ok());
+        let received = received
+            .lock()
+            .expect("lock should've been acquired")
+            .clone()
+            .expect("webhook should've received a message");
+        assert_eq!(
+            received,
+            serde_json::to_string(&message).expect("message should've been serialized")
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn messages_rejected_by_the_webhook_are_reported() {
+        // GIVEN
+        let (url, _) = webhook_stand_in(StatusCode::BAD_REQUEST, "invalid_payload").await;
+        let message = slack_message(&[], &[], "prod", DateTime::from_secs(0));
+
+        // WHEN
+        let result = post_to_webhook(&url, &message).await;
+
+        // THEN
+        let error = result.expect_err("posting should've failed");
+        assert_snapshot!(error, @"webhook responded with 400 Bad Request: invalid_payload");
+    }
+
+    #[tokio::test]
+    async fn webhook_urls_are_left_out_of_errors() {
+        // GIVEN
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should've been bound");
+        let address = listener
+            .local_addr()
+            .expect("listener should've had an address");
+        drop(listener);
+        let url = Url::parse(&format!("http://{address}/services/T000/B000/XXXX"))
+            .expect("url should've been valid");
+        let message = slack_message(&[], &[], "prod", DateTime::from_secs(0));
+
+        // WHEN
+        let result = post_to_webhook(&url, &message).await;
+
+        // THEN
+        let error = result.expect_err("posting should've failed").to_string();
+        assert!(error.starts_with("couldn't send message to webhook"));
+        assert!(!error.contains("XXXX"));
+    }
+}
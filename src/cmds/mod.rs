@@ -1,7 +1,9 @@
 mod deployments;
 mod monitor;
+mod notify;
 mod profiles;
 
 pub use deployments::*;
 pub use monitor::*;
+pub use notify::*;
 pub use profiles::*;
@@ -1,18 +1,32 @@
-use crate::config::{ClusterConfig, ConfigSource};
-use crate::tui::run_tui;
+use crate::config::{ClientKey, ClusterConfig, NamedFilters};
+use crate::domain::FilterExpr;
+use crate::tui::{SessionStore, TuiConfig, run_tui};
 use aws_sdk_ecs::Client as ECSClient;
 use std::collections::HashMap;
 
 pub async fn run_monitor(
     profile_name: String,
-    clients_map: HashMap<ConfigSource, ECSClient>,
+    clients_map: HashMap<ClientKey, ECSClient>,
     clusters: Vec<ClusterConfig>,
+    filter: Option<FilterExpr>,
+    named_filters: NamedFilters,
+    tui_config: TuiConfig,
+    session_store: SessionStore,
 ) -> anyhow::Result<()> {
     if clusters.is_empty() {
         return Ok(());
     }
 
-    run_tui(profile_name, clients_map, clusters).await?;
+    run_tui(
+        profile_name,
+        clients_map,
+        clusters,
+        filter,
+        named_filters,
+        tui_config,
+        session_store,
+    )
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,435 @@
+use super::events::EventKind;
+use super::template::{Template, TemplateError};
+use crate::domain::{FilterExpr, named_filter_reference};
+use reqwest::Url;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use toml::de::Error as TomlError;
+
+pub const NOTIFY_CONFIG_FILE: &str = "notify.toml";
+const DEFAULT_RETRY_ATTEMPTS: u32 = 5;
+const DEFAULT_RETRY_BACKOFF_SECS: u64 = 2;
+const DEFAULT_RETRY_MAX_BACKOFF_SECS: u64 = 60;
+const MAX_RETRY_BACKOFF_SECS: u64 = 3600;
+
+/// Where changes in deployments get sent to, read from notify.toml in ecscope's config directory
+/// (or from a file passed via --notify-file).
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    pub webhooks: Vec<WebhookConfig>,
+    pub retry: RetryConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Identifies the webhook in logs (URLs are secrets)
+    pub name: String,
+    pub url: Url,
+    pub events: BTreeSet<EventKind>,
+    /// Only events matching this filter expression (or "@<name>" for a filter from the profile)
+    /// are sent
+    pub where_filter: Option<String>,
+    pub headers: HeaderMap,
+    /// The payload to send; a JSON document describing the event is sent if there's none
+    pub template: Option<Template>,
+}
+
+/// How deliveries that fail in a way that might not last (eg. timeouts, 5xx and 429 responses)
+/// are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// How many times a delivery is attempted in all
+    pub attempts: u32,
+    /// How long to wait before the first retry; doubled for every retry after that
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: DEFAULT_RETRY_ATTEMPTS,
+            backoff: Duration::from_secs(DEFAULT_RETRY_BACKOFF_SECS),
+            max_backoff: Duration::from_secs(DEFAULT_RETRY_MAX_BACKOFF_SECS),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NotifyConfigFile {
+    #[serde(default)]
+    retry: RetryFileConfig,
+    #[serde(default)]
+    webhooks: Vec<WebhookFileConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetryFileConfig {
+    attempts: Option<u32>,
+    backoff: Option<u64>,
+    max_backoff: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WebhookFileConfig {
+    name: String,
+    url: Option<String>,
+    /// Environment variable to read the URL from, for it to stay out of the file
+    url_env: Option<String>,
+    events: Option<Vec<EventKind>>,
+    #[serde(rename = "where")]
+    where_filter: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    template: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NotifyConfigError {
+    #[error("{0} doesn't exist; add webhooks to be notified to it")]
+    FileDoesntExist(PathBuf),
+    #[error("couldn't read {0}: {1}")]
+    CouldntReadFile(PathBuf, IOError),
+    #[error("{0} is invalid: {1}")]
+    FileInvalid(PathBuf, TomlError),
+    #[error("{0} has no webhooks")]
+    NoWebhooks(PathBuf),
+    #[error("retry attempts need to be at least 1")]
+    RetryAttemptsInvalid,
+    #[error(
+        "retry max_backoff needs to be at most {MAX_RETRY_BACKOFF_SECS} seconds, and backoff can't be more than it"
+    )]
+    RetryBackoffInvalid,
+    #[error(r#"webhook "{0}" is defined more than once"#)]
+    DuplicateWebhook(String),
+    #[error(r#"webhook "{0}" is invalid: {1}"#)]
+    WebhookInvalid(String, String),
+    #[error(r#"webhook "{0}" has an invalid template: {1}"#)]
+    TemplateInvalid(String, TemplateError),
+}
+
+/// Reads the notifier's config; unlike other config files, it's required, as there's nothing to
+/// notify otherwise.
+pub fn get_notify_config(path: &Path) -> Result<NotifyConfig, NotifyConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(NotifyConfigError::FileDoesntExist(path.to_path_buf()));
+        }
+        Err(e) => return Err(NotifyConfigError::CouldntReadFile(path.to_path_buf(), e)),
+    };
+
+    let file: NotifyConfigFile = toml::from_str(&contents)
+        .map_err(|e| NotifyConfigError::FileInvalid(path.to_path_buf(), e))?;
+    if file.webhooks.is_empty() {
+        return Err(NotifyConfigError::NoWebhooks(path.to_path_buf()));
+    }
+
+    parse_notify_config(file, |name| std::env::var(name).ok())
+}
+
+fn parse_notify_config<F>(file: NotifyConfigFile, env: F) -> Result<NotifyConfig, NotifyConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let defaults = RetryConfig::default();
+    let retry = RetryConfig {
+        attempts: file.retry.attempts.unwrap_or(defaults.attempts),
+        backoff: file
+            .retry
+            .backoff
+            .map_or(defaults.backoff, Duration::from_secs),
+        max_backoff: file
+            .retry
+            .max_backoff
+            .map_or(defaults.max_backoff, Duration::from_secs),
+    };
+    if retry.attempts == 0 {
+        return Err(NotifyConfigError::RetryAttemptsInvalid);
+    }
+    if retry.max_backoff > Duration::from_secs(MAX_RETRY_BACKOFF_SECS)
+        || retry.backoff > retry.max_backoff
+    {
+        return Err(NotifyConfigError::RetryBackoffInvalid);
+    }
+
+    let mut names = BTreeSet::new();
+    let mut webhooks = Vec::new();
+    for webhook in file.webhooks {
+        if !names.insert(webhook.name.clone()) {
+            return Err(NotifyConfigError::DuplicateWebhook(webhook.name));
+        }
+        webhooks.push(parse_webhook(webhook, &env)?);
+    }
+
+    Ok(NotifyConfig { webhooks, retry })
+}
+
+fn parse_webhook<F>(webhook: WebhookFileConfig, env: &F) -> Result<WebhookConfig, NotifyConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let name = webhook.name;
+    let invalid = |reason: String| NotifyConfigError::WebhookInvalid(name.clone(), reason);
+
+    // URLs are secrets, so they're left out of errors
+    let url = match (webhook.url, webhook.url_env) {
+        (Some(url), None) => url,
+        (None, Some(var)) => env(&var)
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| invalid(format!("environment variable {var} isn't set")))?,
+        _ => {
+            return Err(invalid(
+                "exactly one of url and url_env needs to be set".to_string(),
+            ));
+        }
+    };
+    let url = Url::parse(url.trim())
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| invalid("URL is not a valid http(s) URL".to_string()))?;
+
+    let events = match webhook.events {
+        Some(events) if events.is_empty() => {
+            return Err(invalid("events can't be empty".to_string()));
+        }
+        Some(events) => events.into_iter().collect(),
+        None => EventKind::ALL.into_iter().collect::<BTreeSet<_>>(),
+    };
+
+    // named filters can only be resolved once the profile has been read
+    if let Some(where_filter) = &webhook.where_filter
+        && named_filter_reference(where_filter).is_none()
+    {
+        FilterExpr::parse(where_filter)
+            .map_err(|e| invalid(format!(r#"filter "{where_filter}" is not valid: {e}"#)))?;
+    }
+
+    let mut headers = HeaderMap::new();
+    for (key, value) in webhook.headers {
+        let header_name = HeaderName::from_bytes(key.as_bytes())
+            .map_err(|_| invalid(format!(r#"header name "{key}" is not valid"#)))?;
+        let header_value = HeaderValue::from_str(&value)
+            .map_err(|_| invalid(format!(r#"value of header "{key}" is not valid"#)))?;
+        headers.insert(header_name, header_value);
+    }
+
+    let template = match webhook.template {
+        Some(source) => {
+            let template = Template::parse(&source)
+                .map_err(|e| NotifyConfigError::TemplateInvalid(name.clone(), e))?;
+            template
+                .validate_for(&events.iter().copied().collect::<Vec<_>>())
+                .map_err(|e| NotifyConfigError::TemplateInvalid(name.clone(), e))?;
+            Some(template)
+        }
+        None => None,
+    };
+
+    Ok(WebhookConfig {
+        name,
+        url,
+        events,
+        where_filter: webhook.where_filter,
+        headers,
+        template,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Result<NotifyConfig, NotifyConfigError> {
+        let file: NotifyConfigFile =
+            toml::from_str(contents).expect("config should've been deserialized");
+        parse_notify_config(file, |name| {
+            (name == "RELEASES_WEBHOOK_URL")
+                .then(|| "https://hooks.slack.com/services/T000/B000/XXXX".to_string())
+        })
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_notify_config_works() {
+        // GIVEN
+        let contents = r#"
+[retry]
+attempts = 3
+backoff = 1
+
+[[webhooks]]
+name = "releases"
+url_env = "RELEASES_WEBHOOK_URL"
+events = ["completed", "failing"]
+where = "key=prod"
+template = '{"text": "{{message}}"}'
+
+[[webhooks]]
+name = "incidents"
+url = "https://alerts.example.com/hooks/ecs"
+headers = { Authorization = "Bearer token" }
+"#;
+
+        // WHEN
+        let config = parse(contents).expect("config should've been parsed");
+
+        // THEN
+        assert_eq!(
+            config.retry,
+            RetryConfig {
+                attempts: 3,
+                backoff: Duration::from_secs(1),
+                max_backoff: Duration::from_secs(DEFAULT_RETRY_MAX_BACKOFF_SECS),
+            }
+        );
+        let [releases, incidents] = &config.webhooks[..] else {
+            panic!("config should've had two webhooks");
+        };
+        assert_eq!(
+            releases.url.as_str(),
+            "https://hooks.slack.com/services/T000/B000/XXXX"
+        );
+        assert_eq!(
+            releases.events.iter().collect::<Vec<_>>(),
+            vec![&EventKind::Completed, &EventKind::Failing]
+        );
+        assert_eq!(releases.where_filter.as_deref(), Some("key=prod"));
+        assert!(releases.template.is_some());
+        assert_eq!(incidents.events.len(), EventKind::ALL.len());
+        assert_eq!(
+            incidents
+                .headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok()),
+            Some("Bearer token")
+        );
+        assert!(incidents.template.is_none());
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_invalid_webhooks_fails() {
+        // GIVEN
+        let cases = [
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+"#,
+                r#"webhook "releases" is invalid: exactly one of url and url_env needs to be set"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url_env = "UNSET_WEBHOOK_URL"
+"#,
+                r#"webhook "releases" is invalid: environment variable UNSET_WEBHOOK_URL isn't set"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url = "ftp://hooks.example.com/secret"
+"#,
+                r#"webhook "releases" is invalid: URL is not a valid http(s) URL"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+events = []
+"#,
+                r#"webhook "releases" is invalid: events can't be empty"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+where = "keys=prod and"
+"#,
+                r#"webhook "releases" is invalid: filter "keys=prod and" is not valid: expected a field, found end of filter"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+template = '{"text": {{message}}}'
+"#,
+                r#"webhook "releases" has an invalid template: template doesn't result in valid JSON for "started" events: expected value at line 1 column 10"#,
+            ),
+            (
+                r#"
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/other-secret"
+"#,
+                r#"webhook "releases" is defined more than once"#,
+            ),
+            (
+                r#"
+[retry]
+attempts = 0
+
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+"#,
+                "retry attempts need to be at least 1",
+            ),
+            (
+                r#"
+[retry]
+backoff = 120
+
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+"#,
+                "retry max_backoff needs to be at most 3600 seconds, and backoff can't be more than it",
+            ),
+            (
+                r#"
+[retry]
+max_backoff = 9223372036854775807
+
+[[webhooks]]
+name = "releases"
+url = "https://hooks.example.com/secret"
+"#,
+                "retry max_backoff needs to be at most 3600 seconds, and backoff can't be more than it",
+            ),
+        ];
+
+        for (contents, expected) in cases {
+            // WHEN
+            let result = parse(contents);
+
+            // THEN
+            let error = result.expect_err("parsing should've failed").to_string();
+            assert_eq!(error, expected);
+            assert!(!error.contains("secret"));
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use crate::domain::{DeploymentDetails, DeploymentError, Field, FieldValue, Filterable};
+use std::collections::{BTreeMap, BTreeSet};
+
+// https://docs.aws.amazon.com/AmazonECS/latest/APIReference/API_Deployment.html
+const DEPLOYMENT_STATUS_PRIMARY: &str = "PRIMARY";
+
+/// The kinds of changes in deployments that webhooks can be notified about.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Started,
+    Completed,
+    Failing,
+    Unreachable,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 4] = [
+        EventKind::Started,
+        EventKind::Completed,
+        EventKind::Failing,
+        EventKind::Unreachable,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Started => "started",
+            EventKind::Completed => "completed",
+            EventKind::Failing => "failing",
+            EventKind::Unreachable => "unreachable",
+        }
+    }
+}
+
+/// A change in deployments seen between two polls.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub(super) enum DeploymentEvent {
+    Started {
+        deployment: DeploymentDetails,
+    },
+    Completed {
+        deployment: DeploymentDetails,
+    },
+    Failing {
+        deployment: DeploymentDetails,
+        previous_failed_count: i32,
+    },
+    Unreachable {
+        cluster_arn: String,
+        keys: String,
+        error: String,
+    },
+}
+
+impl DeploymentEvent {
+    pub(super) fn kind(&self) -> EventKind {
+        match self {
+            DeploymentEvent::Started { .. } => EventKind::Started,
+            DeploymentEvent::Completed { .. } => EventKind::Completed,
+            DeploymentEvent::Failing { .. } => EventKind::Failing,
+            DeploymentEvent::Unreachable { .. } => EventKind::Unreachable,
+        }
+    }
+
+    pub(super) fn deployment(&self) -> Option<&DeploymentDetails> {
+        match self {
+            DeploymentEvent::Started { deployment }
+            | DeploymentEvent::Completed { deployment }
+            | DeploymentEvent::Failing { deployment, .. } => Some(deployment),
+            DeploymentEvent::Unreachable { .. } => None,
+        }
+    }
+
+    /// Describes the event in a line, eg. for chat messages.
+    pub(super) fn message(&self) -> String {
+        match self {
+            DeploymentEvent::Started { deployment } => format!(
+                "{} ({}): deployment {} started ({}/{} running)",
+                deployment.service_name,
+                deployment.keys,
+                deployment.deployment_id,
+                deployment.running_count,
+                deployment.desired_count
+            ),
+            DeploymentEvent::Completed { deployment } => format!(
+                "{} ({}): deployment {} completed ({}/{} running)",
+                deployment.service_name,
+                deployment.keys,
+                deployment.deployment_id,
+                deployment.running_count,
+                deployment.desired_count
+            ),
+            DeploymentEvent::Failing {
+                deployment,
+                previous_failed_count,
+            } => format!(
+                "{} ({}): failed tasks of deployment {} went up from {} to {}",
+                deployment.service_name,
+                deployment.keys,
+                deployment.deployment_id,
+                previous_failed_count,
+                deployment.failed_count
+            ),
+            DeploymentEvent::Unreachable {
+                cluster_arn,
+                keys,
+                error,
+            } => format!(
+                "cluster {cluster_arn} ({keys}) is unreachable: {}",
+                error.trim().lines().next().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+// unreachable clusters only have a key and an ARN to be filtered by
+impl Filterable for DeploymentEvent {
+    const KIND: &'static str = "events";
+
+    fn field(&self, field: Field) -> Option<FieldValue<'_>> {
+        match self {
+            DeploymentEvent::Started { deployment }
+            | DeploymentEvent::Completed { deployment }
+            | DeploymentEvent::Failing { deployment, .. } => deployment.field(field),
+            DeploymentEvent::Unreachable {
+                cluster_arn, keys, ..
+            } => match field {
+                Field::Key => Some(FieldValue::List(keys.split(',').collect())),
+                Field::Cluster => Some(FieldValue::Text(cluster_arn)),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Identifies a deployment across polls.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct DeploymentKey {
+    cluster_arn: String,
+    service_name: String,
+    deployment_id: String,
+}
+
+impl From<&DeploymentDetails> for DeploymentKey {
+    fn from(deployment: &DeploymentDetails) -> Self {
+        Self {
+            cluster_arn: deployment.cluster_arn.clone(),
+            service_name: deployment.service_name.clone(),
+            deployment_id: deployment.deployment_id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Unreachable {
+    keys: String,
+    error: String,
+}
+
+/// What's known about the deployments of a profile as of a poll.
+#[derive(Debug, Default)]
+pub(super) struct Snapshot {
+    deployments: BTreeMap<DeploymentKey, DeploymentDetails>,
+    /// Clusters whose services couldn't be fetched, by ARN
+    unreachable: BTreeMap<String, Unreachable>,
+    /// Clusters that deployments have been fetched from at least once, by ARN
+    seen: BTreeSet<String>,
+}
+
+impl Snapshot {
+    /// Builds a snapshot from the results of a poll. Deployments of clusters that couldn't be
+    /// reached are carried over from the previous snapshot, so that changes made while a cluster
+    /// was unreachable are compared against what was last seen of it.
+    pub(super) fn new(
+        deployments: Vec<DeploymentDetails>,
+        errors: Vec<DeploymentError>,
+        previous: Option<&Snapshot>,
+    ) -> Self {
+        let mut unreachable = BTreeMap::new();
+        for error in errors {
+            unreachable
+                .entry(error.cluster_arn)
+                .or_insert_with(|| Unreachable {
+                    keys: error.keys,
+                    error: error.error,
+                });
+        }
+
+        let mut deployments = deployments
+            .into_iter()
+            .map(|d| (DeploymentKey::from(&d), d))
+            .collect::<BTreeMap<_, _>>();
+        let mut seen = deployments
+            .keys()
+            .map(|k| k.cluster_arn.clone())
+            .collect::<BTreeSet<_>>();
+        if let Some(previous) = previous {
+            for (key, deployment) in &previous.deployments {
+                if unreachable.contains_key(&key.cluster_arn) {
+                    deployments.insert(key.clone(), deployment.clone());
+                }
+            }
+            seen.extend(previous.seen.iter().cloned());
+        }
+
+        Self {
+            deployments,
+            unreachable,
+            seen,
+        }
+    }
+
+    /// Whether a deployment is the only one left for its service, with all its tasks running.
+    fn is_done(&self, deployment: &DeploymentDetails) -> bool {
+        deployment.status == DEPLOYMENT_STATUS_PRIMARY
+            && deployment.running_count == deployment.desired_count
+            && !self.deployments.values().any(|d| {
+                d.cluster_arn == deployment.cluster_arn
+                    && d.service_name == deployment.service_name
+                    && d.deployment_id != deployment.deployment_id
+            })
+    }
+}
+
+/// Returns the events worth notifying about between two polls. Deployments of a cluster are
+/// only compared once it's been fetched from before (so the first poll only serves as a baseline
+/// for them); clusters that can't be reached are reported right away.
+pub(super) fn events_between(
+    previous: Option<&Snapshot>,
+    current: &Snapshot,
+) -> Vec<DeploymentEvent> {
+    let empty = Snapshot::default();
+    let previous = previous.unwrap_or(&empty);
+    let mut events = Vec::new();
+
+    for (cluster_arn, unreachable) in &current.unreachable {
+        if !previous.unreachable.contains_key(cluster_arn) {
+            events.push(DeploymentEvent::Unreachable {
+                cluster_arn: cluster_arn.clone(),
+                keys: unreachable.keys.clone(),
+                error: unreachable.error.clone(),
+            });
+        }
+    }
+
+    for (key, deployment) in &current.deployments {
+        if !previous.seen.contains(&key.cluster_arn) {
+            continue;
+        }
+
+        let before = previous.deployments.get(key);
+        if before.is_none() && deployment.status == DEPLOYMENT_STATUS_PRIMARY {
+            events.push(DeploymentEvent::Started {
+                deployment: deployment.clone(),
+            });
+        }
+
+        let previous_failed_count = before.map_or(0, |b| b.failed_count);
+        if deployment.failed_count > previous_failed_count {
+            events.push(DeploymentEvent::Failing {
+                deployment: deployment.clone(),
+                previous_failed_count,
+            });
+        }
+
+        let was_done = before.is_some_and(|b| previous.is_done(b));
+        if current.is_done(deployment) && !was_done {
+            events.push(DeploymentEvent::Completed {
+                deployment: deployment.clone(),
+            });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLUSTER_ARN: &str = "arn:aws:ecs:eu-central-1:111111111111:cluster/prod";
+
+    fn deployment(id: &str, status: &str, counts: [i32; 3]) -> DeploymentDetails {
+        let [running_count, desired_count, failed_count] = counts;
+        DeploymentDetails {
+            service_name: "payments-api".to_string(),
+            keys: "prod".to_string(),
+            cluster_arn: CLUSTER_ARN.to_string(),
+            deployment_id: id.to_string(),
+            status: status.to_string(),
+            running_count,
+            desired_count,
+            pending_count: desired_count - running_count,
+            failed_count,
+            service_alias: None,
+        }
+    }
+
+    fn unreachable() -> DeploymentError {
+        DeploymentError {
+            service_name: "payments-api".to_string(),
+            error: "AccessDeniedException\nnot authorized".to_string(),
+            cluster_arn: CLUSTER_ARN.to_string(),
+            keys: "prod".to_string(),
+            service_alias: None,
+        }
+    }
+
+    fn messages_over(polls: Vec<(Vec<DeploymentDetails>, Vec<DeploymentError>)>) -> Vec<String> {
+        let mut previous: Option<Snapshot> = None;
+        let mut messages = Vec::new();
+        for (deployments, errors) in polls {
+            let current = Snapshot::new(deployments, errors, previous.as_ref());
+            messages.extend(
+                events_between(previous.as_ref(), &current)
+                    .iter()
+                    .map(|e| format!("{}: {}", e.kind().as_str(), e.message())),
+            );
+            previous = Some(current);
+        }
+
+        messages
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn a_rollout_is_reported_from_start_to_completion() {
+        // GIVEN
+        let polls = vec![
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (
+                vec![
+                    deployment("ecs-svc/1", "ACTIVE", [2, 2, 0]),
+                    deployment("ecs-svc/2", "PRIMARY", [0, 2, 0]),
+                ],
+                vec![],
+            ),
+            (
+                vec![
+                    deployment("ecs-svc/1", "ACTIVE", [1, 0, 0]),
+                    deployment("ecs-svc/2", "PRIMARY", [2, 2, 0]),
+                ],
+                vec![],
+            ),
+            (vec![deployment("ecs-svc/2", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![deployment("ecs-svc/2", "PRIMARY", [2, 2, 0])], vec![]),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert_eq!(
+            messages,
+            vec![
+                "started: payments-api (prod): deployment ecs-svc/2 started (0/2 running)",
+                "completed: payments-api (prod): deployment ecs-svc/2 completed (2/2 running)",
+            ]
+        );
+    }
+
+    #[test]
+    fn deployments_that_finish_between_polls_are_reported_as_started_and_completed() {
+        // GIVEN
+        let polls = vec![
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![deployment("ecs-svc/2", "PRIMARY", [2, 2, 0])], vec![]),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert_eq!(
+            messages,
+            vec![
+                "started: payments-api (prod): deployment ecs-svc/2 started (2/2 running)",
+                "completed: payments-api (prod): deployment ecs-svc/2 completed (2/2 running)",
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_tasks_going_up_is_reported_every_time() {
+        // GIVEN
+        let polls = vec![
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![deployment("ecs-svc/1", "PRIMARY", [1, 2, 1])], vec![]),
+            (vec![deployment("ecs-svc/1", "PRIMARY", [1, 2, 1])], vec![]),
+            (vec![deployment("ecs-svc/1", "PRIMARY", [0, 2, 3])], vec![]),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert_eq!(
+            messages,
+            vec![
+                "failing: payments-api (prod): failed tasks of deployment ecs-svc/1 went up from 0 to 1",
+                "failing: payments-api (prod): failed tasks of deployment ecs-svc/1 went up from 1 to 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_first_poll_is_only_a_baseline_for_deployments() {
+        // GIVEN
+        let polls = vec![(
+            vec![
+                deployment("ecs-svc/1", "ACTIVE", [2, 0, 0]),
+                deployment("ecs-svc/2", "PRIMARY", [0, 2, 4]),
+            ],
+            vec![],
+        )];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn clusters_are_reported_when_they_become_unreachable() {
+        // GIVEN
+        let polls = vec![
+            (vec![], vec![unreachable()]),
+            (vec![], vec![unreachable()]),
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![], vec![unreachable()]),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        let message = format!(
+            "unreachable: cluster {CLUSTER_ARN} (prod) is unreachable: AccessDeniedException"
+        );
+        assert_eq!(messages, vec![message.clone(), message]);
+    }
+
+    #[test]
+    fn clusters_becoming_reachable_again_dont_look_like_new_deployments() {
+        // GIVEN
+        let polls = vec![
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![], vec![unreachable()]),
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert_eq!(
+            messages,
+            vec![format!(
+                "unreachable: cluster {CLUSTER_ARN} (prod) is unreachable: AccessDeniedException"
+            )]
+        );
+    }
+
+    #[test]
+    fn changes_made_while_a_cluster_was_unreachable_are_reported_once_its_reachable_again() {
+        // GIVEN
+        let polls = vec![
+            (vec![deployment("ecs-svc/1", "PRIMARY", [2, 2, 0])], vec![]),
+            (vec![], vec![unreachable()]),
+            (
+                vec![
+                    deployment("ecs-svc/1", "ACTIVE", [2, 2, 0]),
+                    deployment("ecs-svc/2", "PRIMARY", [0, 2, 0]),
+                ],
+                vec![],
+            ),
+        ];
+
+        // WHEN
+        let messages = messages_over(polls);
+
+        // THEN
+        assert_eq!(
+            messages,
+            vec![
+                format!(
+                    "unreachable: cluster {CLUSTER_ARN} (prod) is unreachable: AccessDeniedException"
+                ),
+                "started: payments-api (prod): deployment ecs-svc/2 started (0/2 running)"
+                    .to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod config;
+mod events;
+mod run;
+mod template;
+
+pub use config::*;
+pub use run::*;
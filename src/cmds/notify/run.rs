@@ -0,0 +1,458 @@
+use super::config::{NotifyConfig, RetryConfig, WebhookConfig};
+use super::events::{DeploymentEvent, EventKind, Snapshot, events_between};
+use super::template::Context;
+use crate::config::{ClientKey, ClusterConfig, NamedFilters};
+use crate::domain::{FilterExpr, FilterParseError};
+use crate::server::shutdown_signal;
+use crate::service::get_deployments;
+use crate::webhook::{PostToWebhookError, send_to_webhook, webhook_client};
+use aws_sdk_ecs::Client as ECSClient;
+use aws_sdk_ecs::primitives::{DateTime, DateTimeFormat};
+use reqwest::Client;
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::time::MissedTickBehavior;
+
+// deliveries still being retried when shutting down get this long to go through
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+// deliveries beyond these, for a webhook that's slow or down, are dropped
+const MAX_QUEUED_DELIVERIES: usize = 100;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RunNotifierError {
+    #[error(r#"webhook "{0}" has an invalid filter: {1}"#)]
+    FilterInvalid(String, FilterParseError),
+    #[error("couldn't set up webhook client: {0}")]
+    CouldntBuildClient(PostToWebhookError),
+}
+
+/// A payload on its way to a webhook.
+#[derive(Debug)]
+struct Delivery {
+    kind: EventKind,
+    body: String,
+}
+
+/// A webhook, ready to be sent events.
+struct Subscriber {
+    config: WebhookConfig,
+    filter: Option<FilterExpr>,
+    sender: Sender<Delivery>,
+}
+
+impl Subscriber {
+    fn wants(&self, event: &DeploymentEvent) -> bool {
+        self.config.events.contains(&event.kind())
+            && self.filter.as_ref().is_none_or(|f| f.matches(event))
+    }
+}
+
+/// The payload sent for webhooks without a template.
+#[derive(Debug, serde::Serialize)]
+struct DefaultPayload<'a> {
+    profile: &'a str,
+    at: &'a str,
+    message: String,
+    #[serde(flatten)]
+    event: &'a DeploymentEvent,
+}
+
+/// Polls deployments every `interval`, and sends the changes seen between polls to webhooks,
+/// until interrupted.
+pub async fn run_notifier(
+    profile_name: String,
+    clients_map: HashMap<ClientKey, ECSClient>,
+    clusters: Vec<ClusterConfig>,
+    named_filters: NamedFilters,
+    config: NotifyConfig,
+    interval: Duration,
+) -> Result<(), RunNotifierError> {
+    let client = webhook_client().map_err(RunNotifierError::CouldntBuildClient)?;
+
+    let mut subscribers = Vec::new();
+    let mut workers = Vec::new();
+    for webhook in config.webhooks {
+        let filter = webhook
+            .where_filter
+            .as_deref()
+            .map(|w| FilterExpr::parse_with_named(w, &named_filters))
+            .transpose()
+            .map_err(|e| RunNotifierError::FilterInvalid(webhook.name.clone(), e))?;
+        let (sender, receiver) = channel(MAX_QUEUED_DELIVERIES);
+        workers.push(tokio::spawn(deliver_in_order(
+            client.clone(),
+            webhook.clone(),
+            config.retry,
+            receiver,
+        )));
+        subscribers.push(Subscriber {
+            config: webhook,
+            filter,
+            sender,
+        });
+    }
+
+    println!(
+        "notifying {} about deployments of profile \"{profile_name}\", polling every {}s",
+        match subscribers.len() {
+            1 => "1 webhook".to_string(),
+            n => format!("{n} webhooks"),
+        },
+        interval.as_secs()
+    );
+
+    let clients_map = Arc::new(clients_map);
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut previous: Option<Snapshot> = None;
+
+    let poll = async {
+        loop {
+            ticker.tick().await;
+            let (deployments, errors) =
+                match get_deployments(clusters.clone(), Arc::clone(&clients_map), None).await {
+                    Ok(results) => results,
+                    Err(e) => {
+                        log(serde_json::json!({
+                            "error": format!("couldn't fetch deployments: {e}"),
+                        }));
+                        continue;
+                    }
+                };
+
+            let current = Snapshot::new(deployments, errors, previous.as_ref());
+            let at = now();
+            for event in events_between(previous.as_ref(), &current) {
+                log(serde_json::json!({
+                    "event": event.kind().as_str(),
+                    "message": event.message(),
+                }));
+
+                let context = Context {
+                    profile_name: &profile_name,
+                    at: &at,
+                };
+                for subscriber in subscribers.iter().filter(|s| s.wants(&event)) {
+                    let body = payload(subscriber.config.template.as_ref(), &event, context);
+                    let delivery = Delivery {
+                        kind: event.kind(),
+                        body,
+                    };
+                    // workers only stop once senders are dropped
+                    if let Err(TrySendError::Full(delivery)) = subscriber.sender.try_send(delivery)
+                    {
+                        log(serde_json::json!({
+                            "webhook": subscriber.config.name,
+                            "event": delivery.kind.as_str(),
+                            "delivered": false,
+                            "error": format!("dropped, as {MAX_QUEUED_DELIVERIES} deliveries are already queued up"),
+                        }));
+                    }
+                }
+            }
+            previous = Some(current);
+        }
+    };
+
+    tokio::select! {
+        _ = poll => {},
+        _ = shutdown_signal() => {},
+    }
+
+    drop(subscribers);
+    let _ = tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, async {
+        for worker in workers {
+            let _ = worker.await;
+        }
+    })
+    .await;
+
+    Ok(())
+}
+
+fn payload(
+    template: Option<&super::template::Template>,
+    event: &DeploymentEvent,
+    context: Context<'_>,
+) -> String {
+    match template {
+        Some(template) => template.render(event, context),
+        None => serde_json::json!(DefaultPayload {
+            profile: context.profile_name,
+            at: context.at,
+            message: event.message(),
+            event,
+        })
+        .to_string(),
+    }
+}
+
+/// Delivers payloads to a webhook one at a time, so that they arrive in the order the events
+/// were seen in.
+async fn deliver_in_order(
+    client: Client,
+    webhook: WebhookConfig,
+    retry: RetryConfig,
+    mut receiver: Receiver<Delivery>,
+) {
+    while let Some(delivery) = receiver.recv().await {
+        let line = match deliver(&client, &webhook, &delivery.body, retry).await {
+            Ok(attempts) => serde_json::json!({
+                "webhook": webhook.name,
+                "event": delivery.kind.as_str(),
+                "delivered": true,
+                "attempts": attempts,
+            }),
+            Err((attempts, e)) => serde_json::json!({
+                "webhook": webhook.name,
+                "event": delivery.kind.as_str(),
+                "delivered": false,
+                "attempts": attempts,
+                "error": e.to_string(),
+            }),
+        };
+        log(line);
+    }
+}
+
+/// Sends a payload to a webhook, retrying with exponential backoff while failures might not
+/// last. Returns the number of attempts made.
+async fn deliver(
+    client: &Client,
+    webhook: &WebhookConfig,
+    body: &str,
+    retry: RetryConfig,
+) -> Result<u32, (u32, PostToWebhookError)> {
+    let mut attempt = 1;
+    let mut backoff = retry.backoff;
+    loop {
+        let request = client
+            .post(webhook.url.clone())
+            .headers(webhook.headers.clone())
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body.to_string());
+
+        match send_to_webhook(request).await {
+            Ok(()) => return Ok(attempt),
+            Err(e) if e.is_transient() && attempt < retry.attempts => {
+                log(serde_json::json!({
+                    "webhook": webhook.name,
+                    "attempt": attempt,
+                    "error": e.to_string(),
+                    "retrying_in_ms": backoff.as_millis(),
+                }));
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2).min(retry.max_backoff);
+                attempt += 1;
+            }
+            Err(e) => return Err((attempt, e)),
+        }
+    }
+}
+
+fn now() -> String {
+    DateTime::from(SystemTime::now())
+        .fmt(DateTimeFormat::DateTime)
+        .unwrap_or_default()
+}
+
+/// Logs a line of JSON, with the time prepended.
+fn log(line: serde_json::Value) {
+    let mut entry = serde_json::Map::new();
+    entry.insert("time".to_string(), serde_json::Value::String(now()));
+    if let serde_json::Value::Object(fields) = line {
+        entry.extend(fields);
+    }
+    println!("{}", serde_json::Value::Object(entry));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use reqwest::Url;
+    use reqwest::header::HeaderMap;
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+
+    const RETRY: RetryConfig = RetryConfig {
+        attempts: 3,
+        backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(2),
+    };
+
+    /// Serves a stand-in for a webhook on a local port, which responds with the given statuses
+    /// in turn (and 200 once they run out), and keeps the payloads it received.
+    async fn webhook_stand_in(
+        statuses: Vec<StatusCode>,
+    ) -> (WebhookConfig, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = Arc::new(Mutex::new(statuses.into_iter()));
+        let router = Router::new().route(
+            "/hooks/secret",
+            post({
+                let received = Arc::clone(&received);
+                move |payload: String| async move {
+                    received
+                        .lock()
+                        .expect("lock should've been acquired")
+                        .push(payload);
+                    statuses
+                        .lock()
+                        .expect("lock should've been acquired")
+                        .next()
+                        .unwrap_or(StatusCode::OK)
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should've been bound");
+        let address = listener
+            .local_addr()
+            .expect("listener should've had an address");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let webhook = WebhookConfig {
+            name: "releases".to_string(),
+            url: Url::parse(&format!("http://{address}/hooks/secret"))
+                .expect("url should've been valid"),
+            events: BTreeSet::from(EventKind::ALL),
+            where_filter: None,
+            headers: HeaderMap::new(),
+            template: None,
+        };
+
+        (webhook, received)
+    }
+
+    fn event() -> DeploymentEvent {
+        DeploymentEvent::Unreachable {
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            keys: "prod".to_string(),
+            error: "AccessDeniedException".to_string(),
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn default_payload_describes_the_event() {
+        // GIVEN
+        let context = Context {
+            profile_name: "prod",
+            at: "2026-10-19T07:00:00Z",
+        };
+
+        // WHEN
+        let body = payload(None, &event(), context);
+
+        // THEN
+        insta::assert_snapshot!(body, @r#"{"at":"2026-10-19T07:00:00Z","cluster_arn":"arn:aws:ecs:eu-central-1:111111111111:cluster/prod","error":"AccessDeniedException","event":"unreachable","keys":"prod","message":"cluster arn:aws:ecs:eu-central-1:111111111111:cluster/prod (prod) is unreachable: AccessDeniedException","profile":"prod"}"#);
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_retried_while_failures_might_not_last() {
+        // GIVEN
+        let (webhook, received) = webhook_stand_in(vec![
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::TOO_MANY_REQUESTS,
+        ])
+        .await;
+        let client = webhook_client().expect("client should've been built");
+
+        // WHEN
+        let result = deliver(&client, &webhook, r#"{"text":"hi"}"#, RETRY).await;
+
+        // THEN
+        assert_eq!(result.map_err(|(_, e)| e.to_string()), Ok(3));
+        assert_eq!(
+            *received.lock().expect("lock should've been acquired"),
+            vec![r#"{"text":"hi"}"#; 3]
+        );
+    }
+
+    #[test]
+    fn subscribers_only_want_events_they_asked_for() {
+        // GIVEN
+        let (sender, _) = channel(1);
+        let subscriber = Subscriber {
+            config: WebhookConfig {
+                name: "releases".to_string(),
+                url: Url::parse("https://hooks.example.com/secret")
+                    .expect("url should've been valid"),
+                events: BTreeSet::from([EventKind::Unreachable]),
+                where_filter: None,
+                headers: HeaderMap::new(),
+                template: None,
+            },
+            filter: Some(FilterExpr::parse("key=qa").expect("filter should've been parsed")),
+            sender,
+        };
+        let qa_event = DeploymentEvent::Unreachable {
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/qa".to_string(),
+            keys: "qa".to_string(),
+            error: "AccessDeniedException".to_string(),
+        };
+
+        // WHEN
+        // THEN
+        assert!(subscriber.wants(&qa_event));
+        assert!(!subscriber.wants(&event()));
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn deliveries_rejected_for_good_arent_retried() {
+        // GIVEN
+        let (webhook, received) = webhook_stand_in(vec![StatusCode::NOT_FOUND]).await;
+        let client = webhook_client().expect("client should've been built");
+
+        // WHEN
+        let result = deliver(&client, &webhook, "{}", RETRY).await;
+
+        // THEN
+        let (attempts, error) = result.expect_err("delivery should've failed");
+        assert_eq!(attempts, 1);
+        assert_eq!(error.to_string(), "webhook responded with 404 Not Found: ");
+        assert_eq!(
+            received.lock().expect("lock should've been acquired").len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_given_up_on_after_the_last_attempt() {
+        // GIVEN
+        let (webhook, received) =
+            webhook_stand_in(vec![StatusCode::BAD_GATEWAY; RETRY.attempts as usize]).await;
+        let client = webhook_client().expect("client should've been built");
+
+        // WHEN
+        let result = deliver(&client, &webhook, "{}", RETRY).await;
+
+        // THEN
+        let (attempts, error) = result.expect_err("delivery should've failed");
+        assert_eq!(attempts, RETRY.attempts);
+        assert_eq!(
+            error.to_string(),
+            "webhook responded with 502 Bad Gateway: "
+        );
+        assert_eq!(
+            received.lock().expect("lock should've been acquired").len(),
+            RETRY.attempts as usize
+        );
+    }
+}
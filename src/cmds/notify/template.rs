@@ -0,0 +1,307 @@
+use super::events::{DeploymentEvent, EventKind};
+use crate::domain::DeploymentDetails;
+
+const OPENING: &str = "{{";
+const CLOSING: &str = "}}";
+
+/// A webhook payload, with `{{placeholder}}`s to be filled in with the details of an event.
+///
+/// Placeholders are replaced with JSON-escaped values (without quotes), so that string values
+/// can be put in quotes, and numbers can be used either way. Placeholders that don't apply to an
+/// event (eg. `{{running}}` for an unreachable cluster) are left empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Event,
+    Profile,
+    At,
+    Message,
+    Service,
+    Keys,
+    Cluster,
+    Status,
+    Deployment,
+    Running,
+    Desired,
+    Pending,
+    Failed,
+    PreviousFailed,
+    Error,
+}
+
+impl Placeholder {
+    fn from_name(name: &str) -> Option<Self> {
+        let placeholder = match name {
+            "event" => Placeholder::Event,
+            "profile" => Placeholder::Profile,
+            "at" => Placeholder::At,
+            "message" => Placeholder::Message,
+            "service" => Placeholder::Service,
+            "keys" => Placeholder::Keys,
+            "cluster" => Placeholder::Cluster,
+            "status" => Placeholder::Status,
+            "deployment" => Placeholder::Deployment,
+            "running" => Placeholder::Running,
+            "desired" => Placeholder::Desired,
+            "pending" => Placeholder::Pending,
+            "failed" => Placeholder::Failed,
+            "previous_failed" => Placeholder::PreviousFailed,
+            "error" => Placeholder::Error,
+            _ => return None,
+        };
+
+        Some(placeholder)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("placeholder starting at position {0} isn't closed")]
+    UnclosedPlaceholder(usize),
+    #[error(r#"unknown placeholder "{0}"; valid placeholders: event, profile, at, message, service, keys, cluster, status, deployment, running, desired, pending, failed, previous_failed, error"#)]
+    UnknownPlaceholder(String),
+    #[error(r#"template doesn't result in valid JSON for "{0}" events: {1}"#)]
+    NotJson(&'static str, String),
+}
+
+/// What's needed to fill in a payload, besides the event.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Context<'a> {
+    pub(super) profile_name: &'a str,
+    /// When the event was seen
+    pub(super) at: &'a str,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut rest = source;
+        let mut offset = 0;
+        while let Some(start) = rest.find(OPENING) {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let after_opening = &rest[start + OPENING.len()..];
+            let end = after_opening
+                .find(CLOSING)
+                .ok_or(TemplateError::UnclosedPlaceholder(offset + start))?;
+            let name = after_opening[..end].trim();
+            let placeholder = Placeholder::from_name(name)
+                .ok_or_else(|| TemplateError::UnknownPlaceholder(name.to_string()))?;
+            parts.push(Part::Placeholder(placeholder));
+
+            let consumed = start + OPENING.len() + end + CLOSING.len();
+            rest = &rest[consumed..];
+            offset += consumed;
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Checks that the template results in valid JSON for every kind of event it'll be used for.
+    pub fn validate_for(&self, kinds: &[EventKind]) -> Result<(), TemplateError> {
+        let context = Context {
+            profile_name: "profile",
+            at: "1970-01-01T00:00:00Z",
+        };
+        for kind in kinds {
+            let payload = self.render(&sample_event(*kind), context);
+            serde_json::from_str::<serde_json::Value>(&payload)
+                .map_err(|e| TemplateError::NotJson(kind.as_str(), e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn render(&self, event: &DeploymentEvent, context: Context<'_>) -> String {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => rendered.push_str(text),
+                Part::Placeholder(placeholder) => {
+                    rendered.push_str(&value_of(*placeholder, event, context));
+                }
+            }
+        }
+
+        rendered
+    }
+}
+
+fn value_of(placeholder: Placeholder, event: &DeploymentEvent, context: Context<'_>) -> String {
+    let deployment = event.deployment();
+    let text = |value: Option<&str>| value.map(json_escaped).unwrap_or_default();
+    let number = |value: Option<i32>| value.map(|n| n.to_string()).unwrap_or_default();
+
+    match placeholder {
+        Placeholder::Event => event.kind().as_str().to_string(),
+        Placeholder::Profile => json_escaped(context.profile_name),
+        Placeholder::At => json_escaped(context.at),
+        Placeholder::Message => json_escaped(&event.message()),
+        Placeholder::Service => text(deployment.map(|d| d.service_name.as_str())),
+        Placeholder::Keys => match event {
+            DeploymentEvent::Unreachable { keys, .. } => json_escaped(keys),
+            _ => text(deployment.map(|d| d.keys.as_str())),
+        },
+        Placeholder::Cluster => match event {
+            DeploymentEvent::Unreachable { cluster_arn, .. } => json_escaped(cluster_arn),
+            _ => text(deployment.map(|d| d.cluster_arn.as_str())),
+        },
+        Placeholder::Status => text(deployment.map(|d| d.status.as_str())),
+        Placeholder::Deployment => text(deployment.map(|d| d.deployment_id.as_str())),
+        Placeholder::Running => number(deployment.map(|d| d.running_count)),
+        Placeholder::Desired => number(deployment.map(|d| d.desired_count)),
+        Placeholder::Pending => number(deployment.map(|d| d.pending_count)),
+        Placeholder::Failed => number(deployment.map(|d| d.failed_count)),
+        Placeholder::PreviousFailed => match event {
+            DeploymentEvent::Failing {
+                previous_failed_count,
+                ..
+            } => previous_failed_count.to_string(),
+            _ => String::new(),
+        },
+        Placeholder::Error => match event {
+            DeploymentEvent::Unreachable { error, .. } => json_escaped(error),
+            _ => String::new(),
+        },
+    }
+}
+
+/// Escapes text to be put in a JSON string.
+fn json_escaped(text: &str) -> String {
+    let quoted = serde_json::Value::String(text.to_string()).to_string();
+
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn sample_event(kind: EventKind) -> DeploymentEvent {
+    let deployment = DeploymentDetails {
+        service_name: "service \"a\"".to_string(),
+        keys: "key".to_string(),
+        cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/cluster".to_string(),
+        deployment_id: "ecs-svc/1".to_string(),
+        status: "PRIMARY".to_string(),
+        running_count: 1,
+        desired_count: 2,
+        pending_count: 1,
+        failed_count: 1,
+        service_alias: None,
+    };
+
+    match kind {
+        EventKind::Started => DeploymentEvent::Started { deployment },
+        EventKind::Completed => DeploymentEvent::Completed { deployment },
+        EventKind::Failing => DeploymentEvent::Failing {
+            deployment,
+            previous_failed_count: 0,
+        },
+        EventKind::Unreachable => DeploymentEvent::Unreachable {
+            cluster_arn: deployment.cluster_arn,
+            keys: deployment.keys,
+            error: "AccessDeniedException\n\"not authorized\"".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    const CONTEXT: Context<'static> = Context {
+        profile_name: "prod",
+        at: "2026-10-19T07:00:00Z",
+    };
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn rendering_a_template_works() {
+        // GIVEN
+        let template = Template::parse(
+            r#"{"text": "{{ message }}", "service": "{{service}}", "failed": {{failed}}, "was": {{previous_failed}}, "profile": "{{profile}}", "at": "{{at}}"}"#,
+        )
+        .expect("template should've been parsed");
+        let event = sample_event(EventKind::Failing);
+
+        // WHEN
+        let rendered = template.render(&event, CONTEXT);
+
+        // THEN
+        assert_snapshot!(rendered, @r#"{"text": "service \"a\" (key): failed tasks of deployment ecs-svc/1 went up from 0 to 1", "service": "service \"a\"", "failed": 1, "was": 0, "profile": "prod", "at": "2026-10-19T07:00:00Z"}"#);
+    }
+
+    #[test]
+    fn placeholders_that_dont_apply_to_an_event_are_left_empty() {
+        // GIVEN
+        let template = Template::parse(r#"{"service": "{{service}}", "error": "{{error}}"}"#)
+            .expect("template should've been parsed");
+        let event = sample_event(EventKind::Unreachable);
+
+        // WHEN
+        let rendered = template.render(&event, CONTEXT);
+
+        // THEN
+        assert_snapshot!(rendered, @r#"{"service": "", "error": "AccessDeniedException\n\"not authorized\""}"#);
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_a_template_with_unknown_or_unclosed_placeholders_fails() {
+        // GIVEN
+        let cases = [
+            (
+                r#"{"text": "{{services}}"}"#,
+                TemplateError::UnknownPlaceholder("services".to_string()),
+            ),
+            (
+                r#"{"text": "{{message}} {{service"}"#,
+                TemplateError::UnclosedPlaceholder(22),
+            ),
+        ];
+
+        for (source, expected) in cases {
+            // WHEN
+            let result = Template::parse(source);
+
+            // THEN
+            assert_eq!(result, Err(expected), "source: {source}");
+        }
+    }
+
+    #[test]
+    fn templates_that_dont_result_in_json_for_an_event_are_rejected() {
+        // GIVEN
+        let template = Template::parse(r#"{"text": "{{message}}", "running": {{running}}}"#)
+            .expect("template should've been parsed");
+
+        // WHEN
+        let for_failing = template.validate_for(&[EventKind::Failing]);
+        let for_all = template.validate_for(&EventKind::ALL);
+
+        // THEN
+        assert!(for_failing.is_ok());
+        assert_snapshot!(
+            for_all.expect_err("validation should've failed"),
+            @r#"template doesn't result in valid JSON for "unreachable" events: expected value at line 1 column 130"#
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::common::{PROFILE_FILE_EXTENSION, PROFILES_DIR};
+use super::common::{PROFILE_FILE_EXTENSION, PROFILE_SCHEMA_URL, PROFILES_DIR};
 use crate::domain::Profile;
 use std::fs::File;
 use std::io::Error as IOError;
@@ -47,8 +47,11 @@ pub fn add_profile(
 
     let mut profile_file = File::create(&profile_path).map_err(AddProfileError::CouldntOpenFile)?;
 
+    // lets editors using taplo (eg. via even-better-toml) validate and complete the profile
+    let schema_directive = format!("#:schema {PROFILE_SCHEMA_URL}\n\n");
     profile_file
-        .write_all(SAMPLE_PROFILE)
+        .write_all(schema_directive.as_bytes())
+        .and_then(|_| profile_file.write_all(SAMPLE_PROFILE))
         .map_err(AddProfileError::CouldntWriteToFile)?;
 
     println!(
@@ -62,3 +65,34 @@ You can edit the file in your text editor, and use it via "ecscope -p {}""#,
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn added_profile_points_to_the_schema() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+
+        // WHEN
+        add_profile(dir.path(), "prof".to_string(), false).expect("profile should've been added");
+
+        // THEN
+        let contents = std::fs::read_to_string(
+            dir.path()
+                .join(PROFILES_DIR)
+                .join(format!("prof.{PROFILE_FILE_EXTENSION}")),
+        )
+        .expect("profile should've been read");
+        assert_eq!(
+            contents.lines().next(),
+            Some(format!("#:schema {PROFILE_SCHEMA_URL}").as_str())
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub(super) const PROFILES_DIR: &str = "profiles";
 pub(super) const PROFILE_FILE_EXTENSION: &str = "toml";
+/// Where the schema for profile files (as printed by `ecscope profiles schema`) is published
+pub(super) const PROFILE_SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/dhth/ecscope/main/schemas/profile.schema.json";
@@ -1,8 +1,10 @@
 mod add;
 mod common;
 mod list;
+mod schema;
 mod utils;
 
 pub use add::*;
 pub use list::*;
+pub use schema::*;
 pub use utils::*;
@@ -0,0 +1,14 @@
+use crate::config::profile_schema;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ShowProfileSchemaError {
+    #[error("couldn't serialize schema to JSON: {0}")]
+    SerializeToJson(#[from] serde_json::Error),
+}
+
+pub fn show_profile_schema() -> Result<(), ShowProfileSchemaError> {
+    let schema = serde_json::to_string_pretty(&profile_schema())?;
+    println!("{schema}");
+
+    Ok(())
+}
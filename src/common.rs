@@ -5,10 +5,16 @@ use clap::ValueEnum;
 pub enum OutputFormat {
     /// Delimited output
     Delimited,
+    /// Self-contained HTML report
+    Html,
     /// JSON output
     Json,
+    /// GitHub-flavoured markdown tables
+    Markdown,
     /// Plain output
     Plain,
+    /// Slack message (Block Kit JSON)
+    Slack,
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -16,7 +22,10 @@ impl std::fmt::Display for OutputFormat {
         let value = match self {
             OutputFormat::Plain => "plain",
             OutputFormat::Json => "json",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Slack => "slack",
             OutputFormat::Delimited => "delimited",
+            OutputFormat::Html => "html",
         };
 
         write!(f, "{value}")?;
@@ -25,7 +34,7 @@ impl std::fmt::Display for OutputFormat {
     }
 }
 
-#[derive(Clone, Debug, ValueEnum, Copy)]
+#[derive(Clone, Debug, ValueEnum, Copy, PartialEq, Eq, Hash)]
 pub enum DeploymentState {
     /// Deployment has no pending tasks
     Finished,
@@ -80,6 +89,11 @@ pub enum Environment {
     Prod,
 }
 
+/// Whether ARNs and the like are to be hidden (via ECSCOPE_REDACT=1).
+pub fn redact_mode_on() -> bool {
+    std::env::var("ECSCOPE_REDACT").unwrap_or_default() == "1"
+}
+
 pub fn get_env() -> Environment {
     match std::env::var("ECSCOPE_DEV").unwrap_or_default().as_str() {
         "1" => Environment::Dev,
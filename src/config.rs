@@ -1,24 +1,193 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
 
 use regex::Regex;
+use schemars::generate::SchemaSettings;
+use schemars::transform::RecursiveTransform;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{
     Deserialize, Deserializer,
     de::{self, Visitor},
 };
+use serde_json::Value;
 
-#[derive(Debug, Deserialize)]
+/// An ecscope profile.
+#[derive(Debug, Deserialize, JsonSchema)]
 #[cfg_attr(test, derive(serde::Serialize))]
+#[schemars(title = "ecscope profile")]
 pub struct Config {
-    pub clusters: Vec<ClusterConfig>,
+    /// Names of other profiles whose clusters (and named filters) get included in this one
+    #[serde(default)]
+    pub include: Vec<String>,
+    pub defaults: Option<ClusterDefaults>,
+    // set explicitly, as schemars can only infer defaults for types that implement Serialize
+    #[serde(default)]
+    #[schemars(extend("default" = []))]
+    pub clusters: Vec<ClusterEntry>,
+    /// Named filter expressions, usable as `--where @<name>`
+    #[serde(default)]
+    pub filters: NamedFilters,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Filter expressions by name.
+pub type NamedFilters = BTreeMap<String, String>;
+
+/// Values applied to every `[[clusters]]` entry that doesn't set them itself.
+#[derive(Debug, Deserialize, Clone, Default, JsonSchema)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct ClusterDefaults {
+    pub keys: Option<Vec<String>>,
+    pub config_source: Option<ConfigSource>,
+    pub region: Option<String>,
+}
+
+/// A `[[clusters]]` entry as written in a profile, before defaults are applied.
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct ClusterEntry {
+    /// Keys to identify the cluster by (eg. its environment)
+    pub keys: Option<Vec<String>>,
+    /// ARN of the ECS cluster
+    pub arn: String,
+    pub services: Vec<ServiceConfig>,
+    pub config_source: Option<ConfigSource>,
+    /// AWS region to use (instead of the one from the config source)
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct ClusterConfig {
     pub keys: Vec<String>,
     pub arn: String,
-    pub services: Vec<String>,
+    pub services: Vec<ServiceConfig>,
     pub config_source: ConfigSource,
+    pub region: Option<String>,
+}
+
+/// A service entry in a profile; either just the service's name, or a table with the name and
+/// additional metadata.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[serde(from = "ServiceEntry")]
+pub struct ServiceConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub metadata: ServiceMetadata,
+}
+
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq, Hash, JsonSchema, serde::Serialize)]
+pub struct ServiceMetadata {
+    /// Name to show instead of the service's actual name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runbook: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged, expecting = "either a service name, or a table with a name")]
+enum ServiceEntry {
+    Name(String),
+    Detailed {
+        name: String,
+        #[serde(flatten)]
+        metadata: ServiceMetadata,
+    },
+}
+
+impl From<ServiceEntry> for ServiceConfig {
+    fn from(entry: ServiceEntry) -> Self {
+        match entry {
+            ServiceEntry::Name(name) => Self {
+                name,
+                metadata: ServiceMetadata::default(),
+            },
+            ServiceEntry::Detailed { name, metadata } => Self { name, metadata },
+        }
+    }
+}
+
+/// A `key=value` query matched against service tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub key: String,
+    pub value: String,
+}
+
+impl std::str::FromStr for TagFilter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((k, v)) if !k.trim().is_empty() => Ok(Self {
+                key: k.trim().to_string(),
+                value: v.trim().to_string(),
+            }),
+            _ => Err(format!(
+                r#"tag filter "{value}" is not of the form key=value"#
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+/// Identifies the AWS client to be used for a cluster.
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct ClientKey {
+    pub config_source: ConfigSource,
+    pub region: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ResolveClusterError {
+    #[error(r#"cluster "{0}" has no keys, and no default keys are set"#)]
+    MissingKeys(String),
+    #[error(r#"cluster "{0}" has no config_source, and no default config_source is set"#)]
+    MissingConfigSource(String),
+}
+
+impl ClusterEntry {
+    pub fn resolve(
+        self,
+        defaults: Option<&ClusterDefaults>,
+    ) -> Result<ClusterConfig, ResolveClusterError> {
+        let keys = match self.keys {
+            Some(k) => k,
+            None => defaults
+                .and_then(|d| d.keys.clone())
+                .ok_or_else(|| ResolveClusterError::MissingKeys(self.arn.clone()))?,
+        };
+
+        let config_source = match self.config_source {
+            Some(c) => c,
+            None => defaults
+                .and_then(|d| d.config_source.clone())
+                .ok_or_else(|| ResolveClusterError::MissingConfigSource(self.arn.clone()))?,
+        };
+
+        let region = self
+            .region
+            .or_else(|| defaults.and_then(|d| d.region.clone()));
+
+        Ok(ClusterConfig {
+            keys,
+            arn: self.arn,
+            services: self.services,
+            config_source,
+            region,
+        })
+    }
 }
 
 impl<'de> Deserialize<'de> for ConfigSource {
@@ -68,7 +237,60 @@ pub enum ConfigSource {
     Profile { name: String },
 }
 
+impl JsonSchema for ConfigSource {
+    fn schema_name() -> Cow<'static, str> {
+        "ConfigSource".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Where to get AWS configuration and credentials from: \"env\" for environment variables, \"profile:<profile_name>\" for a profile in the shared AWS config files, or \"assume:<role_arn>\" to assume a role using credentials from environment variables",
+            "type": "string",
+            "pattern": "^(env|profile:.+|assume:.+)$",
+            "examples": ["env", "profile:qa", "assume:arn:aws:iam::111111111111:role/role-name"]
+        })
+    }
+}
+
+/// Returns the JSON Schema for profile files.
+pub fn profile_schema() -> Schema {
+    SchemaSettings::draft2020_12()
+        .with_transform(RecursiveTransform(remove_null))
+        .into_generator()
+        .into_root_schema_for::<Config>()
+}
+
+// TOML has no null; optional fields are already left out of "required", so the null variants
+// schemars adds for them would only clutter completions
+fn remove_null(schema: &mut Schema) {
+    if let Some(Value::Array(types)) = schema.get_mut("type") {
+        types.retain(|t| t != "null");
+        if let [only] = types.as_slice() {
+            let only = only.clone();
+            schema.insert("type".to_string(), only);
+        }
+    }
+
+    if let Some(Value::Array(variants)) = schema.get_mut("anyOf") {
+        variants.retain(|v| v.get("type").is_none_or(|t| t != "null"));
+        if let [Value::Object(only)] = variants.as_slice() {
+            let only = only.clone();
+            schema.remove("anyOf");
+            for (k, v) in only {
+                schema.insert(k, v);
+            }
+        }
+    }
+}
+
 impl ClusterConfig {
+    pub fn client_key(&self) -> ClientKey {
+        ClientKey {
+            config_source: self.config_source.clone(),
+            region: self.region.clone(),
+        }
+    }
+
     pub fn filter_by_cluster_key(self, re: &Regex) -> Option<Self> {
         for key in &self.keys {
             if re.is_match(key) {
@@ -84,7 +306,26 @@ impl ClusterConfig {
             .services
             .clone()
             .into_iter()
-            .filter(|s| re.is_match(s))
+            .filter(|s| {
+                re.is_match(&s.name) || s.metadata.alias.as_ref().is_some_and(|a| re.is_match(a))
+            })
+            .collect::<Vec<_>>();
+
+        if filtered_services.is_empty() {
+            return None;
+        }
+
+        self.services = filtered_services;
+
+        Some(self)
+    }
+
+    pub fn filter_by_tags(mut self, filters: &[TagFilter]) -> Option<Self> {
+        let filtered_services = self
+            .services
+            .clone()
+            .into_iter()
+            .filter(|s| s.matches_tags(filters))
             .collect::<Vec<_>>();
 
         if filtered_services.is_empty() {
@@ -95,6 +336,26 @@ impl ClusterConfig {
 
         Some(self)
     }
+
+    pub fn service_names(&self) -> Vec<String> {
+        self.services.iter().map(|s| s.name.clone()).collect()
+    }
+
+    pub fn service_metadata(&self, service_name: &str) -> ServiceMetadata {
+        self.services
+            .iter()
+            .find(|s| s.name == service_name)
+            .map(|s| s.metadata.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl ServiceConfig {
+    pub fn matches_tags(&self, filters: &[TagFilter]) -> bool {
+        filters
+            .iter()
+            .all(|f| self.metadata.tags.get(&f.key) == Some(&f.value))
+    }
 }
 
 #[cfg(test)]
@@ -148,39 +409,209 @@ config_source = "assume:arn:aws:iam::222222222222:role/role-name"
 
         // THEN
         assert_yaml_snapshot!(config, @r#"
+        include: []
+        defaults: ~
         clusters:
           - keys:
               - qa
             arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/urlpreview-2-cluster-qa"
             services:
-              - service-a
-              - service-b
+              - name: service-a
+              - name: service-b
             config_source: Env
+            region: ~
           - keys:
               - qa
             arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prlserver-cluster-qa"
             services:
-              - service-c
-              - service-d
+              - name: service-c
+              - name: service-d
             config_source:
               Profile:
                 name: qa
+            region: ~
           - keys:
               - qa
             arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prlserver-cluster-qa"
             services:
-              - service-c
-              - service-d
+              - name: service-c
+              - name: service-d
             config_source:
               AssumeRole:
                 role_arn: "arn:aws:iam::222222222222:role/role-name"
+            region: ~
+        filters: {}
+        "#);
+    }
+
+    #[test]
+    fn published_profile_schema_is_up_to_date() {
+        // GIVEN
+        let published = include_str!("../schemas/profile.schema.json");
+
+        // WHEN
+        let schema = serde_json::to_string_pretty(&profile_schema())
+            .expect("schema should've been serialized");
+
+        // THEN
+        assert_eq!(
+            published.trim_end(),
+            schema,
+            "schemas/profile.schema.json is stale; regenerate it with `ecscope profiles schema`"
+        );
+    }
+
+    #[test]
+    fn sample_profile_is_a_valid_profile() {
+        // GIVEN
+        let sample = include_str!("cmds/profiles/static/sample-profile.toml");
+
+        // WHEN
+        let result = toml::from_str::<Config>(sample);
+
+        // THEN
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn defaults_are_applied_to_clusters_that_dont_override_them() {
+        // GIVEN
+        let config = r#"
+include = ["payments"]
+
+[defaults]
+keys = ["qa"]
+config_source = "profile:qa"
+region = "eu-central-1"
+
+[[clusters]]
+arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/cluster-a"
+services = ["service-a"]
+
+[[clusters]]
+keys = ["prod"]
+arn = "arn:aws:ecs:us-east-1:222222222222:cluster/cluster-b"
+services = ["service-b"]
+config_source = "env"
+region = "us-east-1"
+"#;
+        let config: Config = toml::from_str(config).expect("config should've been deserialized");
+
+        // WHEN
+        let clusters = config
+            .clusters
+            .into_iter()
+            .map(|c| c.resolve(config.defaults.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("clusters should've been resolved");
+
+        // THEN
+        assert_eq!(config.include, vec!["payments".to_string()]);
+        assert_yaml_snapshot!(clusters, @r#"
+        - keys:
+            - qa
+          arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/cluster-a"
+          services:
+            - name: service-a
+          config_source:
+            Profile:
+              name: qa
+          region: eu-central-1
+        - keys:
+            - prod
+          arn: "arn:aws:ecs:us-east-1:222222222222:cluster/cluster-b"
+          services:
+            - name: service-b
+          config_source: Env
+          region: us-east-1
         "#);
     }
 
+    #[test]
+    fn deserializing_services_with_metadata_works() {
+        // GIVEN
+        let config = r#"
+[[clusters]]
+keys = ["prod"]
+arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/payments"
+services = [
+  "service-a",
+  { name = "svc-pmt-api-v2-blue", alias = "payments-api", owner = "team-payments", runbook = "https://runbooks.example.com/payments-api", tags = { team = "payments", tier = "1" } },
+]
+config_source = "env"
+"#;
+
+        // WHEN
+        let config: Config = toml::from_str(config).expect("config should've been deserialized");
+
+        // THEN
+        assert_yaml_snapshot!(config.clusters[0].services, @r#"
+        - name: service-a
+        - name: svc-pmt-api-v2-blue
+          alias: payments-api
+          tags:
+            team: payments
+            tier: "1"
+          owner: team-payments
+          runbook: "https://runbooks.example.com/payments-api"
+        "#);
+    }
+
+    #[test]
+    fn filtering_by_tags_only_keeps_services_matching_all_tags() {
+        // GIVEN
+        let config = r#"
+[[clusters]]
+keys = ["prod"]
+arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/payments"
+services = [
+  "service-a",
+  { name = "service-b", tags = { team = "payments", tier = "1" } },
+  { name = "service-c", tags = { team = "payments", tier = "2" } },
+]
+config_source = "env"
+"#;
+        let config: Config = toml::from_str(config).expect("config should've been deserialized");
+        let cluster = config
+            .clusters
+            .into_iter()
+            .next()
+            .expect("config should've had a cluster")
+            .resolve(None)
+            .expect("cluster should've been resolved");
+        let filters = ["team=payments", "tier=1"]
+            .iter()
+            .map(|f| f.parse::<TagFilter>())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("tag filters should've been parsed");
+
+        // WHEN
+        let filtered = cluster
+            .filter_by_tags(&filters)
+            .expect("cluster should've matched");
+
+        // THEN
+        assert_eq!(filtered.service_names(), vec!["service-b".to_string()]);
+    }
+
     //------------//
     //  FAILURES  //
     //------------//
 
+    #[test]
+    fn parsing_incorrect_tag_filter_fails() {
+        // GIVEN
+        let filters = ["team", "=payments", ""];
+
+        for filter in filters {
+            // WHEN
+            let result = filter.parse::<TagFilter>();
+
+            // THEN
+            assert!(result.is_err());
+        }
+    }
+
     #[test]
     fn deserializing_incorrect_cluster_config_fails() {
         // GIVEN
@@ -206,10 +637,34 @@ config_source = "unknown"
 
         for config in bad_configs {
             // WHEN
-            let result: Result<ClusterConfig, toml::de::Error> = toml::from_str(config);
+            let result: Result<Config, toml::de::Error> = toml::from_str(config);
 
             // THEN
             assert!(result.is_err());
         }
     }
+
+    #[test]
+    fn resolving_cluster_without_config_source_or_default_fails() {
+        // GIVEN
+        let config = r#"
+[defaults]
+keys = ["qa"]
+
+[[clusters]]
+arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/cluster-a"
+services = ["service-a"]
+"#;
+        let config: Config = toml::from_str(config).expect("config should've been deserialized");
+        let entry = config.clusters[0].clone();
+
+        // WHEN
+        let result = entry.resolve(config.defaults.as_ref());
+
+        // THEN
+        assert!(matches!(
+            result,
+            Err(ResolveClusterError::MissingConfigSource(_))
+        ));
+    }
 }
@@ -1,14 +1,26 @@
 use crate::args::Args;
+use crate::utils::{LocateProfileError, ProfileSource};
 use std::path::Path;
 
-pub fn display_debug_info(args: &Args, config_dir: &Path) {
+pub fn display_debug_info(
+    args: &Args,
+    config_dir: &Path,
+    profile_source: Option<Result<ProfileSource, LocateProfileError>>,
+) {
+    let profile_source = match profile_source {
+        Some(Ok(source)) => format!("\nprofile source:      {source}"),
+        Some(Err(e)) => format!("\nprofile source:      <{e}>"),
+        None => String::new(),
+    };
+
     println!(
         r#"DEBUG INFO:
 
 [your arguments]{}
 [computed config]
-config directory:    {}"#,
+config directory:    {}{}"#,
         args,
-        config_dir.to_string_lossy()
+        config_dir.to_string_lossy(),
+        profile_source,
     )
 }
@@ -0,0 +1,194 @@
+/// The parts of an ECS ARN, eg. `arn:aws:ecs:eu-central-1:111111111111:cluster/prod`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arn<'a> {
+    pub partition: &'a str,
+    pub region: &'a str,
+    pub account: &'a str,
+    /// Everything after the account, eg. `cluster/prod` or `task/prod/0123abcd`
+    pub resource: &'a str,
+}
+
+impl<'a> Arn<'a> {
+    pub fn parse(arn: &'a str) -> Option<Self> {
+        let mut parts = arn.splitn(6, ':');
+        let (Some("arn"), Some(partition), Some(_), Some(region), Some(account), Some(resource)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return None;
+        };
+
+        if partition.is_empty() || region.is_empty() || resource.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            partition,
+            region,
+            account,
+            resource,
+        })
+    }
+
+    /// The last component of the resource, eg. the cluster's name for a cluster ARN.
+    pub fn resource_name(&self) -> &'a str {
+        self.resource.rsplit('/').next().unwrap_or(self.resource)
+    }
+
+    fn console_base_url(&self) -> String {
+        let domain = match self.partition {
+            "aws-cn" => "console.amazonaws.cn",
+            "aws-us-gov" => "console.amazonaws-us-gov.com",
+            _ => "console.aws.amazon.com",
+        };
+
+        format!("https://{}.{domain}/ecs/v2", self.region)
+    }
+}
+
+/// Returns the ARN of a service, given the ARN of its cluster.
+pub fn service_arn(cluster_arn: &str, service_name: &str) -> Option<String> {
+    let cluster = Arn::parse(cluster_arn)?;
+
+    Some(format!(
+        "arn:{}:ecs:{}:{}:service/{}/{service_name}",
+        cluster.partition,
+        cluster.region,
+        cluster.account,
+        cluster.resource_name()
+    ))
+}
+
+/// Returns the AWS console's URL for a service, given the ARN of its cluster.
+pub fn service_console_url(cluster_arn: &str, service_name: &str) -> Option<String> {
+    let cluster = Arn::parse(cluster_arn)?;
+
+    Some(format!(
+        "{}/clusters/{}/services/{service_name}/health?region={}",
+        cluster.console_base_url(),
+        cluster.resource_name(),
+        cluster.region
+    ))
+}
+
+/// Returns the AWS console's URL for a task, given its ARN (which looks like
+/// `arn:aws:ecs:<region>:<account>:task/<cluster>/<task-id>`).
+pub fn task_console_url(task_arn: &str) -> Option<String> {
+    let task = Arn::parse(task_arn)?;
+    let mut resource = task.resource.split('/');
+    let (Some("task"), Some(cluster), Some(task_id), None) = (
+        resource.next(),
+        resource.next(),
+        resource.next(),
+        resource.next(),
+    ) else {
+        return None;
+    };
+
+    Some(format!(
+        "{}/clusters/{cluster}/tasks/{task_id}/configuration?region={}",
+        task.console_base_url(),
+        task.region
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_an_arn_works() {
+        // GIVEN
+        let cluster_arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/prod";
+
+        // WHEN
+        let arn = Arn::parse(cluster_arn);
+
+        // THEN
+        assert_eq!(
+            arn,
+            Some(Arn {
+                partition: "aws",
+                region: "eu-central-1",
+                account: "111111111111",
+                resource: "cluster/prod",
+            })
+        );
+    }
+
+    #[test]
+    fn service_arns_and_urls_are_derived_from_the_cluster_arn() {
+        // GIVEN
+        let cluster_arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/prod";
+
+        // WHEN
+        let arn = service_arn(cluster_arn, "payments-api");
+        let url = service_console_url(cluster_arn, "payments-api");
+
+        // THEN
+        assert_eq!(
+            arn.as_deref(),
+            Some("arn:aws:ecs:eu-central-1:111111111111:service/prod/payments-api")
+        );
+        assert_eq!(
+            url.as_deref(),
+            Some(
+                "https://eu-central-1.console.aws.amazon.com/ecs/v2/clusters/prod/services/payments-api/health?region=eu-central-1"
+            )
+        );
+    }
+
+    #[test]
+    fn task_urls_use_the_console_of_the_arns_partition() {
+        // GIVEN
+        let task_arn = "arn:aws-cn:ecs:cn-north-1:111111111111:task/prod/0123abcd";
+
+        // WHEN
+        let url = task_console_url(task_arn);
+
+        // THEN
+        assert_eq!(
+            url.as_deref(),
+            Some(
+                "https://cn-north-1.console.amazonaws.cn/ecs/v2/clusters/prod/tasks/0123abcd/configuration?region=cn-north-1"
+            )
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn invalid_arns_are_rejected() {
+        // GIVEN
+        let cases = [
+            "",
+            "prod",
+            "arn:aws:ecs:eu-central-1:111111111111",
+            "arn:aws:ecs::111111111111:cluster/prod",
+            "urn:aws:ecs:eu-central-1:111111111111:cluster/prod",
+        ];
+
+        for arn in cases {
+            // WHEN
+            let result = Arn::parse(arn);
+
+            // THEN
+            assert_eq!(result, None, "arn: {arn}");
+        }
+
+        assert_eq!(
+            task_console_url("arn:aws:ecs:eu-central-1:111111111111:task/0123abcd"),
+            None
+        );
+    }
+}
@@ -1,9 +1,13 @@
-#[derive(Debug, Clone, serde::Serialize, PartialEq, Eq)]
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq, Eq, schemars::JsonSchema)]
 pub struct DeploymentError {
     pub service_name: String,
     pub error: String,
     pub cluster_arn: String,
     pub keys: String,
+    /// The service's alias, if one is configured in the profile
+    pub service_alias: Option<String>,
 }
 
 impl std::fmt::Display for DeploymentError {
@@ -16,13 +20,23 @@ Cluster ARN : {}
 Keys        : {:?}
 Error       : {}"
 "#,
-            self.service_name, self.cluster_arn, self.keys, self.error,
+            self.service_label(),
+            self.cluster_arn,
+            self.keys,
+            self.error,
         )?;
 
         Ok(())
     }
 }
 
+impl DeploymentError {
+    /// The service's alias followed by its name if an alias is configured, its name otherwise.
+    pub fn service_label(&self) -> Cow<'_, str> {
+        service_label(&self.service_name, self.service_alias.as_deref())
+    }
+}
+
 impl Ord for DeploymentError {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.service_name
@@ -39,7 +53,7 @@ impl PartialOrd for DeploymentError {
 
 pub type DeploymentResult = Result<DeploymentDetails, DeploymentError>;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, serde::Serialize)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, serde::Serialize, schemars::JsonSchema)]
 pub struct DeploymentDetails {
     pub service_name: String,
     pub keys: String,
@@ -50,6 +64,8 @@ pub struct DeploymentDetails {
     pub desired_count: i32,
     pub pending_count: i32,
     pub failed_count: i32,
+    /// The service's alias, if one is configured in the profile
+    pub service_alias: Option<String>,
 }
 
 impl Ord for DeploymentDetails {
@@ -67,6 +83,11 @@ impl PartialOrd for DeploymentDetails {
 }
 
 impl DeploymentDetails {
+    /// The service's alias followed by its name if an alias is configured, its name otherwise.
+    pub fn service_label(&self) -> Cow<'_, str> {
+        service_label(&self.service_name, self.service_alias.as_deref())
+    }
+
     pub fn dummy_running(name: &str, keys: &str) -> Self {
         Self {
             service_name: name.to_string(),
@@ -78,6 +99,7 @@ impl DeploymentDetails {
             desired_count: 2,
             pending_count: 0,
             failed_count: 0,
+            service_alias: None,
         }
     }
 
@@ -92,6 +114,7 @@ impl DeploymentDetails {
             desired_count: 2,
             pending_count: 2,
             failed_count: 0,
+            service_alias: None,
         }
     }
 
@@ -106,6 +129,7 @@ impl DeploymentDetails {
             desired_count: 0,
             pending_count: 0,
             failed_count: 0,
+            service_alias: None,
         }
     }
 
@@ -120,6 +144,7 @@ impl DeploymentDetails {
             desired_count: 2,
             pending_count: 2,
             failed_count: 3,
+            service_alias: None,
         }
     }
 
@@ -134,6 +159,7 @@ impl DeploymentDetails {
             desired_count: 0,
             pending_count: 0,
             failed_count: 0,
+            service_alias: None,
         }
     }
 }
@@ -153,7 +179,7 @@ Desired count  : {}
 Pending count  : {}
 Failed tasks   : {}
 "#,
-            self.service_name,
+            self.service_label(),
             self.keys,
             self.cluster_arn,
             self.deployment_id,
@@ -167,3 +193,12 @@ Failed tasks   : {}
         Ok(())
     }
 }
+
+/// Aliases are what people know services by, but names are what they're known as in AWS; eg.
+/// "payments-api (svc-pmt-api-v2-blue)".
+fn service_label<'a>(name: &'a str, alias: Option<&str>) -> Cow<'a, str> {
+    match alias {
+        Some(alias) => Cow::Owned(format!("{alias} ({name})")),
+        None => Cow::Borrowed(name),
+    }
+}
@@ -0,0 +1,817 @@
+use super::{DeploymentDetails, ServiceDetails};
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeMap;
+
+const NAMED_FILTER_PREFIX: char = '@';
+// filters are parsed recursively, and can come from requests to the server
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// A filter expression evaluated over fetched results, eg.
+/// `key=prod and status!=PRIMARY and failed>0`, `running<desired` or `cluster~payments`.
+///
+/// Comparisons can be combined with `and`, `or` and `not`, and grouped with parentheses. The
+/// right hand side of a comparison is either a number, a (possibly quoted) string, or the name of
+/// another field.
+#[derive(Debug, Clone)]
+pub struct FilterExpr {
+    source: String,
+    node: Node,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Service,
+    Key,
+    Cluster,
+    Status,
+    Deployment,
+    Running,
+    Desired,
+    Pending,
+    Failed,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        let field = match name.to_lowercase().as_str() {
+            "service" | "name" => Field::Service,
+            "key" | "keys" => Field::Key,
+            "cluster" => Field::Cluster,
+            "status" => Field::Status,
+            "deployment" => Field::Deployment,
+            "running" => Field::Running,
+            "desired" => Field::Desired,
+            "pending" => Field::Pending,
+            "failed" => Field::Failed,
+            _ => return None,
+        };
+
+        Some(field)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Field::Service => "service",
+            Field::Key => "key",
+            Field::Cluster => "cluster",
+            Field::Status => "status",
+            Field::Deployment => "deployment",
+            Field::Running => "running",
+            Field::Desired => "desired",
+            Field::Pending => "pending",
+            Field::Failed => "failed",
+        }
+    }
+}
+
+pub enum FieldValue<'a> {
+    Number(i64),
+    Text(&'a str),
+    List(Vec<&'a str>),
+}
+
+/// Something a [`FilterExpr`] can be evaluated against.
+pub trait Filterable {
+    /// What the items are called, in errors
+    const KIND: &'static str;
+
+    /// Whether items of this kind have a field at all; filters referring to fields they don't
+    /// have are rejected, rather than never matching.
+    fn has_field(_field: Field) -> bool {
+        true
+    }
+
+    /// Returns the value of a field, or `None` if it's not applicable.
+    fn field(&self, field: Field) -> Option<FieldValue<'_>>;
+}
+
+impl Filterable for DeploymentDetails {
+    const KIND: &'static str = "deployments";
+
+    fn field(&self, field: Field) -> Option<FieldValue<'_>> {
+        let value = match field {
+            Field::Service => FieldValue::Text(&self.service_name),
+            Field::Key => FieldValue::List(self.keys.split(',').collect()),
+            Field::Cluster => FieldValue::Text(&self.cluster_arn),
+            Field::Status => FieldValue::Text(&self.status),
+            Field::Deployment => FieldValue::Text(&self.deployment_id),
+            Field::Running => FieldValue::Number(self.running_count.into()),
+            Field::Desired => FieldValue::Number(self.desired_count.into()),
+            Field::Pending => FieldValue::Number(self.pending_count.into()),
+            Field::Failed => FieldValue::Number(self.failed_count.into()),
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for ServiceDetails {
+    const KIND: &'static str = "services";
+
+    fn has_field(field: Field) -> bool {
+        !matches!(field, Field::Deployment | Field::Failed)
+    }
+
+    fn field(&self, field: Field) -> Option<FieldValue<'_>> {
+        let value = match field {
+            Field::Service => FieldValue::Text(&self.name),
+            Field::Key => FieldValue::List(self.cluster_keys.iter().map(String::as_str).collect()),
+            Field::Cluster => FieldValue::Text(&self.cluster_arn),
+            Field::Status => FieldValue::Text(&self.status),
+            Field::Deployment => return None,
+            Field::Running => FieldValue::Number(self.running_count.into()),
+            Field::Desired => FieldValue::Number(self.desired_count.into()),
+            Field::Pending => FieldValue::Number(self.pending_count.into()),
+            Field::Failed => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Comparison(Field, Op, Operand),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Matches,
+    DoesntMatch,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Field(Field),
+    Number(i64),
+    Text(String),
+    Pattern(Regex),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum FilterParseError {
+    #[error("filter is empty")]
+    Empty,
+    #[error("unterminated string starting at position {0}")]
+    UnterminatedString(usize),
+    #[error(r#"unexpected character "{0}" at position {1}"#)]
+    UnexpectedCharacter(char, usize),
+    #[error(r#"unknown field "{0}"; valid fields: service, key, cluster, status, deployment, running, desired, pending, failed"#)]
+    UnknownField(String),
+    #[error(r#"expected {0}, found "{1}""#)]
+    Unexpected(&'static str, String),
+    #[error("expected {0}, found end of filter")]
+    UnexpectedEnd(&'static str),
+    #[error(r#"invalid pattern "{0}": {1}"#)]
+    InvalidPattern(String, String),
+    #[error(r#"operator "{0}" needs a number or a numeric field on its right hand side"#)]
+    NonNumericOperand(String),
+    #[error(r#"no filter named "{0}" in the profile"#)]
+    UnknownNamedFilter(String),
+    #[error(r#"field "{0}" isn't available for {1}"#)]
+    UnsupportedField(&'static str, &'static str),
+    #[error(r#"named filter "{0}" is invalid: {1}"#)]
+    InvalidNamedFilter(String, Box<FilterParseError>),
+    #[error(
+        "filter is nested too deeply (at most {0} levels of parentheses and \"not\" are allowed)"
+    )]
+    TooDeeplyNested(usize),
+}
+
+impl FilterExpr {
+    pub fn parse(input: &str) -> Result<Self, FilterParseError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(FilterParseError::Empty);
+        }
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let node = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(FilterParseError::Unexpected(
+                "end of filter",
+                token.to_string(),
+            ));
+        }
+
+        Ok(Self {
+            source: input.trim().to_string(),
+            node,
+        })
+    }
+
+    /// Parses a filter, resolving `@<name>` to the named filter with that name.
+    pub fn parse_with_named(
+        input: &str,
+        named_filters: &BTreeMap<String, String>,
+    ) -> Result<Self, FilterParseError> {
+        match named_filter_reference(input) {
+            Some(name) => {
+                let expr = named_filters
+                    .get(name)
+                    .ok_or_else(|| FilterParseError::UnknownNamedFilter(name.to_string()))?;
+                Self::parse(expr).map_err(|e| {
+                    FilterParseError::InvalidNamedFilter(name.to_string(), Box::new(e))
+                })
+            }
+            None => Self::parse(input),
+        }
+    }
+
+    /// Checks that the filter only refers to fields that items of a kind have.
+    pub fn supported_by<T: Filterable>(self) -> Result<Self, FilterParseError> {
+        match self.node.fields().into_iter().find(|f| !T::has_field(*f)) {
+            Some(field) => Err(FilterParseError::UnsupportedField(field.as_str(), T::KIND)),
+            None => Ok(self),
+        }
+    }
+
+    pub fn matches<T: Filterable>(&self, item: &T) -> bool {
+        self.node.evaluate(item)
+    }
+}
+
+impl std::fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Returns the name referenced by input of the form `@<name>`.
+pub fn named_filter_reference(input: &str) -> Option<&str> {
+    input.trim().strip_prefix(NAMED_FILTER_PREFIX)
+}
+
+impl Node {
+    fn fields(&self) -> Vec<Field> {
+        match self {
+            Node::And(l, r) | Node::Or(l, r) => {
+                let mut fields = l.fields();
+                fields.extend(r.fields());
+                fields
+            }
+            Node::Not(n) => n.fields(),
+            Node::Comparison(field, _, Operand::Field(other)) => vec![*field, *other],
+            Node::Comparison(field, _, _) => vec![*field],
+        }
+    }
+
+    fn evaluate<T: Filterable>(&self, item: &T) -> bool {
+        match self {
+            Node::And(l, r) => l.evaluate(item) && r.evaluate(item),
+            Node::Or(l, r) => l.evaluate(item) || r.evaluate(item),
+            Node::Not(n) => !n.evaluate(item),
+            Node::Comparison(field, op, operand) => {
+                let Some(lhs) = item.field(*field) else {
+                    return false;
+                };
+                compare(&lhs, *op, operand, item)
+            }
+        }
+    }
+}
+
+fn compare<T: Filterable>(lhs: &FieldValue<'_>, op: Op, operand: &Operand, item: &T) -> bool {
+    match operand {
+        Operand::Field(other) => match item.field(*other) {
+            Some(FieldValue::Number(n)) => compare_number(lhs, op, n),
+            Some(FieldValue::Text(t)) => compare_text(lhs, op, t),
+            Some(FieldValue::List(l)) => l.iter().any(|t| compare_text(lhs, op, t)),
+            None => false,
+        },
+        Operand::Number(n) => compare_number(lhs, op, *n),
+        Operand::Text(t) => compare_text(lhs, op, t),
+        Operand::Pattern(re) => {
+            let is_match = match lhs {
+                FieldValue::Number(n) => re.is_match(&n.to_string()),
+                FieldValue::Text(t) => re.is_match(t),
+                FieldValue::List(l) => l.iter().any(|t| re.is_match(t)),
+            };
+            match op {
+                Op::DoesntMatch => !is_match,
+                _ => is_match,
+            }
+        }
+    }
+}
+
+fn compare_number(lhs: &FieldValue<'_>, op: Op, rhs: i64) -> bool {
+    let lhs = match lhs {
+        FieldValue::Number(n) => *n,
+        FieldValue::Text(t) => match t.parse::<i64>() {
+            Ok(n) => n,
+            Err(_) => return compare_text(&FieldValue::Text(t), op, &rhs.to_string()),
+        },
+        FieldValue::List(l) => {
+            return l
+                .iter()
+                .any(|t| compare_number(&FieldValue::Text(t), op, rhs));
+        }
+    };
+
+    match op {
+        Op::Eq => lhs == rhs,
+        Op::NotEq => lhs != rhs,
+        Op::Lt => lhs < rhs,
+        Op::Lte => lhs <= rhs,
+        Op::Gt => lhs > rhs,
+        Op::Gte => lhs >= rhs,
+        Op::Matches | Op::DoesntMatch => false,
+    }
+}
+
+fn compare_text(lhs: &FieldValue<'_>, op: Op, rhs: &str) -> bool {
+    match lhs {
+        FieldValue::Number(_) => match rhs.parse::<i64>() {
+            Ok(r) => compare_number(lhs, op, r),
+            Err(_) => op == Op::NotEq,
+        },
+        FieldValue::Text(t) => match op {
+            Op::Eq => t.eq_ignore_ascii_case(rhs),
+            Op::NotEq => !t.eq_ignore_ascii_case(rhs),
+            _ => false,
+        },
+        FieldValue::List(l) => match op {
+            Op::Eq => l.iter().any(|t| t.eq_ignore_ascii_case(rhs)),
+            Op::NotEq => !l.iter().any(|t| t.eq_ignore_ascii_case(rhs)),
+            _ => false,
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "{w}"),
+            Token::Quoted(q) => write!(f, "\"{q}\""),
+            Token::Op(op) => write!(f, "{}", op.as_str()),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+impl Op {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::NotEq => "!=",
+            Op::Lt => "<",
+            Op::Lte => "<=",
+            Op::Gt => ">",
+            Op::Gte => ">=",
+            Op::Matches => "~",
+            Op::DoesntMatch => "!~",
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '=' | '!' | '<' | '>' | '~' | '(' | ')' | '"' | '\'')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterParseError> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let start = i;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some(ch) if *ch == c => break,
+                        Some('\\') if chars.get(i + 1) == Some(&c) => {
+                            value.push(c);
+                            i += 2;
+                        }
+                        Some(ch) => {
+                            value.push(*ch);
+                            i += 1;
+                        }
+                        None => return Err(FilterParseError::UnterminatedString(start)),
+                    }
+                }
+                i += 1;
+                tokens.push(Token::Quoted(value));
+            }
+            '=' => {
+                // "==" is accepted as an alias for "="
+                i += if next == Some('=') { 2 } else { 1 };
+                tokens.push(Token::Op(Op::Eq));
+            }
+            '!' => match next {
+                Some('=') => {
+                    tokens.push(Token::Op(Op::NotEq));
+                    i += 2;
+                }
+                Some('~') => {
+                    tokens.push(Token::Op(Op::DoesntMatch));
+                    i += 2;
+                }
+                _ => return Err(FilterParseError::UnexpectedCharacter(c, i)),
+            },
+            '<' | '>' => {
+                let op = match (c, next == Some('=')) {
+                    ('<', true) => Op::Lte,
+                    ('<', false) => Op::Lt,
+                    (_, true) => Op::Gte,
+                    (_, false) => Op::Gt,
+                };
+                i += if next == Some('=') { 2 } else { 1 };
+                tokens.push(Token::Op(op));
+            }
+            '~' => {
+                tokens.push(Token::Op(Op::Matches));
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && is_word_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// How many parentheses and "not"s the parser is currently inside of
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Node, FilterParseError> {
+        let mut node = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            node = Node::Or(Box::new(node), Box::new(rhs));
+        }
+
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, FilterParseError> {
+        let mut node = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            node = Node::And(Box::new(node), Box::new(rhs));
+        }
+
+        Ok(node)
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, FilterParseError> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            let node = self.nested(Self::parse_unary)?;
+            return Ok(Node::Not(Box::new(node)));
+        }
+
+        match self.next() {
+            Some(Token::LParen) => {
+                let node = self.nested(Self::parse_or)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(node),
+                    Some(t) => Err(FilterParseError::Unexpected(r#"")""#, t.to_string())),
+                    None => Err(FilterParseError::UnexpectedEnd(r#"")""#)),
+                }
+            }
+            Some(Token::Word(w)) => {
+                let field = Field::from_name(&w)
+                    .ok_or_else(|| FilterParseError::UnknownField(w.clone()))?;
+                self.parse_comparison(field)
+            }
+            Some(t) => Err(FilterParseError::Unexpected("a field", t.to_string())),
+            None => Err(FilterParseError::UnexpectedEnd("a field")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Node, FilterParseError>,
+    ) -> Result<Node, FilterParseError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(FilterParseError::TooDeeplyNested(MAX_NESTING_DEPTH));
+        }
+        self.depth += 1;
+        let node = parse(self);
+        self.depth -= 1;
+
+        node
+    }
+
+    fn parse_comparison(&mut self, field: Field) -> Result<Node, FilterParseError> {
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            Some(t) => return Err(FilterParseError::Unexpected("an operator", t.to_string())),
+            None => return Err(FilterParseError::UnexpectedEnd("an operator")),
+        };
+
+        let operand = match self.next() {
+            Some(Token::Word(w)) => match Field::from_name(&w) {
+                Some(f) if !matches!(op, Op::Matches | Op::DoesntMatch) => Operand::Field(f),
+                _ => match w.parse::<i64>() {
+                    Ok(n) => Operand::Number(n),
+                    Err(_) => Operand::Text(w),
+                },
+            },
+            Some(Token::Quoted(q)) => Operand::Text(q),
+            Some(t) => return Err(FilterParseError::Unexpected("a value", t.to_string())),
+            None => return Err(FilterParseError::UnexpectedEnd("a value")),
+        };
+
+        let operand = match (op, operand) {
+            (Op::Matches | Op::DoesntMatch, Operand::Text(p)) => {
+                let re = RegexBuilder::new(&p)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| FilterParseError::InvalidPattern(p.clone(), e.to_string()))?;
+                Operand::Pattern(re)
+            }
+            (Op::Matches | Op::DoesntMatch, Operand::Number(n)) => {
+                let re = Regex::new(&n.to_string())
+                    .map_err(|e| FilterParseError::InvalidPattern(n.to_string(), e.to_string()))?;
+                Operand::Pattern(re)
+            }
+            (Op::Lt | Op::Lte | Op::Gt | Op::Gte, Operand::Text(_)) => {
+                return Err(FilterParseError::NonNumericOperand(op.as_str().to_string()));
+            }
+            (_, operand) => operand,
+        };
+
+        Ok(Node::Comparison(field, op, operand))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(
+        keys: &str,
+        status: &str,
+        running: i32,
+        desired: i32,
+        failed: i32,
+    ) -> DeploymentDetails {
+        DeploymentDetails {
+            service_name: "payments-api".to_string(),
+            keys: keys.to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/payments-prod".to_string(),
+            deployment_id: "ecs-svc/1".to_string(),
+            status: status.to_string(),
+            running_count: running,
+            desired_count: desired,
+            pending_count: 0,
+            failed_count: failed,
+            service_alias: None,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn filters_are_evaluated_correctly() {
+        // GIVEN
+        let healthy = deployment("prod", "PRIMARY", 2, 2, 0);
+        let failing = deployment("prod", "ACTIVE", 1, 2, 3);
+        let qa_failing = deployment("qa", "ACTIVE", 1, 2, 3);
+
+        let cases = [
+            (
+                "key=prod and status!=PRIMARY and failed>0",
+                [false, true, false],
+            ),
+            ("running<desired", [false, true, true]),
+            ("cluster~payments", [true, true, true]),
+            ("cluster!~PAYMENTS", [false, false, false]),
+            ("key=qa or failed=0", [true, false, true]),
+            ("not (key=prod and failed>=3)", [true, false, true]),
+            (r#"status="primary""#, [true, false, false]),
+            ("running>=desired", [true, false, false]),
+        ];
+
+        for (filter, expected) in cases {
+            // WHEN
+            let expr = FilterExpr::parse(filter).expect("filter should've been parsed");
+            let results = [
+                expr.matches(&healthy),
+                expr.matches(&failing),
+                expr.matches(&qa_failing),
+            ];
+
+            // THEN
+            assert_eq!(results, expected, "filter: {filter}");
+        }
+    }
+
+    #[test]
+    fn key_comparisons_consider_each_key() {
+        // GIVEN
+        let deployment = deployment("qa,staging", "PRIMARY", 2, 2, 0);
+
+        // WHEN
+        let eq = FilterExpr::parse("key=staging").expect("filter should've been parsed");
+        let not_eq = FilterExpr::parse("key!=staging").expect("filter should've been parsed");
+
+        // THEN
+        assert!(eq.matches(&deployment));
+        assert!(!not_eq.matches(&deployment));
+    }
+
+    #[test]
+    fn only_keys_are_split_on_commas() {
+        // GIVEN
+        let deployment = DeploymentDetails {
+            service_name: "a,b".to_string(),
+            ..deployment("qa,staging", "PRIMARY", 2, 2, 0)
+        };
+
+        // WHEN
+        let part = FilterExpr::parse("service=b").expect("filter should've been parsed");
+        let not_part = FilterExpr::parse("service!=b").expect("filter should've been parsed");
+        let whole = FilterExpr::parse(r#"service="a,b""#).expect("filter should've been parsed");
+
+        // THEN
+        assert!(!part.matches(&deployment));
+        assert!(not_part.matches(&deployment));
+        assert!(whole.matches(&deployment));
+    }
+
+    #[test]
+    fn named_filters_are_resolved() {
+        // GIVEN
+        let named = BTreeMap::from([("failing".to_string(), "failed>0".to_string())]);
+
+        // WHEN
+        let expr =
+            FilterExpr::parse_with_named("@failing", &named).expect("filter should've been parsed");
+
+        // THEN
+        assert_eq!(expr.to_string(), "failed>0");
+        assert!(expr.matches(&deployment("prod", "ACTIVE", 1, 2, 3)));
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_invalid_filters_fails() {
+        // GIVEN
+        let cases = [
+            ("", FilterParseError::Empty),
+            (
+                "colour=red",
+                FilterParseError::UnknownField("colour".to_string()),
+            ),
+            ("key=", FilterParseError::UnexpectedEnd("a value")),
+            (
+                "key prod",
+                FilterParseError::Unexpected("an operator", "prod".to_string()),
+            ),
+            ("(key=prod", FilterParseError::UnexpectedEnd(r#"")""#)),
+            ("status=\"PRIMARY", FilterParseError::UnterminatedString(7)),
+            (
+                "failed>many",
+                FilterParseError::NonNumericOperand(">".to_string()),
+            ),
+            (
+                "key=prod prod",
+                FilterParseError::Unexpected("end of filter", "prod".to_string()),
+            ),
+        ];
+
+        for (filter, expected) in cases {
+            // WHEN
+            let result = FilterExpr::parse(filter);
+
+            // THEN
+            assert_eq!(result.err(), Some(expected), "filter: {filter}");
+        }
+    }
+
+    #[test]
+    fn parsing_deeply_nested_filters_fails() {
+        // GIVEN
+        let parens = format!("{}key=prod{}", "(".repeat(100_000), ")".repeat(100_000));
+        let nots = format!("{}key=prod", "not ".repeat(100_000));
+        let at_limit = format!(
+            "{}key=prod{}",
+            "(".repeat(MAX_NESTING_DEPTH),
+            ")".repeat(MAX_NESTING_DEPTH)
+        );
+
+        // WHEN
+        let parens_result = FilterExpr::parse(&parens);
+        let nots_result = FilterExpr::parse(&nots);
+        let at_limit_result = FilterExpr::parse(&at_limit);
+
+        // THEN
+        let expected = Some(FilterParseError::TooDeeplyNested(MAX_NESTING_DEPTH));
+        assert_eq!(parens_result.err(), expected);
+        assert_eq!(nots_result.err(), expected);
+        assert!(at_limit_result.is_ok());
+    }
+
+    #[test]
+    fn filters_with_fields_items_dont_have_are_rejected() {
+        // GIVEN
+        let cases = [
+            ("failed>0", Some("failed")),
+            ("key=prod and not deployment~svc", Some("deployment")),
+            ("running<failed", Some("failed")),
+            ("key=prod and running<desired", None),
+        ];
+
+        for (filter, unsupported) in cases {
+            // WHEN
+            let expr = FilterExpr::parse(filter).expect("filter should've been parsed");
+            let for_deployments = expr.clone().supported_by::<DeploymentDetails>();
+            let for_services = expr.supported_by::<ServiceDetails>();
+
+            // THEN
+            assert!(for_deployments.is_ok(), "filter: {filter}");
+            assert_eq!(
+                for_services.err(),
+                unsupported.map(|f| FilterParseError::UnsupportedField(f, "services")),
+                "filter: {filter}"
+            );
+        }
+    }
+
+    #[test]
+    fn referencing_an_unknown_named_filter_fails() {
+        // GIVEN
+        let named = BTreeMap::new();
+
+        // WHEN
+        let result = FilterExpr::parse_with_named("@failing", &named);
+
+        // THEN
+        assert_eq!(
+            result.err(),
+            Some(FilterParseError::UnknownNamedFilter("failing".to_string()))
+        );
+    }
+}
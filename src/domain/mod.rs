@@ -1,7 +1,15 @@
+mod arn;
 mod deployment;
+mod filter;
+mod object_tree;
 mod profiles;
 mod service;
+mod task;
 
+pub use arn::*;
 pub use deployment::*;
+pub use filter::*;
+pub use object_tree::*;
 pub use profiles::*;
 pub use service::*;
+pub use task::*;
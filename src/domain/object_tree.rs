@@ -0,0 +1,473 @@
+use aws_sdk_ecs::primitives::DateTime;
+use aws_sdk_ecs::types::*;
+use aws_smithy_types::{Document, Number};
+use std::collections::HashMap;
+
+/// The fields of an API object (eg. one from the ECS SDK), as a tree. Fields that aren't set are
+/// left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectTree {
+    pub nodes: Vec<ObjectNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectNode {
+    pub name: String,
+    /// Set for scalar values; nodes with children don't have one
+    pub value: Option<String>,
+    pub children: Vec<ObjectNode>,
+}
+
+impl ObjectNode {
+    fn leaf(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: Some(value.into()),
+            children: Vec::new(),
+        }
+    }
+
+    /// Nodes without any children are shown as `empty` instead.
+    fn branch(name: impl Into<String>, children: Vec<ObjectNode>, empty: &str) -> Self {
+        if children.is_empty() {
+            return Self::leaf(name, empty);
+        }
+
+        Self {
+            name: name.into(),
+            value: None,
+            children,
+        }
+    }
+}
+
+impl ObjectTree {
+    pub fn new<T: ObjectFields>(object: &T) -> Self {
+        Self {
+            nodes: object.fields(),
+        }
+    }
+}
+
+/// Implemented by the API objects that can be shown as a tree.
+pub trait ObjectFields {
+    fn fields(&self) -> Vec<ObjectNode>;
+}
+
+/// Implemented by the values that fields of API objects hold.
+trait ObjectValue {
+    /// Returns `None` for values that aren't set.
+    fn object_node(&self, name: &str) -> Option<ObjectNode>;
+
+    /// Items of lists are named after their position.
+    fn list_item_node(&self, index: usize) -> Option<ObjectNode> {
+        self.object_node(&format!("[{index}]"))
+    }
+}
+
+impl<T: ObjectValue> ObjectValue for Option<T> {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        self.as_ref()?.object_node(name)
+    }
+}
+
+impl<T: ObjectValue> ObjectValue for Vec<T> {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        let items = self
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| item.list_item_node(i))
+            .collect();
+
+        Some(ObjectNode::branch(name, items, "[]"))
+    }
+}
+
+/// Entries are sorted by key, so that they're always shown in the same order.
+impl<T: ObjectValue> ObjectValue for HashMap<String, T> {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(k, _)| *k);
+        let entries = entries
+            .into_iter()
+            .filter_map(|(k, v)| v.object_node(k))
+            .collect();
+
+        Some(ObjectNode::branch(name, entries, "{}"))
+    }
+}
+
+impl ObjectValue for Document {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        match self {
+            Document::Object(entries) => entries.object_node(name),
+            Document::Array(items) => items.object_node(name),
+            Document::Number(Number::PosInt(n)) => n.object_node(name),
+            Document::Number(Number::NegInt(n)) => n.object_node(name),
+            Document::Number(Number::Float(n)) => n.object_node(name),
+            Document::String(s) => s.object_node(name),
+            Document::Bool(b) => b.object_node(name),
+            Document::Null => None,
+        }
+    }
+}
+
+/// Implements `ObjectValue` for types that are shown as their `Display` representation.
+macro_rules! display_values {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ObjectValue for $ty {
+                fn object_node(&self, name: &str) -> Option<ObjectNode> {
+                    Some(ObjectNode::leaf(name, self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+display_values!(String, bool, i32, i64, u64, f64, DateTime);
+
+/// Implements `ObjectValue` for the API's enums.
+macro_rules! enum_values {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl ObjectValue for $ty {
+                fn object_node(&self, name: &str) -> Option<ObjectNode> {
+                    Some(ObjectNode::leaf(name, self.as_str()))
+                }
+            }
+        )*
+    };
+}
+
+enum_values!(
+    AssignPublicIp,
+    AvailabilityZoneRebalancing,
+    Connectivity,
+    DeploymentControllerType,
+    DeploymentLifecycleHookAction,
+    DeploymentLifecycleHookStage,
+    DeploymentLifecycleHookTargetType,
+    DeploymentRolloutState,
+    DeploymentStrategy,
+    EbsResourceType,
+    EnvironmentFileType,
+    HealthStatus,
+    LaunchType,
+    LogDriver,
+    ManagedAgentName,
+    PlacementConstraintType,
+    PlacementStrategyType,
+    PropagateTags,
+    ResourceManagementType,
+    ResourceType,
+    ScaleUnit,
+    SchedulingStrategy,
+    ServiceConnectAccessLoggingFormat,
+    ServiceConnectIncludeQueryParameters,
+    StabilityStatus,
+    TargetType,
+    TaskFilesystemType,
+    TaskStopCode,
+    TransportProtocol,
+);
+
+/// Implements `ObjectFields` and `ObjectValue` for the API's objects, showing the given fields in
+/// the order they're listed in. Items of lists are named after their position and their type.
+macro_rules! object_values {
+    ($($ty:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl ObjectFields for $ty {
+                fn fields(&self) -> Vec<ObjectNode> {
+                    [$(self.$field.object_node(stringify!($field).trim_start_matches("r#"))),*]
+                        .into_iter()
+                        .flatten()
+                        .collect()
+                }
+            }
+
+            impl ObjectValue for $ty {
+                fn object_node(&self, name: &str) -> Option<ObjectNode> {
+                    Some(ObjectNode::branch(name, self.fields(), "{}"))
+                }
+
+                fn list_item_node(&self, index: usize) -> Option<ObjectNode> {
+                    self.object_node(&format!("[{index}] {}", stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+object_values! {
+    Service {
+        service_arn, service_name, cluster_arn, load_balancers, service_registries, status,
+        desired_count, running_count, pending_count, launch_type, capacity_provider_strategy,
+        platform_version, platform_family, task_definition, deployment_configuration, task_sets,
+        deployments, role_arn, events, created_at, current_service_deployment,
+        current_service_revisions, placement_constraints, placement_strategy, network_configuration,
+        health_check_grace_period_seconds, scheduling_strategy, deployment_controller, tags,
+        created_by, enable_ecs_managed_tags, propagate_tags, enable_execute_command,
+        availability_zone_rebalancing, resource_management_type,
+    }
+    // inference accelerators are no longer supported by ECS, and are left out
+    Task {
+        attachments, attributes, availability_zone, capacity_provider_name, cluster_arn,
+        connectivity, connectivity_at, container_instance_arn, containers, cpu, created_at,
+        desired_status, enable_execute_command, execution_stopped_at, group, health_status,
+        last_status, launch_type, memory, overrides, platform_version, platform_family,
+        pull_started_at, pull_stopped_at, started_at, started_by, stop_code, stopped_at,
+        stopped_reason, stopping_at, tags, task_arn, task_definition_arn, version,
+        ephemeral_storage, fargate_ephemeral_storage,
+    }
+    Container {
+        container_arn, task_arn, name, image, image_digest, runtime_id, last_status, exit_code,
+        reason, network_bindings, network_interfaces, health_status, managed_agents, cpu, memory,
+        memory_reservation, gpu_ids, neuron_device_ids,
+    }
+    LoadBalancer {
+        target_group_arn, load_balancer_name, container_name, container_port,
+        advanced_configuration,
+    }
+    ServiceRegistry { registry_arn, port, container_name, container_port }
+    CapacityProviderStrategyItem { capacity_provider, weight, base }
+    DeploymentConfiguration {
+        deployment_circuit_breaker, maximum_percent, minimum_healthy_percent, alarms, strategy,
+        bake_time_in_minutes, lifecycle_hooks, linear_configuration, canary_configuration,
+    }
+    TaskSet {
+        id, task_set_arn, service_arn, cluster_arn, started_by, external_id, status,
+        task_definition, computed_desired_count, pending_count, running_count, created_at,
+        updated_at, launch_type, capacity_provider_strategy, platform_version, platform_family,
+        network_configuration, load_balancers, service_registries, scale, stability_status,
+        stability_status_at, tags, fargate_ephemeral_storage,
+    }
+    Deployment {
+        id, status, task_definition, desired_count, pending_count, running_count, failed_tasks,
+        created_at, updated_at, capacity_provider_strategy, launch_type, platform_version,
+        platform_family, network_configuration, rollout_state, rollout_state_reason,
+        service_connect_configuration, service_connect_resources, volume_configurations,
+        fargate_ephemeral_storage, vpc_lattice_configurations,
+    }
+    ServiceEvent { id, created_at, message }
+    ServiceCurrentRevisionSummary {
+        arn, requested_task_count, running_task_count, pending_task_count,
+    }
+    PlacementConstraint { r#type, expression }
+    PlacementStrategy { r#type, field }
+    NetworkConfiguration { awsvpc_configuration }
+    DeploymentController { r#type }
+    Attachment { id, r#type, status, details }
+    Attribute { name, value, target_type, target_id }
+    TaskOverride {
+        container_overrides, cpu, execution_role_arn, memory, task_role_arn, ephemeral_storage,
+    }
+    EphemeralStorage { size_in_gib }
+    TaskEphemeralStorage { size_in_gib, kms_key_id }
+    NetworkBinding {
+        bind_ip, container_port, host_port, protocol, container_port_range, host_port_range,
+    }
+    NetworkInterface { attachment_id, private_ipv4_address, ipv6_address }
+    ManagedAgent { last_started_at, name, reason, last_status }
+    AdvancedConfiguration {
+        alternate_target_group_arn, production_listener_rule, test_listener_rule, role_arn,
+    }
+    DeploymentCircuitBreaker { enable, rollback }
+    DeploymentAlarms { alarm_names, rollback, enable }
+    DeploymentLifecycleHook {
+        target_type, hook_target_arn, role_arn, lifecycle_stages, hook_details,
+        timeout_configuration,
+    }
+    LinearConfiguration { step_percent, step_bake_time_in_minutes }
+    CanaryConfiguration { canary_percent, canary_bake_time_in_minutes }
+    Scale { value, unit }
+    DeploymentEphemeralStorage { kms_key_id }
+    ServiceConnectConfiguration {
+        enabled, namespace, services, log_configuration, access_log_configuration,
+    }
+    ServiceConnectServiceResource { discovery_name, discovery_arn }
+    ServiceVolumeConfiguration { name, managed_ebs_volume }
+    VpcLatticeConfiguration { role_arn, target_group_arn, port_name }
+    AwsVpcConfiguration { subnets, security_groups, assign_public_ip }
+    DeploymentLifecycleHookTimeoutConfiguration { timeout_in_minutes, action }
+    ServiceConnectService {
+        port_name, discovery_name, client_aliases, ingress_port_override, timeout, tls,
+    }
+    LogConfiguration { log_driver, options, secret_options }
+    ServiceConnectAccessLogConfiguration { format, include_query_parameters }
+    ServiceManagedEbsVolumeConfiguration {
+        encrypted, kms_key_id, volume_type, size_in_gib, snapshot_id, volume_initialization_rate,
+        iops, throughput, tag_specifications, role_arn, filesystem_type,
+    }
+    // environment variables can hold secrets, so they're left out (as they are from the SDK's
+    // Debug representation)
+    ContainerOverride {
+        name, command, environment_files, cpu, memory, memory_reservation, resource_requirements,
+    }
+    EnvironmentFile { value, r#type }
+    ResourceRequirement { value, r#type }
+    ServiceConnectClientAlias { port, dns_name, test_traffic_rules }
+    TimeoutConfiguration { idle_timeout_seconds, per_request_timeout_seconds }
+    ServiceConnectTlsConfiguration { issuer_certificate_authority, kms_key, role_arn }
+    Secret { name, value_from }
+    EbsTagSpecification { resource_type, tags, propagate_tags }
+    ServiceConnectTestTrafficRules { header }
+    ServiceConnectTlsCertificateAuthority { aws_pca_authority_arn }
+    ServiceConnectTestTrafficHeaderRules { name, value }
+    ServiceConnectTestTrafficHeaderMatchRules { exact }
+}
+
+/// Tags and the like are shown as the key/value pairs they are.
+impl ObjectValue for Tag {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        Some(ObjectNode::leaf(
+            name,
+            format!("{}={}", self.key()?, self.value()?),
+        ))
+    }
+
+    fn list_item_node(&self, _index: usize) -> Option<ObjectNode> {
+        Some(ObjectNode::leaf(self.key()?, self.value()?))
+    }
+}
+
+impl ObjectValue for KeyValuePair {
+    fn object_node(&self, name: &str) -> Option<ObjectNode> {
+        Some(ObjectNode::leaf(
+            name,
+            format!("{}={}", self.name()?, self.value()?),
+        ))
+    }
+
+    fn list_item_node(&self, _index: usize) -> Option<ObjectNode> {
+        Some(ObjectNode::leaf(self.name()?, self.value()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    fn lines(nodes: &[ObjectNode], depth: usize, out: &mut Vec<String>) {
+        for node in nodes {
+            let indent = "  ".repeat(depth);
+            match &node.value {
+                Some(v) => out.push(format!("{indent}{}: {v}", node.name)),
+                None => out.push(format!("{indent}{}", node.name)),
+            }
+            lines(&node.children, depth + 1, out);
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn building_a_tree_from_an_sdk_object_works() {
+        // GIVEN
+        let task = Task::builder()
+            .task_arn("arn:aws:ecs:eu-central-1:111111111111:task/prod/0123abcd")
+            .availability_zone("eu-central-1a")
+            .connectivity(Connectivity::Connected)
+            .started_at(DateTime::from_secs(1_700_000_000))
+            .cpu("256")
+            .attachments(
+                Attachment::builder()
+                    .r#type("ElasticNetworkInterface")
+                    .details(
+                        KeyValuePair::builder()
+                            .name("privateIPv4Address")
+                            .value("10.0.1.23")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .tags(Tag::builder().key("team").value("payments").build())
+            .build();
+
+        // WHEN
+        let tree = ObjectTree::new(&task);
+
+        // THEN
+        let mut out = Vec::new();
+        lines(&tree.nodes, 0, &mut out);
+        assert_debug_snapshot!(out, @r#"
+        [
+            "attachments",
+            "  [0] Attachment",
+            "    type: ElasticNetworkInterface",
+            "    details",
+            "      privateIPv4Address: 10.0.1.23",
+            "availability_zone: eu-central-1a",
+            "connectivity: CONNECTED",
+            "cpu: 256",
+            "enable_execute_command: false",
+            "started_at: 2023-11-14T22:13:20Z",
+            "tags",
+            "  team: payments",
+            "task_arn: arn:aws:ecs:eu-central-1:111111111111:task/prod/0123abcd",
+            "version: 0",
+        ]
+        "#);
+    }
+
+    #[test]
+    fn lists_maps_and_secrets_are_shown_as_expected() {
+        // GIVEN
+        let task = Task::builder()
+            .overrides(
+                TaskOverride::builder()
+                    .container_overrides(
+                        ContainerOverride::builder()
+                            .name("web")
+                            .command("serve")
+                            .command("--port=8080")
+                            .environment(
+                                KeyValuePair::builder()
+                                    .name("DB_PASSWORD")
+                                    .value("hunter2")
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .build(),
+            )
+            .set_attachments(Some(Vec::new()))
+            .build();
+        let log_configuration = LogConfiguration::builder()
+            .log_driver(LogDriver::Awslogs)
+            .options("awslogs-region", "eu-central-1")
+            .options("awslogs-group", "/ecs/web")
+            .build()
+            .expect("log configuration should've been built");
+
+        // WHEN
+        let task_tree = ObjectTree::new(&task);
+        let log_configuration_tree = ObjectTree::new(&log_configuration);
+
+        // THEN
+        let mut out = Vec::new();
+        lines(&task_tree.nodes, 0, &mut out);
+        lines(&log_configuration_tree.nodes, 0, &mut out);
+        assert_debug_snapshot!(out, @r#"
+        [
+            "attachments: []",
+            "enable_execute_command: false",
+            "overrides",
+            "  container_overrides",
+            "    [0] ContainerOverride",
+            "      name: web",
+            "      command",
+            "        [0]: serve",
+            "        [1]: --port=8080",
+            "version: 0",
+            "log_driver: awslogs",
+            "options",
+            "  awslogs-group: /ecs/web",
+            "  awslogs-region: eu-central-1",
+        ]
+        "#);
+    }
+}
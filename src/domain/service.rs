@@ -1,4 +1,6 @@
-use crate::config::ConfigSource;
+use super::ObjectTree;
+use crate::config::{ClientKey, ServiceMetadata};
+use std::sync::Arc;
 
 pub type ServiceResult = Result<ServiceDetails, ServiceError>;
 
@@ -9,14 +11,86 @@ pub struct ServiceError {
     pub cluster_keys: Vec<String>,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ServiceDetails {
     pub name: String,
     pub status: String,
     pub desired_count: i32,
     pub running_count: i32,
     pub pending_count: i32,
+    /// Tasks that failed to start, across the service's deployments
+    pub failed_count: i32,
     pub cluster_keys: Vec<String>,
     pub cluster_arn: String,
-    pub config_source: ConfigSource,
+    pub client_key: ClientKey,
+    pub metadata: ServiceMetadata,
+    /// When the service was last deployed to (in seconds since the unix epoch)
+    pub last_deployed_at: Option<i64>,
+    /// All of the service's fields, as returned by the API; shared, since services are cloned
+    /// into every command that refreshes them
+    pub object: Arc<ObjectTree>,
+}
+
+/// Identifies a service, whatever state it's in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceId {
+    pub name: String,
+    pub cluster_arn: String,
+}
+
+impl ServiceDetails {
+    pub fn id(&self) -> ServiceId {
+        ServiceId {
+            name: self.name.clone(),
+            cluster_arn: self.cluster_arn.clone(),
+        }
+    }
+
+    /// The service's alias if one is configured, its name otherwise.
+    pub fn display_name(&self) -> &str {
+        self.metadata.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A service, as served by the web view's API.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ServiceSummary {
+    pub service_name: String,
+    pub keys: String,
+    pub cluster_arn: String,
+    #[serde(flatten)]
+    pub metadata: ServiceMetadata,
+    pub status: String,
+    pub running_count: i32,
+    pub desired_count: i32,
+    pub pending_count: i32,
+    /// Tasks that failed to start, across the service's deployments
+    pub failed_count: i32,
+    /// When the service was last deployed to (in seconds since the unix epoch)
+    pub last_deployed_at: Option<i64>,
+    /// The task definition of the service's primary deployment, eg. `payments-api:42`
+    pub task_definition: Option<String>,
+}
+
+impl Ord for ServiceSummary {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.service_name
+            .cmp(&other.service_name)
+            .then_with(|| self.keys.cmp(&other.keys))
+    }
+}
+
+impl PartialOrd for ServiceSummary {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// An entry in a service's event log, eg. "(service payments-api) has reached a steady state."
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ServiceEvent {
+    pub id: String,
+    /// In seconds since the unix epoch
+    pub created_at: Option<i64>,
+    pub message: String,
 }
@@ -0,0 +1,29 @@
+/// A task of a service, as served by the web view's API.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct TaskSummary {
+    pub task_arn: String,
+    /// The last component of the task's ARN
+    pub task_id: String,
+    /// eg. `payments-api:42`
+    pub task_definition: Option<String>,
+    pub last_status: Option<String>,
+    pub desired_status: Option<String>,
+    pub health_status: Option<String>,
+    pub launch_type: Option<String>,
+    pub cpu: Option<String>,
+    pub memory: Option<String>,
+    /// In seconds since the unix epoch
+    pub started_at: Option<i64>,
+    pub stopped_reason: Option<String>,
+    pub containers: Vec<ContainerSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct ContainerSummary {
+    pub name: String,
+    pub image: Option<String>,
+    pub last_status: Option<String>,
+    pub health_status: Option<String>,
+    pub exit_code: Option<i32>,
+    pub reason: Option<String>,
+}
@@ -1,13 +1,24 @@
-use crate::cmds::{AddProfileError, ListDeploymentsError, ListProfilesError};
-use crate::server::ServeDeploymentsError;
-use crate::utils::{ConfigDirError, GetClustersError};
+use crate::cmds::{
+    AddProfileError, ListDeploymentsError, ListProfilesError, NotifyConfigError, RunNotifierError,
+    ShowProfileSchemaError,
+};
+use crate::domain::FilterParseError;
+use crate::server::{AuthConfigError, ServeDeploymentsError};
+use crate::tui::TuiConfigError;
+use crate::utils::{ConfigDirError, DataDirError, GetClustersError, LocateProfileError};
 
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
     #[error(transparent)]
     GetConfigDirectory(#[from] ConfigDirError),
     #[error(transparent)]
+    GetDataDirectory(#[from] DataDirError),
+    #[error(transparent)]
+    LocateProfile(#[from] LocateProfileError),
+    #[error(transparent)]
     GetClusters(#[from] GetClustersError),
+    #[error("invalid filter: {0}")]
+    ParseFilter(#[from] FilterParseError),
     #[error(transparent)]
     AddProfile(#[from] AddProfileError),
     #[error(transparent)]
@@ -15,9 +26,19 @@ pub enum AppError {
     #[error(transparent)]
     RunMonitor(anyhow::Error),
     #[error(transparent)]
+    GetTuiConfig(#[from] TuiConfigError),
+    #[error(transparent)]
     ListDeployments(#[from] ListDeploymentsError),
     #[error(transparent)]
     ServeDeployments(#[from] ServeDeploymentsError),
+    #[error("couldn't set up authentication: {0}")]
+    ConfigureAuth(#[from] AuthConfigError),
+    #[error(transparent)]
+    GetNotifyConfig(#[from] NotifyConfigError),
+    #[error(transparent)]
+    RunNotifier(#[from] RunNotifierError),
+    #[error(transparent)]
+    ShowProfileSchema(#[from] ShowProfileSchemaError),
 }
 
 impl AppError {
@@ -27,11 +48,24 @@ impl AppError {
                 ConfigDirError::XDGConfigHomeNotAbsolute => None,
                 ConfigDirError::CouldntGetConfigDir => Some(100),
             },
+            AppError::GetDataDirectory(e) => match e {
+                DataDirError::XDGDataHomeNotAbsolute => None,
+                DataDirError::CouldntGetDataDir => Some(101),
+            },
+            AppError::LocateProfile(e) => match e {
+                LocateProfileError::NoProfileProvided => None,
+            },
             AppError::GetClusters(e) => match e {
                 GetClustersError::ProfileDoesntExist => None,
                 GetClustersError::CouldntReadProfileFile(_) => Some(200),
                 GetClustersError::ConfigFileInvalid(_) => None,
+                GetClustersError::IncludedProfileDoesntExist(..) => None,
+                GetClustersError::IncludedProfileNameInvalid(..) => None,
+                GetClustersError::IncludedProfileInvalid(..) => None,
+                GetClustersError::CircularInclude(_) => None,
+                GetClustersError::ClusterConfigInvalid(..) => None,
             },
+            AppError::ParseFilter(_) => None,
             AppError::AddProfile(e) => match e {
                 AddProfileError::ProfileNameInvalid(_) => None,
                 AddProfileError::CouldntCreateProfileDir(..) => Some(300),
@@ -45,11 +79,21 @@ impl AppError {
                 ListProfilesError::GetFileStem(_) => Some(402),
             },
             AppError::RunMonitor(_) => Some(500),
+            AppError::GetTuiConfig(e) => match e {
+                TuiConfigError::CouldntReadFile(_) => Some(501),
+                TuiConfigError::FileInvalid(_) => None,
+                TuiConfigError::KeysInvalid(_) => None,
+                TuiConfigError::ThemeInvalid(_) => None,
+            },
             AppError::ListDeployments(e) => match e {
                 ListDeploymentsError::SerialiseToJson(_) => Some(600),
                 ListDeploymentsError::SerializeToCSV(_) => Some(601),
                 ListDeploymentsError::FlushResultsToCSVWriter(_) => Some(602),
                 ListDeploymentsError::Unexpected(_) => Some(603),
+                ListDeploymentsError::CouldntWriteToFile(..) => None,
+                ListDeploymentsError::PostingNeedsSlackFormat => None,
+                ListDeploymentsError::PostingNeedsDefaultMode => None,
+                ListDeploymentsError::PostToWebhook(_) => None,
             },
             AppError::ServeDeployments(e) => match e {
                 ServeDeploymentsError::CouldntFindOpenPort => None,
@@ -57,6 +101,31 @@ impl AppError {
                 ServeDeploymentsError::CouldntBindToAddress(_) => None,
                 ServeDeploymentsError::CouldntStartServer(_) => Some(700),
             },
+            AppError::ConfigureAuth(e) => match e {
+                AuthConfigError::CouldntReadFile(..) => Some(701),
+                AuthConfigError::TokenFileEmpty(_) => None,
+                AuthConfigError::HtpasswdEntryInvalid(..) => None,
+                AuthConfigError::HtpasswdFileEmpty(_) => None,
+                AuthConfigError::HeaderNameInvalid(_) => None,
+            },
+            AppError::GetNotifyConfig(e) => match e {
+                NotifyConfigError::FileDoesntExist(_) => None,
+                NotifyConfigError::CouldntReadFile(..) => Some(800),
+                NotifyConfigError::FileInvalid(..) => None,
+                NotifyConfigError::NoWebhooks(_) => None,
+                NotifyConfigError::RetryAttemptsInvalid => None,
+                NotifyConfigError::RetryBackoffInvalid => None,
+                NotifyConfigError::DuplicateWebhook(_) => None,
+                NotifyConfigError::WebhookInvalid(..) => None,
+                NotifyConfigError::TemplateInvalid(..) => None,
+            },
+            AppError::RunNotifier(e) => match e {
+                RunNotifierError::FilterInvalid(..) => None,
+                RunNotifierError::CouldntBuildClient(_) => Some(801),
+            },
+            AppError::ShowProfileSchema(e) => match e {
+                ShowProfileSchemaError::SerializeToJson(_) => Some(900),
+            },
         }
     }
 }
@@ -1,14 +1,24 @@
 use crate::args::{Args, EcscopeCommand, ProfilesCommand};
-use crate::cmds::{add_profile, list_deployments, list_profiles, run_monitor};
-use crate::common::{OutputMode, get_env};
+use crate::cmds::{
+    ListDeploymentsError, ListOutput, NOTIFY_CONFIG_FILE, add_profile, get_notify_config,
+    list_deployments, list_profiles, run_monitor, run_notifier, show_profile_schema,
+};
+use crate::common::{OutputFormat, OutputMode, get_env, redact_mode_on};
 use crate::debug::display_debug_info;
+use crate::domain::{FilterExpr, ServiceDetails};
 use crate::errors::AppError;
-use crate::server::serve_deployments;
-use crate::utils::{get_clusters, get_config_dir};
+use crate::server::{Auth, CacheConfig, DeploymentsSource, serve_dashboard, serve_deployments};
+use crate::tui::{SessionStore, get_tui_config};
+use crate::utils::{ProfileLocator, get_clusters, get_config_dir, get_data_dir};
+use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 const TOOL_DIR: &str = "ecscope";
+// the web view of deps is for one person, so this only keeps refreshes from piling up
+const WEB_MIN_REFRESH_INTERVAL_SECS: u64 = 1;
 
 pub async fn handle(args: Args) -> Result<(), AppError> {
     let config_dir = match &args.config_dir {
@@ -16,45 +26,104 @@ pub async fn handle(args: Args) -> Result<(), AppError> {
         None => get_config_dir()?,
     };
     let config_dir = config_dir.join(PathBuf::from(TOOL_DIR));
+    let profile_locator = ProfileLocator::new(&config_dir, std::env::current_dir().ok());
 
     if args.debug {
-        display_debug_info(&args, &config_dir);
+        let profile_source = match &args.command {
+            EcscopeCommand::Deployments {
+                profile_name,
+                profile_file,
+                ..
+            }
+            | EcscopeCommand::Monitor {
+                profile_name,
+                profile_file,
+                ..
+            }
+            | EcscopeCommand::Notify {
+                profile_name,
+                profile_file,
+                ..
+            } => Some(profile_locator.locate(profile_name.as_deref(), profile_file.as_deref())),
+            EcscopeCommand::Profiles { .. } | EcscopeCommand::Serve { .. } => None,
+        };
+        display_debug_info(&args, &config_dir, profile_source);
         return Ok(());
     }
 
     match args.command {
         EcscopeCommand::Deployments {
             profile_name,
+            profile_file,
             service_name_filter,
             key_filter,
+            tag_filters,
+            where_filter,
             state,
             format,
+            output,
+            post_to,
             mode,
             web_skip_opening,
+            web_cache_ttl,
+            web_cache_max_stale,
         } => {
-            if let Some((clients_map, clusters)) = get_clusters(
-                &config_dir,
-                profile_name.clone(),
+            if post_to.is_some() && !matches!(format, OutputFormat::Slack) {
+                return Err(ListDeploymentsError::PostingNeedsSlackFormat.into());
+            }
+            if post_to.is_some() && matches!(mode, OutputMode::Web) {
+                return Err(ListDeploymentsError::PostingNeedsDefaultMode.into());
+            }
+
+            let profile_source =
+                profile_locator.locate(profile_name.as_deref(), profile_file.as_deref())?;
+            if let Some((clients_map, clusters, named_filters)) = get_clusters(
+                &profile_locator,
+                &profile_source,
                 service_name_filter,
                 key_filter,
+                &tag_filters,
             )
             .await?
             {
+                let filter = where_filter
+                    .map(|w| FilterExpr::parse_with_named(&w, &named_filters))
+                    .transpose()?;
                 match mode {
                     OutputMode::Default => {
-                        list_deployments(clusters, Arc::new(clients_map), state, format).await?
+                        list_deployments(
+                            clusters,
+                            Arc::new(clients_map),
+                            state,
+                            filter,
+                            ListOutput {
+                                format,
+                                file: output,
+                                post_to,
+                            },
+                            &profile_source.display_name(),
+                        )
+                        .await?
                     }
                     OutputMode::Web => {
                         let env = get_env();
-                        serve_deployments(
+                        let source = DeploymentsSource::new(
                             clusters,
                             Arc::new(clients_map),
-                            state,
-                            web_skip_opening,
-                            env,
+                            named_filters,
+                            CacheConfig {
+                                ttl: Duration::from_secs(web_cache_ttl),
+                                max_stale: Duration::from_secs(web_cache_max_stale),
+                                min_refresh_interval: Duration::from_secs(
+                                    WEB_MIN_REFRESH_INTERVAL_SECS,
+                                ),
+                            },
                         )
-                        .await
-                        .map_err(AppError::ServeDeployments)?
+                        .with_defaults(filter, state)
+                        .redacting(redact_mode_on());
+                        serve_deployments(source, web_skip_opening, env)
+                            .await
+                            .map_err(AppError::ServeDeployments)?
                     }
                 }
             }
@@ -62,23 +131,140 @@ pub async fn handle(args: Args) -> Result<(), AppError> {
         EcscopeCommand::Profiles { profiles_command } => match profiles_command {
             ProfilesCommand::Add { name } => add_profile(&config_dir, name, false)?,
             ProfilesCommand::List => list_profiles(&config_dir)?,
+            ProfilesCommand::Schema => show_profile_schema()?,
         },
         EcscopeCommand::Monitor {
             profile_name,
+            profile_file,
+            service_name_filter,
+            key_filter,
+            tag_filters,
+            where_filter,
+            fresh,
+        } => {
+            let profile_source =
+                profile_locator.locate(profile_name.as_deref(), profile_file.as_deref())?;
+            let tui_config = get_tui_config(&config_dir)?;
+            let data_dir = get_data_dir()?.join(PathBuf::from(TOOL_DIR));
+            let session_store = SessionStore::new(&data_dir, &profile_source.display_name(), fresh);
+            if let Some((clients_map, clusters, named_filters)) = get_clusters(
+                &profile_locator,
+                &profile_source,
+                service_name_filter,
+                key_filter,
+                &tag_filters,
+            )
+            .await?
+            {
+                let filter = where_filter
+                    .map(|w| {
+                        FilterExpr::parse_with_named(&w, &named_filters)
+                            .and_then(FilterExpr::supported_by::<ServiceDetails>)
+                    })
+                    .transpose()?;
+                run_monitor(
+                    profile_source.display_name(),
+                    clients_map,
+                    clusters,
+                    filter,
+                    named_filters,
+                    tui_config,
+                    session_store,
+                )
+                .await
+                .map_err(AppError::RunMonitor)?;
+            }
+        }
+        EcscopeCommand::Serve {
+            profile_names,
+            bind,
+            port,
+            cache_ttl,
+            cache_max_stale,
+            min_refresh_interval,
+            rate_limit,
+            token_file,
+            htpasswd_file,
+            trusted_user_header,
+            cors_origins,
+            redact,
+        } => {
+            let auth = Auth::new(
+                token_file.as_deref(),
+                htpasswd_file.as_deref(),
+                trusted_user_header.as_deref(),
+            )?;
+            let redact = redact || redact_mode_on();
+            let cache_config = CacheConfig {
+                ttl: Duration::from_secs(cache_ttl),
+                max_stale: Duration::from_secs(cache_max_stale),
+                min_refresh_interval: Duration::from_secs(min_refresh_interval),
+            };
+            let mut profiles = Vec::new();
+            // every profile gets its own routes, which can't overlap
+            let mut seen = HashSet::new();
+            let profile_names = profile_names
+                .into_iter()
+                .filter(|name| seen.insert(name.clone()));
+            for name in profile_names {
+                let profile_source = profile_locator.locate(Some(&name), None)?;
+                match get_clusters(&profile_locator, &profile_source, None, None, &[]).await? {
+                    Some((clients_map, clusters, named_filters)) => profiles.push((
+                        name,
+                        DeploymentsSource::new(
+                            clusters,
+                            Arc::new(clients_map),
+                            named_filters,
+                            cache_config,
+                        )
+                        .redacting(redact),
+                    )),
+                    None => eprintln!("profile \"{name}\" has no clusters; skipping it"),
+                }
+            }
+
+            serve_dashboard(
+                profiles,
+                SocketAddr::new(bind, port),
+                auth,
+                cors_origins,
+                rate_limit,
+                get_env(),
+            )
+            .await
+            .map_err(AppError::ServeDeployments)?
+        }
+        EcscopeCommand::Notify {
+            profile_name,
+            profile_file,
             service_name_filter,
             key_filter,
+            tag_filters,
+            notify_file,
+            interval,
         } => {
-            if let Some((clients_map, clusters)) = get_clusters(
-                &config_dir,
-                profile_name.clone(),
+            let profile_source =
+                profile_locator.locate(profile_name.as_deref(), profile_file.as_deref())?;
+            let notify_file = notify_file.unwrap_or_else(|| config_dir.join(NOTIFY_CONFIG_FILE));
+            let notify_config = get_notify_config(&notify_file)?;
+            if let Some((clients_map, clusters, named_filters)) = get_clusters(
+                &profile_locator,
+                &profile_source,
                 service_name_filter,
                 key_filter,
+                &tag_filters,
             )
             .await?
             {
-                run_monitor(profile_name.clone(), clients_map, clusters)
-                    .await
-                    .map_err(AppError::RunMonitor)?;
+                run_notifier(
+                    profile_source.display_name(),
+                    clients_map,
+                    clusters,
+                    named_filters,
+                    notify_config,
+                    Duration::from_secs(interval),
+                )
+                .await?;
             }
         }
     }
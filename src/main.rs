@@ -11,6 +11,7 @@ mod server;
 mod service;
 mod tui;
 mod utils;
+mod webhook;
 
 use args::Args;
 use clap::Parser;
@@ -0,0 +1,566 @@
+use super::rate_limit::{ClientRateLimiter, client_address, too_many_requests};
+use axum::Json;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderName, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+const ENV_VAR_TOKEN: &str = "ECSCOPE_SERVE_TOKEN";
+const BASIC_AUTH_REALM: &str = "ecscope";
+const VERIFIED_CREDENTIALS_TTL: Duration = Duration::from_secs(60);
+const MAX_VERIFIED_CREDENTIALS: usize = 1000;
+// bcrypt is slow by design; this keeps a flood of requests from taking up every CPU
+const MAX_CONCURRENT_VERIFICATIONS: usize = 4;
+/// How many times a minute a client can fail to authenticate before it's turned away without its
+/// credentials being checked
+pub const MAX_FAILED_AUTH_ATTEMPTS: u32 = 10;
+
+type Digest256 = [u8; 32];
+
+/// How requests to the server are authenticated.
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// Every request is let through
+    None,
+    /// Requests need to carry this token (as `Authorization: Bearer <token>`)
+    BearerToken(String),
+    /// Requests need to carry credentials (via HTTP basic auth) that match an entry in a htpasswd
+    /// file
+    Basic(Htpasswd),
+    /// Requests are authenticated by a reverse proxy, which passes the user on in this header
+    TrustedHeader(HeaderName),
+}
+
+/// The user a request was authenticated as, if known; attached to responses so that it can be
+/// logged.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub String);
+
+/// Why a request was turned away, with the challenge to send back (if any).
+#[derive(Debug)]
+struct Unauthorized(Option<String>);
+
+impl IntoResponse for Unauthorized {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": "unauthorized" }));
+        match self.0 {
+            Some(challenge) => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, challenge)],
+                body,
+            )
+                .into_response(),
+            None => (StatusCode::UNAUTHORIZED, body).into_response(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthConfigError {
+    #[error("couldn't read {0}: {1}")]
+    CouldntReadFile(PathBuf, IOError),
+    #[error("token file {0} is empty")]
+    TokenFileEmpty(PathBuf),
+    #[error(
+        "line {1} of {0} is invalid; entries need to be of the form <user>:<bcrypt hash> (as written by `htpasswd -B`)"
+    )]
+    HtpasswdEntryInvalid(PathBuf, usize),
+    #[error("htpasswd file {0} has no entries")]
+    HtpasswdFileEmpty(PathBuf),
+    #[error("\"{0}\" is not a valid header name")]
+    HeaderNameInvalid(String),
+}
+
+impl Auth {
+    /// Sets up authentication from the options provided (at most one of which is expected); a
+    /// token in ECSCOPE_SERVE_TOKEN is used if none are.
+    pub fn new(
+        token_file: Option<&Path>,
+        htpasswd_file: Option<&Path>,
+        trusted_user_header: Option<&str>,
+    ) -> Result<Self, AuthConfigError> {
+        if let Some(path) = token_file {
+            let token = std::fs::read_to_string(path)
+                .map_err(|e| AuthConfigError::CouldntReadFile(path.to_path_buf(), e))?;
+            let token = token.trim();
+            if token.is_empty() {
+                return Err(AuthConfigError::TokenFileEmpty(path.to_path_buf()));
+            }
+
+            return Ok(Auth::BearerToken(token.to_string()));
+        }
+
+        if let Some(path) = htpasswd_file {
+            return Ok(Auth::Basic(Htpasswd::load(path)?));
+        }
+
+        if let Some(name) = trusted_user_header {
+            let header_name = HeaderName::try_from(name)
+                .map_err(|_| AuthConfigError::HeaderNameInvalid(name.to_string()))?;
+
+            return Ok(Auth::TrustedHeader(header_name));
+        }
+
+        match std::env::var(ENV_VAR_TOKEN) {
+            Ok(token) if !token.trim().is_empty() => {
+                Ok(Auth::BearerToken(token.trim().to_string()))
+            }
+            _ => Ok(Auth::None),
+        }
+    }
+
+    async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<Option<AuthenticatedUser>, Unauthorized> {
+        match self {
+            Auth::None => Ok(None),
+            Auth::BearerToken(token) => {
+                let provided = authorization(headers, "Bearer ");
+                if provided.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes())) {
+                    Ok(None)
+                } else {
+                    Err(Unauthorized(Some("Bearer".to_string())))
+                }
+            }
+            Auth::Basic(htpasswd) => {
+                let user = match authorization(headers, "Basic ") {
+                    Some(encoded) => htpasswd.verify(encoded).await,
+                    None => None,
+                };
+
+                match user {
+                    Some(user) => Ok(Some(AuthenticatedUser(user))),
+                    None => Err(Unauthorized(Some(format!(
+                        r#"Basic realm="{BASIC_AUTH_REALM}", charset="UTF-8""#
+                    )))),
+                }
+            }
+            Auth::TrustedHeader(header_name) => match headers
+                .get(header_name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+            {
+                Some(user) if !user.is_empty() => Ok(Some(AuthenticatedUser(user.to_string()))),
+                _ => Err(Unauthorized(None)),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::None => write!(f, "none"),
+            Auth::BearerToken(_) => write!(f, "bearer token"),
+            Auth::Basic(_) => write!(f, "basic (htpasswd)"),
+            Auth::TrustedHeader(name) => write!(f, "trusted header ({name})"),
+        }
+    }
+}
+
+/// Users and their bcrypt password hashes, as read from a htpasswd file.
+#[derive(Clone)]
+pub struct Htpasswd {
+    entries: HashMap<String, String>,
+    /// Users whose credentials were verified recently, keyed by a digest of the credentials;
+    /// bcrypt is slow by design, and clients send the same credentials with every request
+    verified: Arc<Mutex<HashMap<Digest256, (String, Instant)>>>,
+    verifications: Arc<Semaphore>,
+    /// Checked against for users that don't exist, so that they take as long to turn away as the
+    /// ones that do
+    dummy_hash: String,
+}
+
+impl std::fmt::Debug for Htpasswd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // hashes are best kept out of logs
+        f.debug_tuple("Htpasswd")
+            .field(&self.entries.len())
+            .finish()
+    }
+}
+
+impl Htpasswd {
+    pub fn load(path: &Path) -> Result<Self, AuthConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| AuthConfigError::CouldntReadFile(path.to_path_buf(), e))?;
+
+        let mut entries = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(':') {
+                Some((user, hash)) if !user.is_empty() && hash.starts_with("$2") => {
+                    entries.insert(user.to_string(), hash.to_string());
+                }
+                _ => {
+                    return Err(AuthConfigError::HtpasswdEntryInvalid(
+                        path.to_path_buf(),
+                        index + 1,
+                    ));
+                }
+            }
+        }
+
+        if entries.is_empty() {
+            return Err(AuthConfigError::HtpasswdFileEmpty(path.to_path_buf()));
+        }
+
+        // as slow to verify as the slowest entry; a cost that's out of range fails every entry
+        // that uses it as quickly as the dummy hash does
+        let cost = entries
+            .values()
+            .filter_map(|hash| hash.split('$').nth(2)?.parse().ok())
+            .max()
+            .unwrap_or(bcrypt::DEFAULT_COST);
+        let dummy_hash = bcrypt::hash(BASIC_AUTH_REALM, cost).unwrap_or_default();
+
+        Ok(Self {
+            entries,
+            verified: Arc::default(),
+            verifications: Arc::new(Semaphore::new(MAX_CONCURRENT_VERIFICATIONS)),
+            dummy_hash,
+        })
+    }
+
+    /// Returns the user that base64 encoded credentials (as sent via HTTP basic auth) belong to,
+    /// if they match an entry.
+    async fn verify(&self, encoded: &str) -> Option<String> {
+        let key = digest(encoded.as_bytes());
+        if let Some(user) = self.recently_verified(&key) {
+            return Some(user);
+        }
+
+        let credentials = String::from_utf8(BASE64.decode(encoded).ok()?).ok()?;
+        let (user, password) = credentials.split_once(':')?;
+        let (hash, user_exists) = match self.entries.get(user) {
+            Some(hash) => (hash.clone(), true),
+            None => (self.dummy_hash.clone(), false),
+        };
+        let password = password.to_string();
+        let _permit = self.verifications.acquire().await.ok()?;
+        // bcrypt would hold up everything else running on the worker thread
+        let matches =
+            tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash).unwrap_or(false))
+                .await
+                .unwrap_or(false);
+        if !(matches && user_exists) {
+            return None;
+        }
+
+        let mut verified = self.verified.lock().unwrap_or_else(PoisonError::into_inner);
+        verified.retain(|_, (_, at)| at.elapsed() < VERIFIED_CREDENTIALS_TTL);
+        if verified.len() < MAX_VERIFIED_CREDENTIALS {
+            verified.insert(key, (user.to_string(), Instant::now()));
+        }
+
+        Some(user.to_string())
+    }
+
+    fn recently_verified(&self, key: &Digest256) -> Option<String> {
+        let verified = self.verified.lock().unwrap_or_else(PoisonError::into_inner);
+        verified
+            .get(key)
+            .filter(|(_, at)| at.elapsed() < VERIFIED_CREDENTIALS_TTL)
+            .map(|(user, _)| user.clone())
+    }
+}
+
+/// Lets through only the requests that can be authenticated; clients (told apart by their IP
+/// address) that fail to authenticate too often are turned away before their credentials are
+/// checked.
+pub async fn require_auth(
+    State((auth, failed_attempts)): State<(Arc<Auth>, Arc<ClientRateLimiter>)>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let client = client_address(&request);
+    if let Some(retry_after) = failed_attempts.retry_after(&client, Instant::now()) {
+        return too_many_requests(retry_after);
+    }
+
+    match auth.authenticate(request.headers()).await {
+        Ok(user) => {
+            // for the rate limiter to tell clients apart by
+            if let Some(user) = &user {
+                request.extensions_mut().insert(user.clone());
+            }
+            let mut response = next.run(request).await;
+            if let Some(user) = user {
+                response.extensions_mut().insert(user);
+            }
+            response
+        }
+        Err(unauthorized) => {
+            failed_attempts.count(&client, Instant::now());
+            unauthorized.into_response()
+        }
+    }
+}
+
+fn authorization<'a>(headers: &'a HeaderMap, scheme: &str) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix(scheme))
+        .map(str::trim)
+}
+
+fn digest(value: &[u8]) -> Digest256 {
+    Sha256::digest(value).into()
+}
+
+/// Compares secrets in time that depends on neither where they differ nor their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, b) = (digest(a), digest(b));
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(name: HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            name,
+            HeaderValue::from_str(value).expect("header value should've been valid"),
+        );
+        headers
+    }
+
+    fn htpasswd(entries: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join("htpasswd");
+        std::fs::write(&path, entries).expect("htpasswd file should've been written");
+        (dir, path)
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test]
+    async fn requests_with_the_right_bearer_token_are_let_through() {
+        // GIVEN
+        let auth = Auth::BearerToken("s3cr3t".to_string());
+
+        // WHEN
+        let result = auth
+            .authenticate(&headers(header::AUTHORIZATION, "Bearer s3cr3t"))
+            .await;
+
+        // THEN
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn requests_with_credentials_from_the_htpasswd_file_are_let_through() {
+        // GIVEN
+        let hash = bcrypt::hash("hunter2", 4).expect("password should've been hashed");
+        let (_dir, path) = htpasswd(&format!("# team\nalice:{hash}\n"));
+        let auth = Auth::new(None, Some(&path), None).expect("auth should've been set up");
+        let credentials = BASE64.encode("alice:hunter2");
+
+        // WHEN
+        let result = auth
+            .authenticate(&headers(
+                header::AUTHORIZATION,
+                &format!("Basic {credentials}"),
+            ))
+            .await;
+
+        // THEN
+        let user = result
+            .expect("request should've been let through")
+            .expect("user should've been known");
+        assert_eq!(user.0, "alice");
+    }
+
+    #[tokio::test]
+    async fn requests_carrying_the_trusted_header_are_let_through() {
+        // GIVEN
+        let auth =
+            Auth::new(None, None, Some("X-Forwarded-User")).expect("auth should've been set up");
+
+        // WHEN
+        let result = auth
+            .authenticate(&headers(
+                HeaderName::from_static("x-forwarded-user"),
+                "alice@example.com",
+            ))
+            .await;
+
+        // THEN
+        let user = result
+            .expect("request should've been let through")
+            .expect("user should've been known");
+        assert_eq!(user.0, "alice@example.com");
+    }
+
+    #[tokio::test]
+    async fn only_credentials_that_were_verified_are_remembered() {
+        // GIVEN
+        let hash = bcrypt::hash("hunter2", 4).expect("password should've been hashed");
+        let (_dir, path) = htpasswd(&format!("alice:{hash}\n"));
+        let htpasswd = Htpasswd::load(&path).expect("htpasswd file should've been loaded");
+        let right = BASE64.encode("alice:hunter2");
+        let wrong = BASE64.encode("alice:hunter3");
+
+        // WHEN
+        let first = htpasswd.verify(&right).await;
+        let rejected = htpasswd.verify(&wrong).await;
+        let second = htpasswd.verify(&right).await;
+
+        // THEN
+        assert_eq!(first.as_deref(), Some("alice"));
+        assert_eq!(rejected, None);
+        assert_eq!(second.as_deref(), Some("alice"));
+        let verified = htpasswd
+            .verified
+            .lock()
+            .expect("lock should've been acquired");
+        assert_eq!(verified.len(), 1);
+        assert!(verified.contains_key(&digest(right.as_bytes())));
+    }
+
+    #[test]
+    fn secrets_are_compared_correctly() {
+        // GIVEN
+        let cases: [(&[u8], bool); 4] = [
+            (b"s3cr3t", true),
+            (b"s3cr3", false),
+            (b"s3cr3T", false),
+            (b"", false),
+        ];
+
+        for (secret, expected) in cases {
+            // WHEN
+            let result = constant_time_eq(secret, b"s3cr3t");
+
+            // THEN
+            assert_eq!(result, expected, "secret: {secret:?}");
+        }
+    }
+
+    #[test]
+    fn tokens_are_read_from_files() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join("token");
+        std::fs::write(&path, "s3cr3t\n").expect("token file should've been written");
+
+        // WHEN
+        let auth = Auth::new(Some(&path), None, None).expect("auth should've been set up");
+
+        // THEN
+        assert!(matches!(auth, Auth::BearerToken(t) if t == "s3cr3t"));
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn requests_with_the_wrong_bearer_token_are_rejected() {
+        // GIVEN
+        let auth = Auth::BearerToken("s3cr3t".to_string());
+
+        // WHEN
+        let result = auth
+            .authenticate(&headers(header::AUTHORIZATION, "Bearer s3cr3"))
+            .await;
+
+        // THEN
+        let response = result
+            .expect_err("request should've been rejected")
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers().get(header::WWW_AUTHENTICATE),
+            Some(&HeaderValue::from_static("Bearer"))
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_with_the_wrong_password_are_rejected() {
+        // GIVEN
+        let hash = bcrypt::hash("hunter2", 4).expect("password should've been hashed");
+        let (_dir, path) = htpasswd(&format!("alice:{hash}\n"));
+        let auth = Auth::new(None, Some(&path), None).expect("auth should've been set up");
+        let credentials = BASE64.encode("alice:hunter3");
+
+        // WHEN
+        let result = auth
+            .authenticate(&headers(
+                header::AUTHORIZATION,
+                &format!("Basic {credentials}"),
+            ))
+            .await;
+
+        // THEN
+        let response = result
+            .expect_err("request should've been rejected")
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn users_that_dont_exist_are_rejected() {
+        // GIVEN
+        let hash = bcrypt::hash("hunter2", 4).expect("password should've been hashed");
+        let (_dir, path) = htpasswd(&format!("alice:{hash}\n"));
+        let htpasswd = Htpasswd::load(&path).expect("htpasswd file should've been loaded");
+        // the password the dummy hash is of
+        let credentials = BASE64.encode(format!("bob:{BASIC_AUTH_REALM}"));
+
+        // WHEN
+        let user = htpasswd.verify(&credentials).await;
+
+        // THEN
+        assert_eq!(user, None);
+        assert!(htpasswd.dummy_hash.starts_with("$2b$04$"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_trusted_header_are_rejected() {
+        // GIVEN
+        let auth =
+            Auth::new(None, None, Some("X-Forwarded-User")).expect("auth should've been set up");
+
+        // WHEN
+        let result = auth.authenticate(&HeaderMap::new()).await;
+
+        // THEN
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn htpasswd_entries_that_arent_bcrypt_hashes_are_reported() {
+        // GIVEN
+        let (_dir, path) = htpasswd("alice:$apr1$abc$def\n");
+
+        // WHEN
+        let result = Auth::new(None, Some(&path), None);
+
+        // THEN
+        assert!(matches!(
+            result,
+            Err(AuthConfigError::HtpasswdEntryInvalid(_, 1))
+        ));
+    }
+}
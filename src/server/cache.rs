@@ -0,0 +1,327 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+
+/// How long results are served from the cache for.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheConfig {
+    /// How long results are considered fresh for
+    pub ttl: Duration,
+    /// How long results can be served for after they've gone stale (while they're refreshed in
+    /// the background)
+    pub max_stale: Duration,
+    /// The least time between fetches made for a profile, across all of its caches
+    pub min_refresh_interval: Duration,
+}
+
+/// Spaces out the fetches of the caches that share it, so that they start at least
+/// `min_interval` apart, however many caches there are and however often they're asked for
+/// results.
+#[derive(Debug)]
+pub struct RefreshLimiter {
+    min_interval: Duration,
+    next_allowed: tokio::sync::Mutex<Option<Instant>>,
+}
+
+impl RefreshLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            next_allowed: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Waits for the turn of the next fetch.
+    async fn wait(&self) {
+        let mut next_allowed = self.next_allowed.lock().await;
+        if let Some(at) = *next_allowed {
+            tokio::time::sleep_until(at).await;
+        }
+        *next_allowed = Some(Instant::now() + self.min_interval);
+    }
+}
+
+/// Results, as fetched at some point.
+#[derive(Debug)]
+pub struct Cached<T> {
+    pub value: T,
+    pub fetched_at: SystemTime,
+    /// How long the fetch took
+    pub fetch_duration: Duration,
+    fetched: Instant,
+}
+
+impl<T> Cached<T> {
+    pub(super) fn new(value: T, fetch_duration: Duration) -> Self {
+        Self {
+            value,
+            fetched_at: SystemTime::now(),
+            fetch_duration,
+            fetched: Instant::now(),
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        self.fetched.elapsed()
+    }
+}
+
+/// Holds on to the last results of a fetch. Fresh results are served as is; stale ones are
+/// served while they're refreshed in the background; and concurrent fetches are coalesced, so
+/// that however many callers there are, there's only ever one fetch in flight.
+pub struct ResultsCache<T, F> {
+    config: CacheConfig,
+    fetch: F,
+    entry: Mutex<Option<Arc<Cached<T>>>>,
+    refreshing: tokio::sync::Mutex<()>,
+    limiter: Option<Arc<RefreshLimiter>>,
+}
+
+impl<T, F, Fut> ResultsCache<T, F>
+where
+    T: Send + Sync + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = T> + Send,
+{
+    pub fn new(config: CacheConfig, fetch: F) -> Self {
+        Self {
+            config,
+            fetch,
+            entry: Mutex::new(None),
+            refreshing: tokio::sync::Mutex::new(()),
+            limiter: None,
+        }
+    }
+
+    pub fn limited_by(mut self, limiter: Arc<RefreshLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    pub async fn get(self: &Arc<Self>) -> Arc<Cached<T>> {
+        let current = self.current();
+        if let Some(entry) = &current {
+            let age = entry.age();
+            if age < self.config.ttl {
+                return Arc::clone(entry);
+            }
+
+            if age < self.config.ttl + self.config.max_stale {
+                self.refresh_in_background();
+                return Arc::clone(entry);
+            }
+        }
+
+        self.refresh(current).await
+    }
+
+    fn current(&self) -> Option<Arc<Cached<T>>> {
+        self.entry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    async fn refresh(&self, seen: Option<Arc<Cached<T>>>) -> Arc<Cached<T>> {
+        let _refreshing = self.refreshing.lock().await;
+
+        // whoever held the lock before might've just refreshed the results
+        if let Some(entry) = self.current()
+            && seen.is_none_or(|s| !Arc::ptr_eq(&s, &entry))
+        {
+            return entry;
+        }
+
+        self.fetch_and_store().await
+    }
+
+    fn refresh_in_background(self: &Arc<Self>) {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            // a refresh already in flight will do
+            let Ok(_refreshing) = cache.refreshing.try_lock() else {
+                return;
+            };
+
+            if let Some(entry) = cache.current()
+                && entry.age() < cache.config.ttl
+            {
+                return;
+            }
+
+            cache.fetch_and_store().await;
+        });
+    }
+
+    async fn fetch_and_store(&self) -> Arc<Cached<T>> {
+        if let Some(limiter) = &self.limiter {
+            limiter.wait().await;
+        }
+
+        let started = Instant::now();
+        let value = (self.fetch)().await;
+        let entry = Arc::new(Cached::new(value, started.elapsed()));
+        *self.entry.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::clone(&entry));
+
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const CONFIG: CacheConfig = CacheConfig {
+        ttl: Duration::from_secs(5),
+        max_stale: Duration::from_secs(30),
+        min_refresh_interval: Duration::ZERO,
+    };
+
+    type CountingFetch = Pin<Box<dyn Future<Output = usize> + Send>>;
+
+    /// A cache whose fetches take a second, and return how many fetches have been made so far.
+    fn counting_cache(config: CacheConfig) -> Arc<ResultsCache<usize, impl Fn() -> CountingFetch>> {
+        Arc::new(ResultsCache::new(config, counting_fetch()))
+    }
+
+    fn counting_fetch() -> impl Fn() -> CountingFetch {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        move || -> CountingFetch {
+            let fetches = Arc::clone(&fetches);
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                fetches.fetch_add(1, Ordering::SeqCst) + 1
+            })
+        }
+    }
+
+    async fn get_concurrently<F>(cache: &Arc<ResultsCache<usize, F>>, callers: usize) -> Vec<usize>
+    where
+        F: Fn() -> CountingFetch + Send + Sync + 'static,
+    {
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..callers {
+            let cache = Arc::clone(cache);
+            tasks.spawn(async move { cache.get().await.value });
+        }
+
+        tasks.join_all().await
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test(start_paused = true)]
+    async fn fresh_results_are_served_from_the_cache() {
+        // GIVEN
+        let cache = counting_cache(CONFIG);
+        cache.get().await;
+
+        // WHEN
+        tokio::time::advance(Duration::from_secs(4)).await;
+        let entry = cache.get().await;
+
+        // THEN
+        assert_eq!(entry.value, 1);
+        assert_eq!(entry.age(), Duration::from_secs(4));
+        assert_eq!(entry.fetch_duration, Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_gets_are_coalesced_into_one_fetch() {
+        // GIVEN
+        let cache = counting_cache(CONFIG);
+
+        // WHEN
+        let values = get_concurrently(&cache, 10).await;
+
+        // THEN
+        assert_eq!(values, vec![1; 10]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stale_results_are_served_while_being_refreshed() {
+        // GIVEN
+        let cache = counting_cache(CONFIG);
+        cache.get().await;
+        tokio::time::advance(Duration::from_secs(6)).await;
+
+        // WHEN
+        let stale = cache.get().await;
+        let also_stale = cache.get().await;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let refreshed = cache.get().await;
+
+        // THEN
+        assert_eq!(stale.value, 1);
+        assert_eq!(also_stale.value, 1);
+        assert_eq!(refreshed.value, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn results_too_stale_to_serve_are_fetched_again() {
+        // GIVEN
+        let cache = counting_cache(CONFIG);
+        cache.get().await;
+        tokio::time::advance(Duration::from_secs(40)).await;
+
+        // WHEN
+        let entry = cache.get().await;
+
+        // THEN
+        assert_eq!(entry.value, 2);
+        assert_eq!(entry.age(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn results_are_always_fetched_when_caching_is_disabled() {
+        // GIVEN
+        let cache = counting_cache(CacheConfig {
+            ttl: Duration::ZERO,
+            max_stale: Duration::ZERO,
+            min_refresh_interval: Duration::ZERO,
+        });
+        cache.get().await;
+
+        // WHEN
+        let entry = cache.get().await;
+        let coalesced = get_concurrently(&cache, 10).await;
+
+        // THEN
+        assert_eq!(entry.value, 2);
+        assert_eq!(coalesced, vec![3; 10]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fetches_of_caches_sharing_a_limiter_are_spaced_out() {
+        // GIVEN
+        let config = CacheConfig {
+            ttl: Duration::ZERO,
+            max_stale: Duration::ZERO,
+            min_refresh_interval: Duration::from_secs(10),
+        };
+        let limiter = Arc::new(RefreshLimiter::new(config.min_refresh_interval));
+        let first =
+            Arc::new(ResultsCache::new(config, counting_fetch()).limited_by(Arc::clone(&limiter)));
+        let second =
+            Arc::new(ResultsCache::new(config, counting_fetch()).limited_by(Arc::clone(&limiter)));
+        let started = Instant::now();
+
+        // WHEN
+        tokio::join!(first.get(), second.get());
+        let after_both = started.elapsed();
+        let refreshed = first.get().await;
+        let after_refresh = started.elapsed();
+
+        // THEN
+        // the second fetch has to wait until 10s after the first one started
+        assert_eq!(after_both, Duration::from_secs(11));
+        // and the refresh, until 10s after the second one started
+        assert_eq!(after_refresh, Duration::from_secs(21));
+        assert_eq!(refreshed.value, 2);
+    }
+}
@@ -0,0 +1,158 @@
+use super::auth::{Auth, AuthenticatedUser, MAX_FAILED_AUTH_ATTEMPTS, require_auth};
+use super::deployments::{
+    DeploymentsSource, ServeDeploymentsError, deployments_router, metrics_router, shutdown_signal,
+};
+use super::rate_limit::{ClientRateLimiter, limit_api_requests};
+use crate::common::Environment;
+use aws_sdk_ecs::primitives::{DateTime, DateTimeFormat};
+use axum::Json;
+use axum::extract::Request;
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::{Router, routing::get};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Serves the deployments of several profiles, each at `/p/<profile>/`, along with metrics for
+/// all of them at `/metrics`, for as long as it's left running. Everything but the health check
+/// is behind `auth`; cross-origin requests are only allowed from `cors_origins`; and clients can
+/// make at most `rate_limit` requests to the API per minute.
+pub async fn serve_dashboard(
+    profiles: Vec<(String, DeploymentsSource)>,
+    address: SocketAddr,
+    auth: Auth,
+    cors_origins: Vec<HeaderValue>,
+    rate_limit: u32,
+    env: Environment,
+) -> Result<(), ServeDeploymentsError> {
+    let profile_names = profiles
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+
+    let profiles = profiles
+        .into_iter()
+        .map(|(name, source)| (name, Arc::new(source)))
+        .collect::<Vec<_>>();
+
+    let mut router = Router::new()
+        .route("/", get(move || index_get(profile_names)))
+        .merge(metrics_router(profiles.clone()));
+
+    for (name, source) in profiles {
+        let path = format!("/p/{name}");
+        let redirect_to = format!("{path}/");
+        router = router
+            .route(
+                &path,
+                get(move || async move { Redirect::permanent(&redirect_to) }),
+            )
+            .nest(&format!("{path}/"), deployments_router(source, env));
+    }
+
+    let auth_description = auth.to_string();
+    let router = router
+        .fallback(|| async { (StatusCode::NOT_FOUND, "not found") })
+        .layer(middleware::from_fn_with_state(
+            Arc::new(ClientRateLimiter::new(rate_limit)),
+            limit_api_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            (
+                Arc::new(auth),
+                Arc::new(ClientRateLimiter::new(MAX_FAILED_AUTH_ATTEMPTS)),
+            ),
+            require_auth,
+        ))
+        // load balancers and the like need to be able to check on the server without credentials
+        .route("/healthz", get(healthz_get));
+
+    let cors = match env {
+        Environment::Dev => Some(CorsLayer::new().allow_methods(Any).allow_origin(Any)),
+        Environment::Prod if cors_origins.is_empty() => None,
+        Environment::Prod => Some(
+            CorsLayer::new()
+                .allow_methods([Method::GET])
+                .allow_headers([header::AUTHORIZATION])
+                .allow_origin(AllowOrigin::list(cors_origins)),
+        ),
+    };
+    let router = match cors {
+        Some(cors) => router.layer(cors),
+        None => router,
+    };
+
+    let router = router.layer(middleware::from_fn(log_request));
+
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .map_err(ServeDeploymentsError::CouldntBindToAddress)?;
+
+    println!("serving results on http://{address} (authentication: {auth_description})");
+
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .map_err(ServeDeploymentsError::CouldntStartServer)?;
+
+    Ok(())
+}
+
+async fn index_get(profile_names: Vec<String>) -> impl IntoResponse {
+    // profile names are limited to characters that are safe to use as is
+    let links = profile_names
+        .iter()
+        .map(|name| format!(r#"<li><a href="/p/{name}/">{name}</a></li>"#))
+        .collect::<Vec<_>>()
+        .join("\n      ");
+
+    Html(format!(
+        r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <title>ecscope</title>
+  </head>
+  <body style="background-color:#282828; color:#ebdbb2; font-family:monospace;">
+    <h1>ecscope</h1>
+    <ul>
+      {links}
+    </ul>
+  </body>
+</html>
+"#
+    ))
+}
+
+async fn healthz_get() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Logs every request as a line of JSON.
+async fn log_request(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    let response = next.run(request).await;
+
+    let line = serde_json::json!({
+        "time": DateTime::from(SystemTime::now()).fmt(DateTimeFormat::DateTime).ok(),
+        "method": method,
+        "path": path,
+        "query": query,
+        "status": response.status().as_u16(),
+        "duration_ms": started.elapsed().as_millis(),
+        "user": response.extensions().get::<AuthenticatedUser>().map(|u| &u.0),
+    });
+    println!("{line}");
+
+    response
+}
@@ -410,6 +410,14 @@ function unwrap(option, default$) {
     return default$;
   }
 }
+function map4(option, fun) {
+  if (option instanceof Some) {
+    let x = option[0];
+    return new Some(fun(x));
+  } else {
+    return new None();
+  }
+}
 
 // build/dev/javascript/gleam_stdlib/dict.mjs
 var referenceMap = /* @__PURE__ */ new WeakMap();
@@ -1183,6 +1191,9 @@ function pop_codeunit(str) {
 function lowercase(string6) {
   return string6.toLowerCase();
 }
+function percent_encode(string6) {
+  return encodeURIComponent(string6).replace("%2B", "+");
+}
 function split(xs, pattern) {
   return List.fromArray(xs.split(pattern));
 }
@@ -1544,6 +1555,27 @@ function append_loop(loop$first, loop$second) {
 function append(first2, second) {
   return append_loop(reverse(first2), second);
 }
+function flatten_loop(loop$lists, loop$acc) {
+  while (true) {
+    let lists = loop$lists;
+    let acc = loop$acc;
+    if (lists.hasLength(0)) {
+      return reverse(acc);
+    } else {
+      let list3 = lists.head;
+      let further_lists = lists.tail;
+      loop$lists = further_lists;
+      loop$acc = append_loop(list3, acc);
+    }
+  }
+}
+function flatten(lists) {
+  return flatten_loop(lists, toList([]));
+}
+function flat_map(list3, fun) {
+  let _pipe = map(list3, fun);
+  return flatten(_pipe);
+}
 function fold(loop$list, loop$initial, loop$fun) {
   while (true) {
     let list3 = loop$list;
@@ -1814,6 +1846,9 @@ function string3(data) {
     return new Ok(data);
   return new Error(0);
 }
+function is_null(data) {
+  return data === null || data === void 0;
+}
 
 // build/dev/javascript/gleam_stdlib/gleam/dynamic/decode.mjs
 var DecodeError2 = class extends CustomType {
@@ -1928,6 +1963,21 @@ function list2(inner) {
     }
   );
 }
+function optional(inner) {
+  return new Decoder(
+    (data) => {
+      let $ = is_null(data);
+      if ($) {
+        return [new None(), toList([])];
+      } else {
+        let $1 = inner.function(data);
+        let data$1 = $1[0];
+        let errors = $1[1];
+        return [new Some(data$1), errors];
+      }
+    }
+  );
+}
 function push_path2(layer, path) {
   let decoder = one_of(
     string4,
@@ -2172,6 +2222,9 @@ function parse(json, decoder) {
 function to_string2(json) {
   return json_to_string(json);
 }
+function string5(input2) {
+  return identity2(input2);
+}
 function bool2(input2) {
   return identity2(input2);
 }
@@ -4424,11 +4477,6 @@ async function import_(string6) {
   }
 }
 
-// build/dev/javascript/plinth/global_ffi.mjs
-function setTimeout(delay, callback) {
-  return globalThis.setTimeout(callback, delay);
-}
-
 // build/dev/javascript/deps/types.mjs
 var Deployment = class extends CustomType {
   constructor(service_name, keys2, status, running_count, desired_count, pending_count, failed_count) {
@@ -4457,6 +4505,58 @@ var DeploymentResults = class extends CustomType {
     this.errors = errors;
   }
 };
+var Service = class extends CustomType {
+  constructor(service_name, keys2, status, running_count, desired_count, pending_count, failed_count, task_definition) {
+    super();
+    this.service_name = service_name;
+    this.keys = keys2;
+    this.status = status;
+    this.running_count = running_count;
+    this.desired_count = desired_count;
+    this.pending_count = pending_count;
+    this.failed_count = failed_count;
+    this.task_definition = task_definition;
+  }
+};
+var ServiceEvent = class extends CustomType {
+  constructor(created_at, message) {
+    super();
+    this.created_at = created_at;
+    this.message = message;
+  }
+};
+var ServiceResults = class extends CustomType {
+  constructor(service, deployments, events) {
+    super();
+    this.service = service;
+    this.deployments = deployments;
+    this.events = events;
+  }
+};
+var Container = class extends CustomType {
+  constructor(name, image, last_status, health_status, exit_code, reason) {
+    super();
+    this.name = name;
+    this.image = image;
+    this.last_status = last_status;
+    this.health_status = health_status;
+    this.exit_code = exit_code;
+    this.reason = reason;
+  }
+};
+var Task = class extends CustomType {
+  constructor(task_id, task_definition, last_status, desired_status, health_status, started_at, stopped_reason, containers) {
+    super();
+    this.task_id = task_id;
+    this.task_definition = task_definition;
+    this.last_status = last_status;
+    this.desired_status = desired_status;
+    this.health_status = health_status;
+    this.started_at = started_at;
+    this.stopped_reason = stopped_reason;
+    this.containers = containers;
+  }
+};
 var Loading = class extends CustomType {
 };
 var Loaded = class extends CustomType {
@@ -4471,6 +4571,39 @@ var Errored = class extends CustomType {
     this[0] = x0;
   }
 };
+var PageLoading = class extends CustomType {
+};
+var PageLoaded = class extends CustomType {
+  constructor(x0) {
+    super();
+    this[0] = x0;
+  }
+};
+var PageErrored = class extends CustomType {
+  constructor(x0) {
+    super();
+    this[0] = x0;
+  }
+};
+var DeploymentsPage = class extends CustomType {
+};
+var ServicePage = class extends CustomType {
+  constructor(keys2, service_name, service, tasks) {
+    super();
+    this.keys = keys2;
+    this.service_name = service_name;
+    this.service = service;
+    this.tasks = tasks;
+  }
+};
+var TaskPage = class extends CustomType {
+  constructor(keys2, service_name, task) {
+    super();
+    this.keys = keys2;
+    this.service_name = service_name;
+    this.task = task;
+  }
+};
 var FetchResults = class extends CustomType {
 };
 var AutoRefreshToggled = class extends CustomType {
@@ -4479,19 +4612,38 @@ var AutoRefreshToggled = class extends CustomType {
     this[0] = x0;
   }
 };
-var AutoRefreshScheduleChanged = class extends CustomType {
+var ResultsFetched = class extends CustomType {
   constructor(x0) {
     super();
     this[0] = x0;
   }
 };
-var ResultsFetched = class extends CustomType {
+var DeploymentsSelected = class extends CustomType {
+};
+var ServiceSelected = class extends CustomType {
+  constructor(keys2, service_name) {
+    super();
+    this.keys = keys2;
+    this.service_name = service_name;
+  }
+};
+var ServiceFetched = class extends CustomType {
   constructor(x0) {
     super();
     this[0] = x0;
   }
 };
-var Tick = class extends CustomType {
+var TasksFetched = class extends CustomType {
+  constructor(x0) {
+    super();
+    this[0] = x0;
+  }
+};
+var TaskSelected = class extends CustomType {
+  constructor(x0) {
+    super();
+    this[0] = x0;
+  }
 };
 function deployment_decoder() {
   return field2(
@@ -4586,6 +4738,249 @@ function deployment_results_decoder() {
     }
   );
 }
+function service_decoder() {
+  return field2(
+    "service_name",
+    string4,
+    (service_name) => {
+      return field2(
+        "keys",
+        string4,
+        (keys2) => {
+          return field2(
+            "status",
+            string4,
+            (status) => {
+              return field2(
+                "running_count",
+                int2,
+                (running_count) => {
+                  return field2(
+                    "desired_count",
+                    int2,
+                    (desired_count) => {
+                      return field2(
+                        "pending_count",
+                        int2,
+                        (pending_count) => {
+                          return field2(
+                            "failed_count",
+                            int2,
+                            (failed_count) => {
+                              return field2(
+                                "task_definition",
+                                optional(string4),
+                                (task_definition) => {
+                                  return success(
+                                    new Service(
+                                      service_name,
+                                      keys2,
+                                      status,
+                                      running_count,
+                                      desired_count,
+                                      pending_count,
+                                      failed_count,
+                                      task_definition
+                                    )
+                                  );
+                                }
+                              );
+                            }
+                          );
+                        }
+                      );
+                    }
+                  );
+                }
+              );
+            }
+          );
+        }
+      );
+    }
+  );
+}
+function service_event_decoder() {
+  return field2(
+    "created_at",
+    optional(int2),
+    (created_at) => {
+      return field2(
+        "message",
+        string4,
+        (message) => {
+          return success(
+            new ServiceEvent(
+              created_at,
+              message
+            )
+          );
+        }
+      );
+    }
+  );
+}
+function service_results_decoder() {
+  return field2(
+    "service",
+    service_decoder(),
+    (service) => {
+      return field2(
+        "deployments",
+        list2(deployment_decoder()),
+        (deployments) => {
+          return field2(
+            "events",
+            list2(service_event_decoder()),
+            (events) => {
+              return success(
+                new ServiceResults(
+                  service,
+                  deployments,
+                  events
+                )
+              );
+            }
+          );
+        }
+      );
+    }
+  );
+}
+function container_decoder() {
+  return field2(
+    "name",
+    string4,
+    (name) => {
+      return field2(
+        "image",
+        optional(string4),
+        (image) => {
+          return field2(
+            "last_status",
+            optional(string4),
+            (last_status) => {
+              return field2(
+                "health_status",
+                optional(string4),
+                (health_status) => {
+                  return field2(
+                    "exit_code",
+                    optional(int2),
+                    (exit_code) => {
+                      return field2(
+                        "reason",
+                        optional(string4),
+                        (reason) => {
+                          return success(
+                            new Container(
+                              name,
+                              image,
+                              last_status,
+                              health_status,
+                              exit_code,
+                              reason
+                            )
+                          );
+                        }
+                      );
+                    }
+                  );
+                }
+              );
+            }
+          );
+        }
+      );
+    }
+  );
+}
+function task_decoder() {
+  return field2(
+    "task_id",
+    string4,
+    (task_id) => {
+      return field2(
+        "task_definition",
+        optional(string4),
+        (task_definition) => {
+          return field2(
+            "last_status",
+            optional(string4),
+            (last_status) => {
+              return field2(
+                "desired_status",
+                optional(string4),
+                (desired_status) => {
+                  return field2(
+                    "health_status",
+                    optional(string4),
+                    (health_status) => {
+                      return field2(
+                        "started_at",
+                        optional(int2),
+                        (started_at) => {
+                          return field2(
+                            "stopped_reason",
+                            optional(string4),
+                            (stopped_reason) => {
+                              return field2(
+                                "containers",
+                                list2(container_decoder()),
+                                (containers) => {
+                                  return success(
+                                    new Task(
+                                      task_id,
+                                      task_definition,
+                                      last_status,
+                                      desired_status,
+                                      health_status,
+                                      started_at,
+                                      stopped_reason,
+                                      containers
+                                    )
+                                  );
+                                }
+                              );
+                            }
+                          );
+                        }
+                      );
+                    }
+                  );
+                }
+              );
+            }
+          );
+        }
+      );
+    }
+  );
+}
+function tasks_decoder() {
+  return field2(
+    "tasks",
+    list2(task_decoder()),
+    (tasks) => {
+      return success(tasks);
+    }
+  );
+}
+
+// build/dev/javascript/deps/event_source_ffi.mjs
+var source = null;
+function subscribe(url, on_results, on_failure) {
+  unsubscribe();
+  source = new EventSource(url);
+  source.addEventListener("deployments", (event2) => on_results(event2.data));
+  source.addEventListener("failure", (event2) => on_failure(event2.data));
+}
+function unsubscribe() {
+  if (source !== null) {
+    source.close();
+    source = null;
+  }
+}
 
 // build/dev/javascript/deps/effects.mjs
 function fetch_deps() {
@@ -4595,40 +4990,114 @@ function fetch_deps() {
       return new ResultsFetched(var0);
     }
   );
-  return get(location() + "api/deps", expect);
+  return get(location() + "api/v1/deps", expect);
 }
-function schedule_next_tick(delay_seconds) {
+function service_path(keys2, service_name) {
+  return "api/v1/services/" + percent_encode(keys2) + "/" + percent_encode(
+    service_name
+  );
+}
+function fetch_service(keys2, service_name) {
+  let expect = expect_json(
+    service_results_decoder(),
+    (var0) => {
+      return new ServiceFetched(var0);
+    }
+  );
+  return get(location() + service_path(keys2, service_name), expect);
+}
+function fetch_tasks(keys2, service_name) {
+  let expect = expect_json(
+    tasks_decoder(),
+    (var0) => {
+      return new TasksFetched(var0);
+    }
+  );
+  return get(
+    location() + service_path(keys2, service_name) + "/tasks",
+    expect
+  );
+}
+function api_error_decoder() {
+  return field2(
+    "error",
+    string4,
+    (error) => {
+      return success(error);
+    }
+  );
+}
+function subscribe_to_deps() {
   return from(
     (dispatch) => {
-      setTimeout(
-        delay_seconds * 1e3,
-        () => {
-          return dispatch(new Tick());
+      return subscribe(
+        location() + "api/v1/deps/stream",
+        (data) => {
+          let _pipe = parse(data, deployment_results_decoder());
+          let results = map_error(
+            _pipe,
+            (var0) => {
+              return new JsonError(var0);
+            }
+          );
+          return dispatch(new ResultsFetched(results));
+        },
+        (data) => {
+          let error = (() => {
+            let $ = parse(data, api_error_decoder());
+            if ($.isOk()) {
+              let message = $[0];
+              return message;
+            } else {
+              return data;
+            }
+          })();
+          return dispatch(
+            new ResultsFetched(new Error(new InternalServerError(error)))
+          );
         }
       );
-      return void 0;
     }
   );
 }
+function unsubscribe_from_deps() {
+  return from((_) => {
+    return unsubscribe();
+  });
+}
 
 // build/dev/javascript/deps/model.mjs
 var Model2 = class extends CustomType {
-  constructor(status, reload_seconds, auto_refresh, fetching, debug) {
+  constructor(status, page, auto_refresh, fetching, debug) {
     super();
     this.status = status;
-    this.reload_seconds = reload_seconds;
+    this.page = page;
     this.auto_refresh = auto_refresh;
     this.fetching = fetching;
     this.debug = debug;
   }
 };
 function init_model() {
-  return new Model2(new Loading(), 5, false, true, false);
+  return new Model2(new Loading(), new DeploymentsPage(), false, true, false);
+}
+function page_name(page) {
+  if (page instanceof DeploymentsPage) {
+    return "deployments";
+  } else if (page instanceof ServicePage) {
+    let keys2 = page.keys;
+    let service_name = page.service_name;
+    return "service " + keys2 + "/" + service_name;
+  } else {
+    let keys2 = page.keys;
+    let service_name = page.service_name;
+    let task = page.task;
+    return "task " + keys2 + "/" + service_name + "/" + task.task_id;
+  }
 }
 function encode_model(model) {
   return object2(
     toList([
-      ["reload_seconds", int3(model.reload_seconds)],
+      ["page", string5(page_name(model.page))],
       ["auto_refresh", bool2(model.auto_refresh)],
       ["fetching", bool2(model.fetching)],
       ["debug", bool2(model.debug)]
@@ -4637,66 +5106,31 @@ function encode_model(model) {
 }
 
 // build/dev/javascript/deps/update.mjs
+function page_status(result) {
+  if (result.isOk()) {
+    let value4 = result[0];
+    return new PageLoaded(value4);
+  } else {
+    let err = result[0];
+    return new PageErrored(err);
+  }
+}
 function update(model, msg) {
-  if (msg instanceof AutoRefreshScheduleChanged) {
-    let seconds_string = msg[0];
-    let $ = parse_int(seconds_string);
-    if (!$.isOk()) {
-      return [model, none()];
-    } else {
-      let seconds = $[0];
-      if (seconds >= 5 && seconds <= 60) {
-        let s = seconds;
-        return [
-          (() => {
-            let _record = model;
-            return new Model2(
-              _record.status,
-              s,
-              _record.auto_refresh,
-              _record.fetching,
-              _record.debug
-            );
-          })(),
-          none()
-        ];
-      } else {
-        return [
-          (() => {
-            let _record = model;
-            return new Model2(
-              _record.status,
-              5,
-              _record.auto_refresh,
-              _record.fetching,
-              _record.debug
-            );
-          })(),
-          none()
-        ];
-      }
-    }
-  } else if (msg instanceof AutoRefreshToggled) {
+  if (msg instanceof AutoRefreshToggled) {
     let checked3 = msg[0];
-    if (checked3 && !model.fetching) {
-      let c = checked3;
+    if (checked3) {
       return [
         (() => {
           let _record = model;
           return new Model2(
             _record.status,
-            _record.reload_seconds,
+            _record.page,
             true,
             true,
             _record.debug
           );
         })(),
-        batch(
-          toList([
-            fetch_deps(),
-            schedule_next_tick(model.reload_seconds)
-          ])
-        )
+        subscribe_to_deps()
       ];
     } else {
       return [
@@ -4704,13 +5138,13 @@ function update(model, msg) {
           let _record = model;
           return new Model2(
             _record.status,
-            _record.reload_seconds,
-            checked3,
-            _record.fetching,
+            _record.page,
+            false,
+            false,
             _record.debug
           );
         })(),
-        none()
+        unsubscribe_from_deps()
       ];
     }
   } else if (msg instanceof FetchResults) {
@@ -4719,7 +5153,7 @@ function update(model, msg) {
         let _record = model;
         return new Model2(
           _record.status,
-          _record.reload_seconds,
+          _record.page,
           _record.auto_refresh,
           true,
           _record.debug
@@ -4736,7 +5170,7 @@ function update(model, msg) {
           let _record = model;
           return new Model2(
             new Errored(err),
-            _record.reload_seconds,
+            _record.page,
             _record.auto_refresh,
             false,
             _record.debug
@@ -4751,7 +5185,7 @@ function update(model, msg) {
           let _record = model;
           return new Model2(
             new Loaded(results$1),
-            _record.reload_seconds,
+            _record.page,
             _record.auto_refresh,
             false,
             _record.debug
@@ -4760,32 +5194,111 @@ function update(model, msg) {
         none()
       ];
     }
+  } else if (msg instanceof DeploymentsSelected) {
+    return [
+      (() => {
+        let _record = model;
+        return new Model2(
+          _record.status,
+          new DeploymentsPage(),
+          _record.auto_refresh,
+          _record.fetching,
+          _record.debug
+        );
+      })(),
+      none()
+    ];
+  } else if (msg instanceof ServiceSelected) {
+    let keys2 = msg.keys;
+    let service_name = msg.service_name;
+    return [
+      (() => {
+        let _record = model;
+        return new Model2(
+          _record.status,
+          new ServicePage(
+            keys2,
+            service_name,
+            new PageLoading(),
+            new PageLoading()
+          ),
+          _record.auto_refresh,
+          _record.fetching,
+          _record.debug
+        );
+      })(),
+      batch(
+        toList([
+          fetch_service(keys2, service_name),
+          fetch_tasks(keys2, service_name)
+        ])
+      )
+    ];
+  } else if (msg instanceof ServiceFetched) {
+    let result = msg[0];
+    let $ = model.page;
+    if ($ instanceof ServicePage) {
+      let keys2 = $.keys;
+      let service_name = $.service_name;
+      let tasks = $.tasks;
+      return [
+        (() => {
+          let _record = model;
+          return new Model2(
+            _record.status,
+            new ServicePage(keys2, service_name, page_status(result), tasks),
+            _record.auto_refresh,
+            _record.fetching,
+            _record.debug
+          );
+        })(),
+        none()
+      ];
+    } else {
+      return [model, none()];
+    }
+  } else if (msg instanceof TasksFetched) {
+    let result = msg[0];
+    let $ = model.page;
+    if ($ instanceof ServicePage) {
+      let keys2 = $.keys;
+      let service_name = $.service_name;
+      let service = $.service;
+      return [
+        (() => {
+          let _record = model;
+          return new Model2(
+            _record.status,
+            new ServicePage(keys2, service_name, service, page_status(result)),
+            _record.auto_refresh,
+            _record.fetching,
+            _record.debug
+          );
+        })(),
+        none()
+      ];
+    } else {
+      return [model, none()];
+    }
   } else {
-    let $ = model.auto_refresh;
-    if ($) {
-      let $1 = model.fetching;
-      if ($1) {
-        return [model, schedule_next_tick(model.reload_seconds)];
-      } else {
-        return [
-          (() => {
-            let _record = model;
-            return new Model2(
-              _record.status,
-              _record.reload_seconds,
-              _record.auto_refresh,
-              true,
-              _record.debug
-            );
-          })(),
-          batch(
-            toList([
-              fetch_deps(),
-              schedule_next_tick(model.reload_seconds)
-            ])
-          )
-        ];
-      }
+    let task = msg[0];
+    let $ = model.page;
+    if ($ instanceof ServicePage) {
+      let keys2 = $.keys;
+      let service_name = $.service_name;
+      return [
+        (() => {
+          let _record = model;
+          return new Model2(
+            _record.status,
+            new TaskPage(keys2, service_name, task),
+            _record.auto_refresh,
+            _record.fetching,
+            _record.debug
+          );
+        })(),
+        none()
+      ];
     } else {
       return [model, none()];
     }
@@ -4838,6 +5351,9 @@ function button(attrs, children2) {
 function input(attrs) {
   return element("input", attrs, toList([]));
 }
+function span(attrs, children2) {
+  return element("span", attrs, children2);
+}
 function label(attrs, children2) {
   return element("label", attrs, children2);
 }
@@ -4882,6 +5398,11 @@ function on_check(msg) {
   );
 }
 
+// build/dev/javascript/deps/utils_ffi.mjs
+function format_timestamp(seconds) {
+  return new Date(seconds * 1e3).toLocaleString();
+}
+
 // build/dev/javascript/deps/utils.mjs
 function http_error_to_string(error) {
   if (error instanceof BadUrl) {
@@ -4992,29 +5513,7 @@ function fetch_controls_div(model) {
               class$("items-center space-x-2"),
               for$("auto-refresh-toggle")
             ]),
-            toList([text("auto refresh every")])
-          ),
-          input(
-            toList([
-              class$(
-                "h-8 text-center text-[#ebdbb2] bg-[#3c3836] focus:ring-[#fabd2f] pl-5 pr-2"
-              ),
-              id("auto-refresh-interval"),
-              type_("number"),
-              min("5"),
-              max("60"),
-              value(to_string(model.reload_seconds)),
-              disabled(model.auto_refresh),
-              on_input(
-                (var0) => {
-                  return new AutoRefreshScheduleChanged(var0);
-                }
-              )
-            ])
-          ),
-          p(
-            toList([class$("items-center space-x-2")]),
-            toList([text("seconds")])
+            toList([text("live updates")])
           ),
           div(
             toList([]),
@@ -5085,15 +5584,21 @@ function table_row_class(deployment) {
 }
 function service_name_table_data(deployment) {
   let service_color = color_for_string(deployment.service_name);
+  let link = span(
+    toList([
+      class$("service-link"),
+      on_click(
+        new ServiceSelected(deployment.keys, deployment.service_name)
+      )
+    ]),
+    toList([text(deployment.service_name)])
+  );
   if (deployment.failed_count > 0 || deployment.status === "ACTIVE" || deployment.running_count !== deployment.desired_count || deployment.status === "DRAINING") {
-    return td(
-      toList([class$("font-semibold")]),
-      toList([text(deployment.service_name)])
-    );
+    return td(toList([class$("font-semibold")]), toList([link]));
   } else {
     return td(
       toList([class$("font-semibold " + service_color)]),
-      toList([text(deployment.service_name)])
+      toList([link])
     );
   }
 }
@@ -5287,20 +5792,316 @@ function results_div(results) {
   })();
   return div(toList([id("deployment-results")]), results$1);
 }
-function main_div(model) {
+function back_link(msg) {
   return div(
-    toList([class$("mb-8")]),
+    toList([class$("mb-4")]),
+    toList([
+      button(
+        toList([
+          id("back"),
+          class$("font-semibold px-4 py-1 bg-[#d3869b] text-[#282828]"),
+          on_click(msg)
+        ]),
+        toList([text("Back")])
+      )
+    ])
+  );
+}
+function details_list(entries) {
+  return div(
+    toList([class$("details")]),
+    flat_map(
+      entries,
+      (entry) => {
+        return toList([
+          p(toList([class$("font-semibold")]), toList([text(entry[0])])),
+          p(toList([]), toList([text(entry[1])]))
+        ]);
+      }
+    )
+  );
+}
+function task_status(task) {
+  let last_status = unwrap(task.last_status, "-");
+  let $ = task.desired_status;
+  if ($ instanceof Some && $[0] !== last_status) {
+    let desired = $[0];
+    return last_status + " \u2192 " + desired;
+  } else {
+    return last_status;
+  }
+}
+function timestamp(seconds) {
+  if (seconds instanceof Some) {
+    let seconds$1 = seconds[0];
+    return format_timestamp(seconds$1);
+  } else {
+    return "-";
+  }
+}
+function service_summary_div(service) {
+  return div(
+    toList([id("service-summary"), class$("mb-8")]),
+    toList([
+      h2(
+        toList([class$("text-xl font-bold mb-6 text-[#83a598]")]),
+        toList([text(service.service_name + " (" + service.keys + ")")])
+      ),
+      details_list(
+        toList([
+          ["Status", service.status],
+          ["Task definition", unwrap(service.task_definition, "-")],
+          ["Running", to_string(service.running_count)],
+          ["Desired", to_string(service.desired_count)],
+          ["Pending", to_string(service.pending_count)],
+          ["Failed", to_string(service.failed_count)]
+        ])
+      )
+    ])
+  );
+}
+function service_events_div(events) {
+  return div(
+    toList([id("service-events")]),
+    toList([
+      h2(
+        toList([class$("text-xl font-bold mb-6 text-[#83a598]")]),
+        toList([text("Events")])
+      ),
+      table(
+        toList([
+          class$("table-auto w-full px-4 py-2"),
+          id("service-events-table")
+        ]),
+        toList([
+          thead(
+            toList([]),
+            toList([
+              tr(
+                toList([]),
+                toList([
+                  th(toList([]), toList([text("Time")])),
+                  th(toList([]), toList([text("Message")]))
+                ])
+              )
+            ])
+          ),
+          tbody(
+            toList([]),
+            map(
+              events,
+              (event2) => {
+                return tr(
+                  toList([class$("border-y-2 border-[#504945]")]),
+                  toList([
+                    td(
+                      toList([]),
+                      toList([text(timestamp(event2.created_at))])
+                    ),
+                    td(toList([]), toList([text(event2.message)]))
+                  ])
+                );
+              }
+            )
+          )
+        ])
+      )
+    ])
+  );
+}
+function task_table_row(task) {
+  return tr(
+    toList([class$("border-y-2 border-[#504945]")]),
+    toList([
+      td(
+        toList([class$("font-semibold")]),
+        toList([
+          span(
+            toList([
+              class$("service-link"),
+              on_click(new TaskSelected(task))
+            ]),
+            toList([text(task.task_id)])
+          )
+        ])
+      ),
+      td(toList([]), toList([text(unwrap(task.task_definition, "-"))])),
+      td(toList([]), toList([text(task_status(task))])),
+      td(toList([]), toList([text(unwrap(task.health_status, "-"))])),
+      td(toList([]), toList([text(timestamp(task.started_at))]))
+    ])
+  );
+}
+function tasks_div(tasks) {
+  return div(
+    toList([id("service-tasks")]),
+    toList([
+      h2(
+        toList([class$("text-xl font-bold mb-6 text-[#83a598]")]),
+        toList([text("Tasks")])
+      ),
+      table(
+        toList([
+          class$("table-auto w-full px-4 py-2"),
+          id("service-tasks-table")
+        ]),
+        toList([
+          thead(
+            toList([]),
+            toList([
+              tr(
+                toList([]),
+                toList([
+                  th(toList([]), toList([text("Task")])),
+                  th(toList([]), toList([text("Task definition")])),
+                  th(toList([]), toList([text("Status")])),
+                  th(toList([]), toList([text("Health")])),
+                  th(toList([]), toList([text("Started")]))
+                ])
+              )
+            ])
+          ),
+          tbody(toList([]), map(tasks, task_table_row))
+        ])
+      )
+    ])
+  );
+}
+function service_page_div(service, tasks) {
+  return div(
+    toList([id("service-page")]),
     toList([
       (() => {
-        let $ = model.status;
-        if ($ instanceof Errored) {
-          let error = $[0];
+        if (service instanceof PageLoading) {
+          return loading_div();
+        } else if (service instanceof PageErrored) {
+          let error = service[0];
           return http_error_div(error);
-        } else if ($ instanceof Loaded) {
-          let results = $[0];
-          return results_div(results);
         } else {
+          let results = service[0];
+          return div(
+            toList([]),
+            toList([
+              service_summary_div(results.service),
+              deployments_table(results.deployments),
+              deployment_results_divider(),
+              service_events_div(results.events)
+            ])
+          );
+        }
+      })(),
+      deployment_results_divider(),
+      (() => {
+        if (tasks instanceof PageLoading) {
           return loading_div();
+        } else if (tasks instanceof PageErrored) {
+          let error = tasks[0];
+          return http_error_div(error);
+        } else {
+          let tasks$1 = tasks[0];
+          return tasks_div(tasks$1);
+        }
+      })()
+    ])
+  );
+}
+function container_table_row(container) {
+  return tr(
+    toList([class$("border-y-2 border-[#504945]")]),
+    toList([
+      td(toList([class$("font-semibold")]), toList([text(container.name)])),
+      td(toList([]), toList([text(unwrap(container.image, "-"))])),
+      td(toList([]), toList([text(unwrap(container.last_status, "-"))])),
+      td(toList([]), toList([text(unwrap(container.health_status, "-"))])),
+      td(
+        toList([]),
+        toList([
+          text(
+            (() => {
+              let _pipe = container.exit_code;
+              let _pipe$1 = map4(_pipe, to_string);
+              return unwrap(_pipe$1, "-");
+            })()
+          )
+        ])
+      ),
+      td(toList([]), toList([text(unwrap(container.reason, "-"))]))
+    ])
+  );
+}
+function task_page_div(task) {
+  return div(
+    toList([id("task-page")]),
+    toList([
+      h2(
+        toList([class$("text-xl font-bold mb-6 text-[#83a598]")]),
+        toList([text("Task " + task.task_id)])
+      ),
+      details_list(
+        toList([
+          ["Task definition", unwrap(task.task_definition, "-")],
+          ["Status", task_status(task)],
+          ["Health", unwrap(task.health_status, "-")],
+          ["Started", timestamp(task.started_at)],
+          ["Stopped reason", unwrap(task.stopped_reason, "-")]
+        ])
+      ),
+      h2(
+        toList([class$("text-xl font-bold my-10 text-[#83a598]")]),
+        toList([text("Containers")])
+      ),
+      table(
+        toList([
+          class$("table-auto w-full px-4 py-2"),
+          id("task-containers-table")
+        ]),
+        toList([
+          thead(
+            toList([]),
+            toList([
+              tr(
+                toList([]),
+                toList([
+                  th(toList([]), toList([text("Container")])),
+                  th(toList([]), toList([text("Image")])),
+                  th(toList([]), toList([text("Status")])),
+                  th(toList([]), toList([text("Health")])),
+                  th(toList([]), toList([text("Exit code")])),
+                  th(toList([]), toList([text("Reason")]))
+                ])
+              )
+            ])
+          ),
+          tbody(toList([]), map(task.containers, container_table_row))
+        ])
+      )
+    ])
+  );
+}
+function main_div(model) {
+  return div(
+    toList([class$("mb-8")]),
+    toList([
+      (() => {
+        let $ = model.page;
+        if ($ instanceof DeploymentsPage) {
+          let $1 = model.status;
+          if ($1 instanceof Errored) {
+            let error = $1[0];
+            return http_error_div(error);
+          } else if ($1 instanceof Loaded) {
+            let results = $1[0];
+            return results_div(results);
+          } else {
+            return loading_div();
+          }
+        } else if ($ instanceof ServicePage) {
+          let service = $.service;
+          let tasks = $.tasks;
+          return service_page_div(service, tasks);
+        } else {
+          let task = $.task;
+          return task_page_div(task);
         }
       })()
     ])
@@ -5318,7 +6119,18 @@ function view(model) {
             toList([
               model_debug_div(model),
               heading(model.fetching),
-              fetch_controls_div(model),
+              (() => {
+                let $ = model.page;
+                if ($ instanceof DeploymentsPage) {
+                  return fetch_controls_div(model);
+                } else if ($ instanceof ServicePage) {
+                  return back_link(new DeploymentsSelected());
+                } else {
+                  let keys2 = $.keys;
+                  let service_name = $.service_name;
+                  return back_link(new ServiceSelected(keys2, service_name));
+                }
+              })(),
               main_div(model)
             ])
           )
@@ -0,0 +1,16 @@
+let source = null;
+
+export function subscribe(url, on_results, on_failure) {
+  unsubscribe();
+
+  source = new EventSource(url);
+  source.addEventListener("deployments", (event) => on_results(event.data));
+  source.addEventListener("failure", (event) => on_failure(event.data));
+}
+
+export function unsubscribe() {
+  if (source !== null) {
+    source.close();
+    source = null;
+  }
+}
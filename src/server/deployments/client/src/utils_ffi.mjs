@@ -0,0 +1,3 @@
+export function format_timestamp(seconds) {
+  return new Date(seconds * 1000).toLocaleString();
+}
@@ -0,0 +1,443 @@
+use super::serve::{
+    ApiError, DeploymentsResults, DeploymentsSource, GetDeploymentsResponse, REDACTED, to_response,
+};
+use crate::domain::DeploymentDetails;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::{Router, routing::get};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Picks one of the task counts of a deployment.
+type TaskCount = fn(&DeploymentDetails) -> i32;
+
+/// Serves the deployments of profiles as Prometheus metrics, at `/metrics`.
+pub(crate) fn metrics_router(profiles: Vec<(String, Arc<DeploymentsSource>)>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_get))
+        .with_state(Arc::new(MetricsEndpoint::new(profiles)))
+}
+
+struct MetricsEndpoint {
+    profiles: Vec<(String, Arc<DeploymentsSource>)>,
+}
+
+/// The metrics of a profile that add up across fetches. They're counted as deployments are
+/// fetched (for whichever cache of the profile), so they don't depend on how often they're
+/// scraped.
+#[derive(Debug, Default)]
+pub(crate) struct FetchCounters(Mutex<Counters>);
+
+#[derive(Debug, Default, Clone)]
+struct Counters {
+    /// Errors per cluster key and cluster ARN
+    cluster_errors: BTreeMap<(String, String), u64>,
+    /// Fetches that failed altogether
+    fetch_failures: u64,
+}
+
+impl FetchCounters {
+    pub(super) fn count(&self, results: &DeploymentsResults, redact: bool) {
+        let mut counters = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match results {
+            Ok((_, errors)) => {
+                for error in errors {
+                    let cluster_arn = if redact {
+                        REDACTED.to_string()
+                    } else {
+                        error.cluster_arn.clone()
+                    };
+                    *counters
+                        .cluster_errors
+                        .entry((error.keys.clone(), cluster_arn))
+                        .or_default() += 1;
+                }
+            }
+            Err(_) => counters.fetch_failures += 1,
+        }
+    }
+
+    fn get(&self) -> Counters {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// The latest results of a profile, as of a scrape.
+struct ProfileSample {
+    profile: String,
+    results: Result<GetDeploymentsResponse, ApiError>,
+    fetch_duration: Duration,
+    age: Duration,
+    counters: Counters,
+}
+
+impl MetricsEndpoint {
+    fn new(profiles: Vec<(String, Arc<DeploymentsSource>)>) -> Self {
+        Self { profiles }
+    }
+
+    async fn scrape(&self) -> String {
+        let mut samples = Vec::with_capacity(self.profiles.len());
+        for (profile, source) in &self.profiles {
+            // every deployment is reported, regardless of state
+            let results = source.cache(None).get().await;
+            samples.push(ProfileSample {
+                profile: profile.clone(),
+                results: to_response(&results, None, source.redact),
+                fetch_duration: results.fetch_duration,
+                age: results.age(),
+                counters: source.fetch_counters.get(),
+            });
+        }
+
+        render(&samples)
+    }
+}
+
+async fn metrics_get(State(endpoint): State<Arc<MetricsEndpoint>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        endpoint.scrape().await,
+    )
+}
+
+fn render(samples: &[ProfileSample]) -> String {
+    let mut out = String::new();
+
+    header_lines(
+        &mut out,
+        "ecscope_up",
+        "gauge",
+        "Whether deployments of the profile could be fetched",
+    );
+    for sample in samples {
+        let up = u8::from(sample.results.is_ok());
+        line(&mut out, "ecscope_up", &[("profile", &sample.profile)], up);
+    }
+
+    let task_counts: [(&str, &str, TaskCount); 4] = [
+        (
+            "ecscope_deployment_running_tasks",
+            "Number of running tasks of the service's deployments with the status",
+            |d| d.running_count,
+        ),
+        (
+            "ecscope_deployment_desired_tasks",
+            "Number of tasks the service's deployments with the status are meant to run",
+            |d| d.desired_count,
+        ),
+        (
+            "ecscope_deployment_pending_tasks",
+            "Number of pending tasks of the service's deployments with the status",
+            |d| d.pending_count,
+        ),
+        (
+            "ecscope_deployment_failed_tasks",
+            "Number of tasks of the service's deployments with the status that failed to launch",
+            |d| d.failed_count,
+        ),
+    ];
+    for (name, help, count) in task_counts {
+        header_lines(&mut out, name, "gauge", help);
+        for sample in samples {
+            let Ok(response) = &sample.results else {
+                continue;
+            };
+            // deployments come and go, so they're not labelled individually; the counts of a
+            // service's deployments with the same status add up instead
+            let mut counts: BTreeMap<_, i32> = BTreeMap::new();
+            for deployment in &response.deployments {
+                *counts
+                    .entry((
+                        &deployment.service_name,
+                        &deployment.keys,
+                        &deployment.cluster_arn,
+                        &deployment.status,
+                    ))
+                    .or_default() += count(deployment);
+            }
+            for ((service, keys, cluster_arn, status), count) in counts {
+                line(
+                    &mut out,
+                    name,
+                    &[
+                        ("profile", &sample.profile),
+                        ("service", service),
+                        ("cluster_key", keys),
+                        ("cluster_arn", cluster_arn),
+                        ("status", status),
+                    ],
+                    count,
+                );
+            }
+        }
+    }
+
+    header_lines(
+        &mut out,
+        "ecscope_scrape_duration_seconds",
+        "gauge",
+        "How long fetching the deployments of the profile took",
+    );
+    for sample in samples {
+        line(
+            &mut out,
+            "ecscope_scrape_duration_seconds",
+            &[("profile", &sample.profile)],
+            sample.fetch_duration.as_secs_f64(),
+        );
+    }
+
+    header_lines(
+        &mut out,
+        "ecscope_results_age_seconds",
+        "gauge",
+        "How long ago the deployments of the profile were fetched",
+    );
+    for sample in samples {
+        line(
+            &mut out,
+            "ecscope_results_age_seconds",
+            &[("profile", &sample.profile)],
+            sample.age.as_secs_f64(),
+        );
+    }
+
+    header_lines(
+        &mut out,
+        "ecscope_cluster_errors_total",
+        "counter",
+        "Number of errors encountered while fetching deployments from the cluster",
+    );
+    for sample in samples {
+        for ((keys, cluster_arn), count) in &sample.counters.cluster_errors {
+            line(
+                &mut out,
+                "ecscope_cluster_errors_total",
+                &[
+                    ("profile", &sample.profile),
+                    ("cluster_key", keys),
+                    ("cluster_arn", cluster_arn),
+                ],
+                count,
+            );
+        }
+    }
+
+    header_lines(
+        &mut out,
+        "ecscope_fetch_failures_total",
+        "counter",
+        "Number of times deployments of the profile couldn't be fetched at all",
+    );
+    for sample in samples {
+        line(
+            &mut out,
+            "ecscope_fetch_failures_total",
+            &[("profile", &sample.profile)],
+            sample.counters.fetch_failures,
+        );
+    }
+
+    out
+}
+
+fn header_lines(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn line(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| format!(r#"{k}="{}""#, escape_label_value(v)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let _ = writeln!(out, "{name}{{{labels}}} {value}");
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DeploymentError;
+    use insta::assert_snapshot;
+    use std::time::UNIX_EPOCH;
+
+    fn error(cluster: &str) -> DeploymentError {
+        DeploymentError {
+            service_name: "payments-api".to_string(),
+            error: "couldn't describe services".to_string(),
+            cluster_arn: format!("arn:aws:ecs:eu-central-1:111111111111:cluster/{cluster}"),
+            keys: cluster.to_string(),
+            service_alias: None,
+        }
+    }
+
+    fn response(errors: Vec<DeploymentError>) -> GetDeploymentsResponse {
+        GetDeploymentsResponse::new(
+            vec![
+                DeploymentDetails {
+                    cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+                    deployment_id: "ecs-svc/1111111111111111111".to_string(),
+                    ..DeploymentDetails::dummy_running("auth-api", "prod")
+                },
+                DeploymentDetails {
+                    cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/qa".to_string(),
+                    deployment_id: "ecs-svc/2222222222222222222".to_string(),
+                    ..DeploymentDetails::dummy_failing("payments-api", "qa")
+                },
+            ],
+            errors,
+            UNIX_EPOCH,
+            Duration::ZERO,
+        )
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn deployments_are_rendered_as_metrics() {
+        // GIVEN
+        let prod_counters = FetchCounters::default();
+        prod_counters.count(&Ok((Vec::new(), vec![error("qa")])), false);
+        let staging_counters = FetchCounters::default();
+        staging_counters.count(&Err("couldn't fetch".to_string()), false);
+        let mut prod_response = response(vec![error("qa")]);
+        // deployments of a service with the same status add up
+        prod_response.deployments.push(DeploymentDetails {
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/qa".to_string(),
+            deployment_id: "ecs-svc/3333333333333333333".to_string(),
+            ..DeploymentDetails::dummy_failing("payments-api", "qa")
+        });
+        let samples = vec![
+            ProfileSample {
+                profile: "prod".to_string(),
+                results: Ok(prod_response),
+                fetch_duration: Duration::from_millis(1250),
+                age: Duration::from_secs(3),
+                counters: prod_counters.get(),
+            },
+            ProfileSample {
+                profile: "staging".to_string(),
+                results: Err(ApiError {
+                    error: "clients_map did not have entry for cluster".to_string(),
+                }),
+                fetch_duration: Duration::from_millis(10),
+                age: Duration::ZERO,
+                counters: staging_counters.get(),
+            },
+        ];
+
+        // WHEN
+        let metrics = render(&samples);
+
+        // THEN
+        assert_snapshot!(metrics, @r#"
+        # HELP ecscope_up Whether deployments of the profile could be fetched
+        # TYPE ecscope_up gauge
+        ecscope_up{profile="prod"} 1
+        ecscope_up{profile="staging"} 0
+        # HELP ecscope_deployment_running_tasks Number of running tasks of the service's deployments with the status
+        # TYPE ecscope_deployment_running_tasks gauge
+        ecscope_deployment_running_tasks{profile="prod",service="auth-api",cluster_key="prod",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/prod",status="PRIMARY"} 2
+        ecscope_deployment_running_tasks{profile="prod",service="payments-api",cluster_key="qa",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/qa",status="ACTIVE"} 0
+        # HELP ecscope_deployment_desired_tasks Number of tasks the service's deployments with the status are meant to run
+        # TYPE ecscope_deployment_desired_tasks gauge
+        ecscope_deployment_desired_tasks{profile="prod",service="auth-api",cluster_key="prod",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/prod",status="PRIMARY"} 2
+        ecscope_deployment_desired_tasks{profile="prod",service="payments-api",cluster_key="qa",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/qa",status="ACTIVE"} 4
+        # HELP ecscope_deployment_pending_tasks Number of pending tasks of the service's deployments with the status
+        # TYPE ecscope_deployment_pending_tasks gauge
+        ecscope_deployment_pending_tasks{profile="prod",service="auth-api",cluster_key="prod",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/prod",status="PRIMARY"} 0
+        ecscope_deployment_pending_tasks{profile="prod",service="payments-api",cluster_key="qa",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/qa",status="ACTIVE"} 4
+        # HELP ecscope_deployment_failed_tasks Number of tasks of the service's deployments with the status that failed to launch
+        # TYPE ecscope_deployment_failed_tasks gauge
+        ecscope_deployment_failed_tasks{profile="prod",service="auth-api",cluster_key="prod",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/prod",status="PRIMARY"} 0
+        ecscope_deployment_failed_tasks{profile="prod",service="payments-api",cluster_key="qa",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/qa",status="ACTIVE"} 6
+        # HELP ecscope_scrape_duration_seconds How long fetching the deployments of the profile took
+        # TYPE ecscope_scrape_duration_seconds gauge
+        ecscope_scrape_duration_seconds{profile="prod"} 1.25
+        ecscope_scrape_duration_seconds{profile="staging"} 0.01
+        # HELP ecscope_results_age_seconds How long ago the deployments of the profile were fetched
+        # TYPE ecscope_results_age_seconds gauge
+        ecscope_results_age_seconds{profile="prod"} 3
+        ecscope_results_age_seconds{profile="staging"} 0
+        # HELP ecscope_cluster_errors_total Number of errors encountered while fetching deployments from the cluster
+        # TYPE ecscope_cluster_errors_total counter
+        ecscope_cluster_errors_total{profile="prod",cluster_key="qa",cluster_arn="arn:aws:ecs:eu-central-1:111111111111:cluster/qa"} 1
+        # HELP ecscope_fetch_failures_total Number of times deployments of the profile couldn't be fetched at all
+        # TYPE ecscope_fetch_failures_total counter
+        ecscope_fetch_failures_total{profile="prod"} 0
+        ecscope_fetch_failures_total{profile="staging"} 1
+        "#);
+    }
+
+    #[test]
+    fn every_fetch_is_counted() {
+        // GIVEN
+        let results = Ok((Vec::new(), vec![error("qa"), error("qa")]));
+        let counters = FetchCounters::default();
+
+        // WHEN
+        counters.count(&results, false);
+        counters.count(&results, false);
+        counters.count(&Err("couldn't fetch".to_string()), false);
+
+        // THEN
+        let counters = counters.get();
+        let key = (
+            "qa".to_string(),
+            "arn:aws:ecs:eu-central-1:111111111111:cluster/qa".to_string(),
+        );
+        assert_eq!(counters.cluster_errors.get(&key), Some(&4));
+        assert_eq!(counters.fetch_failures, 1);
+    }
+
+    #[test]
+    fn cluster_arns_of_errors_are_redacted_when_counted() {
+        // GIVEN
+        let results = Ok((Vec::new(), vec![error("qa")]));
+        let counters = FetchCounters::default();
+
+        // WHEN
+        counters.count(&results, true);
+
+        // THEN
+        assert_eq!(
+            counters
+                .get()
+                .cluster_errors
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec![("qa".to_string(), REDACTED.to_string())]
+        );
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        // GIVEN
+        let value = "a \"quoted\"\\path\nand more";
+
+        // WHEN
+        let escaped = escape_label_value(value);
+
+        // THEN
+        assert_eq!(escaped, r#"a \"quoted\"\\path\nand more"#);
+    }
+}
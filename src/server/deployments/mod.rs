@@ -1,3 +1,7 @@
+mod metrics;
+mod openapi;
 mod serve;
+mod services;
 
+pub(crate) use metrics::metrics_router;
 pub use serve::*;
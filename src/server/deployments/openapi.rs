@@ -0,0 +1,438 @@
+use super::serve::{ApiError, DeploymentsSource, GetDeploymentsResponse};
+use super::services::{GetServiceResponse, GetServicesResponse, GetTasksResponse};
+use crate::common::DeploymentState;
+use axum::Json;
+use axum::{Router, routing::get};
+use clap::ValueEnum;
+use schemars::Schema;
+use schemars::generate::SchemaSettings;
+use serde_json::{Value, json};
+use std::sync::{Arc, LazyLock};
+
+/// Where the current version of the API is served.
+pub(super) const API_PREFIX: &str = "/api/v1";
+
+static OPENAPI_DOCUMENT: LazyLock<Value> = LazyLock::new(openapi_document);
+
+/// Serves the OpenAPI document describing the API.
+pub(super) fn openapi_routes() -> Router<Arc<DeploymentsSource>> {
+    Router::new().route("/api/openapi.json", get(openapi_get))
+}
+
+async fn openapi_get() -> Json<&'static Value> {
+    Json(&*OPENAPI_DOCUMENT)
+}
+
+/// Describes the API, with the schemas of requests and responses generated from the types that
+/// are actually served.
+fn openapi_document() -> Value {
+    let mut generator = SchemaSettings::draft2020_12()
+        .with(|s| {
+            s.definitions_path = "/components/schemas".into();
+            s.meta_schema = None;
+        })
+        .for_serialize()
+        .into_generator();
+
+    let deployments = generator.subschema_for::<GetDeploymentsResponse>();
+    let services = generator.subschema_for::<GetServicesResponse>();
+    let service = generator.subschema_for::<GetServiceResponse>();
+    let tasks = generator.subschema_for::<GetTasksResponse>();
+    let error = generator.subschema_for::<ApiError>();
+
+    let states = DeploymentState::value_variants()
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>();
+    let deployments_parameters = json!([
+        {
+            "name": "where",
+            "in": "query",
+            "description": r#"A filter expression, eg. "status != 'PRIMARY' and failed > 0", or "@<name>" for a filter from the profile"#,
+            "schema": { "type": "string" },
+        },
+        {
+            "name": "state",
+            "in": "query",
+            "description": "Only show deployments in this state",
+            "schema": { "type": "string", "enum": states },
+        },
+    ]);
+    let service_parameters = json!([
+        {
+            "name": "cluster",
+            "in": "path",
+            "required": true,
+            "description": r#"The keys of the cluster, as they show up in results (eg. "prod,eu"), or its name"#,
+            "schema": { "type": "string" },
+        },
+        {
+            "name": "service",
+            "in": "path",
+            "required": true,
+            "description": "The name of a service configured in the profile",
+            "schema": { "type": "string" },
+        },
+    ]);
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "ecscope",
+            "description": "The deployments, services and tasks of an ecscope profile.",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        // relative to where this document is served from, so that it holds wherever the profile
+        // is served (eg. under "/p/<profile>/" by "ecscope serve")
+        "servers": [{ "url": "v1" }],
+        "paths": {
+            "/deps": {
+                "get": {
+                    "operationId": "getDeployments",
+                    "summary": "The deployments of the services in the profile",
+                    "parameters": deployments_parameters,
+                    "responses": {
+                        "200": json_response("Deployments, along with the services they couldn't be fetched for", &deployments),
+                        "400": json_response("An invalid filter or state", &error),
+                        "500": json_response("Deployments couldn't be fetched", &error),
+                    },
+                },
+            },
+            "/deps/stream": {
+                "get": {
+                    "operationId": "streamDeployments",
+                    "summary": "The deployments of the services in the profile, as server-sent events",
+                    "description": r#"The latest results are sent right away, and then again whenever they change: as "deployments" events (holding a GetDeploymentsResponse), or as "failure" events (holding an ApiError) when deployments couldn't be fetched."#,
+                    "parameters": deployments_parameters,
+                    "responses": {
+                        "200": {
+                            "description": "A stream of events",
+                            "content": { "text/event-stream": { "schema": { "type": "string" } } },
+                        },
+                        "400": json_response("An invalid filter or state", &error),
+                    },
+                },
+            },
+            "/services": {
+                "get": {
+                    "operationId": "getServices",
+                    "summary": "Every service in the profile, with its task counts",
+                    "responses": {
+                        "200": json_response("Services, along with the ones that couldn't be fetched", &services),
+                        "500": json_response("Services couldn't be fetched", &error),
+                    },
+                },
+            },
+            "/services/{cluster}/{service}": {
+                "get": {
+                    "operationId": "getService",
+                    "summary": "A service, along with its deployments and most recent events",
+                    "parameters": service_parameters,
+                    "responses": {
+                        "200": json_response("The service", &service),
+                        "404": json_response("The service isn't in the profile, or ECS doesn't know of it", &error),
+                        "500": json_response("The service couldn't be fetched", &error),
+                    },
+                },
+            },
+            "/services/{cluster}/{service}/tasks": {
+                "get": {
+                    "operationId": "getTasks",
+                    "summary": "A service's tasks, along with their containers",
+                    "parameters": service_parameters,
+                    "responses": {
+                        "200": json_response("The service's tasks", &tasks),
+                        "404": json_response("The service isn't in the profile", &error),
+                        "500": json_response("The tasks couldn't be fetched", &error),
+                    },
+                },
+            },
+        },
+        "components": {
+            "schemas": generator.take_definitions(true),
+        },
+    })
+}
+
+fn json_response(description: &str, schema: &Schema) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use regex::Regex;
+
+    const CLIENT_SOURCES: [&str; 2] = [
+        include_str!("client/src/types.gleam"),
+        include_str!("client/src/effects.gleam"),
+    ];
+
+    /// The schemas of what each of the client's decoders decodes.
+    const CLIENT_DECODERS: [(&str, &str); 10] = [
+        ("deployment_decoder", "DeploymentDetails"),
+        ("deployment_error_decoder", "DeploymentError"),
+        ("deployment_results_decoder", "GetDeploymentsResponse"),
+        ("service_decoder", "ServiceSummary"),
+        ("service_event_decoder", "ServiceEvent"),
+        ("service_results_decoder", "GetServiceResponse"),
+        ("container_decoder", "ContainerSummary"),
+        ("task_decoder", "TaskSummary"),
+        ("tasks_decoder", "GetTasksResponse"),
+        ("api_error_decoder", "ApiError"),
+    ];
+
+    /// A decoder from `gleam/dynamic/decode`, as used for a field.
+    #[derive(Debug)]
+    enum FieldDecoder {
+        String,
+        Int,
+        Optional(Box<FieldDecoder>),
+        List(Box<FieldDecoder>),
+        /// One of the client's own decoders, eg. `deployment_decoder()`
+        Named(String),
+    }
+
+    type WrapDecoder = fn(Box<FieldDecoder>) -> FieldDecoder;
+
+    /// Parses a decoder off the start of `text`, returning it along with what's left.
+    fn parse_field_decoder(text: &str) -> Option<(FieldDecoder, &str)> {
+        let text = text.trim_start();
+        let wrappers: [(&str, WrapDecoder); 2] = [
+            ("decode.optional(", FieldDecoder::Optional),
+            ("decode.list(", FieldDecoder::List),
+        ];
+        for (prefix, wrap) in wrappers {
+            if let Some(rest) = text.strip_prefix(prefix) {
+                let (inner, rest) = parse_field_decoder(rest)?;
+                let rest = rest.trim_start().strip_prefix(')')?;
+                return Some((wrap(Box::new(inner)), rest));
+            }
+        }
+
+        if let Some(rest) = text.strip_prefix("decode.string") {
+            return Some((FieldDecoder::String, rest));
+        }
+        if let Some(rest) = text.strip_prefix("decode.int") {
+            return Some((FieldDecoder::Int, rest));
+        }
+
+        let name_len = text.find("_decoder()")? + "_decoder".len();
+        let name = &text[..name_len];
+        if !name.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+            return None;
+        }
+
+        Some((
+            FieldDecoder::Named(name.to_string()),
+            &text[name_len + "()".len()..],
+        ))
+    }
+
+    /// Returns the decoders in the client's sources, along with the fields each one decodes.
+    fn client_decoders(sources: &[&str]) -> Vec<(String, Vec<(String, FieldDecoder)>)> {
+        let decoder_regex = Regex::new(r"(?s)fn (\w+_decoder)\(\) -> decode\.Decoder\(.*?\n\}")
+            .expect("regex should've been valid");
+        let field_regex =
+            Regex::new(r#"decode\.field\(\s*"(\w+)",\s*"#).expect("regex should've been valid");
+
+        let mut decoders = Vec::new();
+        for source in sources {
+            for decoder in decoder_regex.captures_iter(source) {
+                let body = decoder
+                    .get(0)
+                    .expect("match should've had a group 0")
+                    .as_str();
+                let fields = field_regex
+                    .captures_iter(body)
+                    .map(|field| {
+                        let rest =
+                            &body[field.get(0).expect("match should've had a group 0").end()..];
+                        let (field_decoder, _) = parse_field_decoder(rest)
+                            .expect("field should've used a known decoder");
+                        (field[1].to_string(), field_decoder)
+                    })
+                    .collect();
+                decoders.push((decoder[1].to_string(), fields));
+            }
+        }
+
+        decoders
+    }
+
+    /// Returns everything the client's decoders expect that the API doesn't serve.
+    fn client_drift(sources: &[&str], document: &Value) -> Vec<String> {
+        let schemas = &document["components"]["schemas"];
+        let schema_name = |decoder: &str| {
+            CLIENT_DECODERS
+                .iter()
+                .find(|(d, _)| *d == decoder)
+                .map(|(_, schema)| *schema)
+        };
+
+        let mut problems = Vec::new();
+        for (decoder, fields) in client_decoders(sources) {
+            let Some(name) = schema_name(&decoder) else {
+                problems.push(format!("{decoder}: not mapped to a schema"));
+                continue;
+            };
+            let schema = &schemas[name];
+            let required = schema["required"].as_array().cloned().unwrap_or_default();
+
+            for (field, field_decoder) in fields {
+                let at = format!("{decoder}: {name}.{field}");
+                let Some(property) = schema["properties"].get(&field) else {
+                    problems.push(format!("{at} isn't served"));
+                    continue;
+                };
+                if !required.contains(&Value::String(field.clone())) {
+                    problems.push(format!("{at} isn't always served"));
+                }
+                check_property(property, &field_decoder, &schema_name, &at, &mut problems);
+            }
+        }
+
+        problems
+    }
+
+    fn check_property(
+        property: &Value,
+        decoder: &FieldDecoder,
+        schema_name: &dyn Fn(&str) -> Option<&'static str>,
+        at: &str,
+        problems: &mut Vec<String>,
+    ) {
+        let types = match &property["type"] {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let nullable = types.contains(&"null");
+
+        let expected = match decoder {
+            FieldDecoder::Optional(inner) => {
+                let mut property = property.clone();
+                if let Some(t) = types.iter().find(|t| **t != "null") {
+                    property["type"] = json!(t);
+                }
+                return check_property(&property, inner, schema_name, at, problems);
+            }
+            _ if nullable => {
+                problems.push(format!("{at} can be null"));
+                return;
+            }
+            FieldDecoder::Named(decoder) => {
+                let expected_ref = schema_name(decoder)
+                    .map(|name| format!("#/components/schemas/{name}"))
+                    .unwrap_or_default();
+                if property["$ref"] != expected_ref.as_str() {
+                    problems.push(format!("{at} isn't a {expected_ref}"));
+                }
+                return;
+            }
+            FieldDecoder::String => "string",
+            FieldDecoder::Int => "integer",
+            FieldDecoder::List(_) => "array",
+        };
+
+        if types != [expected] {
+            problems.push(format!("{at} isn't of type {expected}"));
+        } else if let FieldDecoder::List(inner) = decoder {
+            check_property(&property["items"], inner, schema_name, at, problems);
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn the_document_describes_every_endpoint() {
+        // GIVEN
+        let document = openapi_document();
+
+        // WHEN
+        let operations = document["paths"]
+            .as_object()
+            .expect("paths should've been an object")
+            .iter()
+            .map(|(path, item)| format!("{path}: {}", item["get"]["operationId"]))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(operations, @r#"
+        /deps: "getDeployments"
+        /deps/stream: "streamDeployments"
+        /services: "getServices"
+        /services/{cluster}/{service}: "getService"
+        /services/{cluster}/{service}/tasks: "getTasks"
+        "#);
+    }
+
+    #[test]
+    fn the_client_decodes_what_the_api_serves() {
+        // GIVEN
+        let document = openapi_document();
+
+        // WHEN
+        let problems = client_drift(&CLIENT_SOURCES, &document);
+
+        // THEN
+        assert!(
+            problems.is_empty(),
+            "the client's decoders have drifted from the API:\n{}",
+            problems.join("\n")
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn drift_between_the_client_and_the_api_is_caught() {
+        // GIVEN
+        let document = openapi_document();
+        let source = r#"
+fn deployment_error_decoder() -> decode.Decoder(DeploymentError) {
+  use service <- decode.field("service", decode.string)
+  use keys <- decode.field("keys", decode.list(decode.string))
+  use error <- decode.field("error", decode.optional(decode.int))
+  decode.success(DeploymentError(service:, keys:, error:))
+}
+
+fn service_event_decoder() -> decode.Decoder(ServiceEvent) {
+  use created_at <- decode.field("created_at", decode.int)
+  decode.success(ServiceEvent(created_at:))
+}
+
+fn service_results_decoder() -> decode.Decoder(ServiceResults) {
+  use service <- decode.field("service", deployment_decoder())
+  decode.success(ServiceResults(service:))
+}
+
+fn cluster_decoder() -> decode.Decoder(Cluster) {
+  use name <- decode.field("name", decode.string)
+  decode.success(Cluster(name:))
+}
+"#;
+
+        // WHEN
+        let problems = client_drift(&[source], &document);
+
+        // THEN
+        assert_snapshot!(problems.join("\n"), @r"
+        deployment_error_decoder: DeploymentError.service isn't served
+        deployment_error_decoder: DeploymentError.keys isn't of type array
+        deployment_error_decoder: DeploymentError.error isn't of type integer
+        service_event_decoder: ServiceEvent.created_at can be null
+        service_results_decoder: GetServiceResponse.service isn't a #/components/schemas/DeploymentDetails
+        cluster_decoder: not mapped to a schema
+        ");
+    }
+}
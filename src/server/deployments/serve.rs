@@ -1,37 +1,256 @@
+use super::super::cache::{CacheConfig, Cached, RefreshLimiter, ResultsCache};
 use super::super::utils::find_open_port_in_range;
+use super::metrics::FetchCounters;
+use super::openapi::{API_PREFIX, openapi_routes};
+use super::services::{ServiceCaches, services_routes};
 use crate::common::{DeploymentState, Environment};
-use crate::config::{ClusterConfig, ConfigSource};
-use crate::domain::{DeploymentDetails, DeploymentError};
+use crate::config::{ClientKey, ClusterConfig, NamedFilters};
+use crate::domain::{DeploymentDetails, DeploymentError, FilterExpr};
 use crate::service::get_deployments;
 use aws_sdk_ecs::Client as ECSClient;
 use axum::Json;
+use axum::extract::{Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::Response;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{Html, IntoResponse};
 use axum::{Router, routing::get};
+use clap::ValueEnum;
 use rand::RngExt;
+use regex::Regex;
 use std::collections::HashMap;
+use std::future::Future;
 use std::io::Error as IOError;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::signal;
+use tokio::time::MissedTickBehavior;
+use tokio_stream::{Stream, StreamExt, wrappers::IntervalStream};
 use tower_http::cors::{Any, CorsLayer};
 
+pub(super) const REDACTED: &str = "<REDACTED>";
+#[allow(clippy::expect_used)]
+static ARN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"arn:aws[a-z-]*:[^\s"',]+"#).expect("ARN regex is invalid"));
+
 const ENV_VAR_PORT: &str = "ECSCOPE_PORT";
 const ROOT_HTML: &str = include_str!("client/index.html");
 const DEPS_JS: &str = include_str!("client/priv/static/deps.mjs");
 const DEPS_CSS: &str = include_str!("client/priv/static/deps.css");
 const DEPS_CUSTOM_CSS: &str = include_str!("client/priv/static/custom.css");
 const DEPS_FAVICON: &[u8] = include_bytes!("client/priv/static/favicon.png");
+const STREAM_POLL_INTERVAL_SECS: u64 = 5;
+// filters worth writing by hand fit in this comfortably
+const MAX_WHERE_LEN: usize = 1000;
+
+pub(super) type DeploymentsResults = Result<(Vec<DeploymentDetails>, Vec<DeploymentError>), String>;
+type FetchDeployments =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = DeploymentsResults> + Send>> + Send + Sync>;
+type DeploymentsCache = ResultsCache<DeploymentsResults, FetchDeployments>;
+
+/// The deployments of a profile, as served by the API.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+pub(super) struct GetDeploymentsResponse {
+    pub(super) deployments: Vec<DeploymentDetails>,
+    pub(super) errors: Vec<DeploymentError>,
+    /// When the results were fetched from ECS (seconds since the Unix epoch)
+    fetched_at: u64,
+    /// How long the results had been cached for when they were served
+    cache_age_secs: u64,
+}
+
+impl GetDeploymentsResponse {
+    pub(super) fn new(
+        deployments: Vec<DeploymentDetails>,
+        errors: Vec<DeploymentError>,
+        fetched_at: SystemTime,
+        cache_age: Duration,
+    ) -> Self {
+        Self {
+            deployments,
+            errors,
+            fetched_at: fetched_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            cache_age_secs: cache_age.as_secs(),
+        }
+    }
+
+    /// Hides ARNs, which give away account IDs, and the names of resources.
+    fn redacted(mut self) -> Self {
+        for deployment in &mut self.deployments {
+            deployment.cluster_arn = REDACTED.to_string();
+        }
+        for error in &mut self.errors {
+            error.cluster_arn = REDACTED.to_string();
+            error.error = redact_arns(&error.error);
+        }
+
+        self
+    }
+
+    /// Whether both responses hold the same results, regardless of when they were fetched.
+    fn has_same_results(&self, other: &Self) -> bool {
+        self.deployments == other.deployments && self.errors == other.errors
+    }
+}
+
+/// What's served when a request can't be answered.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+pub(super) struct ApiError {
+    pub(super) error: String,
+}
+
+/// What a request for deployments can narrow the results down to.
+#[derive(Debug, Default, serde::Deserialize)]
+struct DeploymentsQuery {
+    /// A filter expression (or "@<name>" for a filter from the profile)
+    #[serde(rename = "where")]
+    where_filter: Option<String>,
+    state: Option<String>,
+}
+
+/// Everything needed to serve the deployments of a profile.
+pub struct DeploymentsSource {
+    pub(super) clusters: Vec<ClusterConfig>,
+    pub(super) clients_map: Arc<HashMap<ClientKey, ECSClient>>,
+    named_filters: NamedFilters,
+    /// Used for requests that don't provide a filter of their own
+    default_filter: Option<FilterExpr>,
+    /// Used for requests that don't provide a state of their own
+    default_state: Option<DeploymentState>,
+    pub(super) cache_config: CacheConfig,
+    /// Shared by all the caches, so that the profile's clusters aren't called more often than
+    /// `cache_config.min_refresh_interval` allows
+    pub(super) refresh_limiter: Arc<RefreshLimiter>,
+    /// Whether ARNs are to be left out of responses
+    pub(super) redact: bool,
+    /// Results are cached per state, since that determines what's fetched; filters are applied
+    /// to the cached results
+    caches: Mutex<HashMap<Option<DeploymentState>, Arc<DeploymentsCache>>>,
+    pub(super) service_caches: ServiceCaches,
+    /// Updated by every fetch the caches make
+    pub(super) fetch_counters: Arc<FetchCounters>,
+}
+
+impl DeploymentsSource {
+    pub fn new(
+        clusters: Vec<ClusterConfig>,
+        clients_map: Arc<HashMap<ClientKey, ECSClient>>,
+        named_filters: NamedFilters,
+        cache_config: CacheConfig,
+    ) -> Self {
+        Self {
+            clusters,
+            clients_map,
+            named_filters,
+            default_filter: None,
+            default_state: None,
+            cache_config,
+            refresh_limiter: Arc::new(RefreshLimiter::new(cache_config.min_refresh_interval)),
+            redact: false,
+            caches: Mutex::new(HashMap::new()),
+            service_caches: ServiceCaches::default(),
+            fetch_counters: Arc::new(FetchCounters::default()),
+        }
+    }
+
+    pub fn with_defaults(
+        mut self,
+        filter: Option<FilterExpr>,
+        state: Option<DeploymentState>,
+    ) -> Self {
+        self.default_filter = filter;
+        self.default_state = state;
+        self
+    }
+
+    pub fn redacting(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
 
-#[derive(serde::Serialize)]
-struct GetDeploymentsResponse {
-    deployments: Vec<DeploymentDetails>,
-    errors: Vec<DeploymentError>,
+    pub(super) fn cache(&self, state: Option<DeploymentState>) -> Arc<DeploymentsCache> {
+        let mut caches = self.caches.lock().unwrap_or_else(PoisonError::into_inner);
+        let cache = caches.entry(state).or_insert_with(|| {
+            let clusters = self.clusters.clone();
+            let clients_map = Arc::clone(&self.clients_map);
+            let fetch_counters = Arc::clone(&self.fetch_counters);
+            let redact = self.redact;
+            let fetch: FetchDeployments = Box::new(move || {
+                let clusters = clusters.clone();
+                let clients_map = Arc::clone(&clients_map);
+                let fetch_counters = Arc::clone(&fetch_counters);
+                Box::pin(async move {
+                    let results = get_deployments(clusters, clients_map, state).await;
+                    fetch_counters.count(&results, redact);
+                    results
+                })
+            });
+            Arc::new(
+                ResultsCache::new(self.cache_config, fetch)
+                    .limited_by(Arc::clone(&self.refresh_limiter)),
+            )
+        });
+
+        Arc::clone(cache)
+    }
+
+    fn resolve(
+        &self,
+        query: DeploymentsQuery,
+    ) -> Result<(Option<DeploymentState>, Option<FilterExpr>), ApiError> {
+        let state = match query.state.as_deref() {
+            Some(s) => Some(DeploymentState::from_str(s, true).map_err(|_| ApiError {
+                error: format!("invalid state: {s}"),
+            })?),
+            None => self.default_state,
+        };
+
+        let filter = match query.where_filter.as_deref() {
+            Some(w) if w.len() > MAX_WHERE_LEN => {
+                return Err(ApiError {
+                    error: format!("invalid filter: filter is longer than {MAX_WHERE_LEN} bytes"),
+                });
+            }
+            Some(w) => Some(
+                FilterExpr::parse_with_named(w, &self.named_filters).map_err(|e| ApiError {
+                    error: format!("invalid filter: {e}"),
+                })?,
+            ),
+            None => self.default_filter.clone(),
+        };
+
+        Ok((state, filter))
+    }
 }
 
-#[derive(serde::Serialize)]
-struct ApiError {
-    error: String,
+/// The latest results, as pushed to the clients subscribed to the stream.
+#[derive(Debug, Clone, PartialEq)]
+enum StreamUpdate {
+    Deployments(GetDeploymentsResponse),
+    Failure(ApiError),
+}
+
+impl StreamUpdate {
+    fn has_same_results(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StreamUpdate::Deployments(a), StreamUpdate::Deployments(b)) => a.has_same_results(b),
+            (StreamUpdate::Failure(a), StreamUpdate::Failure(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    fn event(&self) -> Result<Event, axum::Error> {
+        match self {
+            StreamUpdate::Deployments(response) => {
+                Event::default().event("deployments").json_data(response)
+            }
+            StreamUpdate::Failure(error) => Event::default().event("failure").json_data(error),
+        }
+    }
 }
 
 impl IntoResponse for GetDeploymentsResponse {
@@ -46,6 +265,12 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl ApiError {
+    fn bad_request(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ServeDeploymentsError {
     #[error("couldn't find open port")]
@@ -59,29 +284,16 @@ pub enum ServeDeploymentsError {
 }
 
 pub async fn serve_deployments(
-    clusters: Vec<ClusterConfig>,
-    clients_map: Arc<HashMap<ConfigSource, ECSClient>>,
-    state: Option<DeploymentState>,
+    source: DeploymentsSource,
     skip_opening: bool,
     env: Environment,
 ) -> Result<(), ServeDeploymentsError> {
-    let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any);
-
-    let router = Router::new()
-        .route("/", get(move || root_get(env)))
-        .route("/priv/static/deps.mjs", get(move || js_get(env)))
-        .route("/priv/static/deps.css", get(move || css_get(env)))
-        .route("/priv/static/custom.css", get(move || css_custom_get(env)))
-        .route("/priv/static/favicon.png", get(favicon_get))
-        .route("/dev/api/deps", get(fake_deployments_get))
-        .route(
-            "/api/deps",
-            get({
-                let clients_map = Arc::clone(&clients_map);
-                move || deployments_get(clusters, clients_map, state)
-            }),
-        )
-        .layer(cors);
+    let router = deployments_router(Arc::new(source), env);
+    // the web view is served from the same origin as the API, except during its development
+    let router = match env {
+        Environment::Dev => router.layer(CorsLayer::new().allow_methods(Any).allow_origin(Any)),
+        Environment::Prod => router,
+    };
 
     let port = match std::env::var(ENV_VAR_PORT) {
         Ok(port_str) => match port_str.parse::<u16>() {
@@ -126,6 +338,30 @@ pub async fn serve_deployments(
     Ok(())
 }
 
+/// The web view, and the API behind it, for the deployments of one profile.
+pub(crate) fn deployments_router(source: Arc<DeploymentsSource>, env: Environment) -> Router {
+    Router::new()
+        .route("/", get(move || root_get(env)))
+        .route("/priv/static/deps.mjs", get(move || js_get(env)))
+        .route("/priv/static/deps.css", get(move || css_get(env)))
+        .route("/priv/static/custom.css", get(move || css_custom_get(env)))
+        .route("/priv/static/favicon.png", get(favicon_get))
+        .route("/dev/api/deps", get(fake_deployments_get))
+        .nest(API_PREFIX, api_routes())
+        // where the API was served before it was versioned
+        .nest("/api", api_routes())
+        .merge(openapi_routes())
+        .with_state(source)
+}
+
+/// The API, as described by the OpenAPI document (relative to [`API_PREFIX`]).
+fn api_routes() -> Router<Arc<DeploymentsSource>> {
+    Router::new()
+        .route("/deps", get(deployments_get))
+        .route("/deps/stream", get(deployments_stream))
+        .merge(services_routes())
+}
+
 async fn js_get(env: Environment) -> impl IntoResponse {
     let mut headers = HeaderMap::new();
     #[allow(clippy::unwrap_used)]
@@ -198,20 +434,93 @@ async fn root_get(env: Environment) -> impl IntoResponse {
 }
 
 async fn deployments_get(
-    clusters: Vec<ClusterConfig>,
-    clients_map: Arc<HashMap<ConfigSource, ECSClient>>,
-    state: Option<DeploymentState>,
+    State(source): State<Arc<DeploymentsSource>>,
+    Query(query): Query<DeploymentsQuery>,
+) -> Result<GetDeploymentsResponse, Response> {
+    let (state, filter) = source.resolve(query).map_err(ApiError::bad_request)?;
+    let results = source.cache(state).get().await;
+
+    to_response(&results, filter.as_ref(), source.redact).map_err(IntoResponse::into_response)
+}
+
+pub(super) fn to_response(
+    results: &Cached<DeploymentsResults>,
+    filter: Option<&FilterExpr>,
+    redact: bool,
 ) -> Result<GetDeploymentsResponse, ApiError> {
-    let (deployments, errors) = get_deployments(clusters, clients_map, state)
-        .await
-        .map_err(|error| ApiError { error })?;
+    let (deployments, errors) = results.value.as_ref().map_err(|error| ApiError {
+        error: if redact {
+            redact_arns(error)
+        } else {
+            error.clone()
+        },
+    })?;
 
-    let response = GetDeploymentsResponse {
+    let deployments = deployments
+        .iter()
+        .filter(|d| filter.is_none_or(|f| f.matches(*d)))
+        .cloned()
+        .collect();
+
+    let response = GetDeploymentsResponse::new(
         deployments,
-        errors,
-    };
+        errors.clone(),
+        results.fetched_at,
+        results.age(),
+    );
 
-    Ok(response)
+    Ok(if redact {
+        response.redacted()
+    } else {
+        response
+    })
+}
+
+pub(super) fn redact_arns(text: &str) -> String {
+    ARN_REGEX.replace_all(text, REDACTED).into_owned()
+}
+
+/// Streams the results as server-sent events; the latest results are sent right away, and then
+/// again whenever they change. Every subscriber reads from the same cache, so the number of
+/// subscribers doesn't add to the calls made to ECS.
+async fn deployments_stream(
+    State(source): State<Arc<DeploymentsSource>>,
+    Query(query): Query<DeploymentsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, Response> {
+    let (state, filter) = source.resolve(query).map_err(ApiError::bad_request)?;
+    let cache = source.cache(state);
+    let redact = source.redact;
+
+    let mut interval = tokio::time::interval(Duration::from_secs(STREAM_POLL_INTERVAL_SECS));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut last_update: Option<StreamUpdate> = None;
+    let events = IntervalStream::new(interval)
+        .then(move |_| {
+            let cache = Arc::clone(&cache);
+            let filter = filter.clone();
+            async move {
+                let results = cache.get().await;
+                match to_response(&results, filter.as_ref(), redact) {
+                    Ok(response) => StreamUpdate::Deployments(response),
+                    Err(error) => StreamUpdate::Failure(error),
+                }
+            }
+        })
+        .filter_map(move |update| {
+            if last_update
+                .as_ref()
+                .is_some_and(|last| last.has_same_results(&update))
+            {
+                return None;
+            }
+
+            let event = update.event();
+            last_update = Some(update);
+            Some(event)
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
 
 async fn fake_deployments_get() -> Result<GetDeploymentsResponse, ApiError> {
@@ -259,17 +568,20 @@ async fn fake_deployments_get() -> Result<GetDeploymentsResponse, ApiError> {
             error: "couldn't get access token\nLine 2\nLine 3\nLine 4".to_string(),
             cluster_arn: format!("cluster-{i}"),
             keys: "qa".to_string(),
+            service_alias: None,
         })
         .collect::<Vec<_>>();
 
-    Ok(GetDeploymentsResponse {
+    Ok(GetDeploymentsResponse::new(
         deployments,
         errors,
-    })
+        SystemTime::now(),
+        Duration::ZERO,
+    ))
 }
 
 #[allow(clippy::expect_used)]
-async fn shutdown_signal() {
+pub(crate) async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -294,3 +606,193 @@ async fn shutdown_signal() {
 
     println!("\nbye 👋");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> DeploymentsSource {
+        DeploymentsSource::new(
+            vec![],
+            Arc::new(HashMap::new()),
+            NamedFilters::from([("failing".to_string(), "failed>0".to_string())]),
+            CacheConfig {
+                ttl: Duration::from_secs(5),
+                max_stale: Duration::from_secs(30),
+                min_refresh_interval: Duration::ZERO,
+            },
+        )
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn queries_take_precedence_over_defaults() {
+        // GIVEN
+        let source = source().with_defaults(
+            Some(FilterExpr::parse("key=prod").expect("filter should've been parsed")),
+            Some(DeploymentState::Finished),
+        );
+        let query = DeploymentsQuery {
+            where_filter: Some("key=qa".to_string()),
+            state: Some("failing".to_string()),
+        };
+
+        // WHEN
+        let (state, filter) = source
+            .resolve(query)
+            .expect("query should've been resolved");
+
+        // THEN
+        let filter = filter.expect("filter should've been present");
+        assert_eq!(state, Some(DeploymentState::Failing));
+        assert!(filter.matches(&DeploymentDetails::dummy_running("auth-api", "qa")));
+        assert!(!filter.matches(&DeploymentDetails::dummy_running("auth-api", "prod")));
+    }
+
+    #[test]
+    fn defaults_are_used_for_what_queries_dont_provide() {
+        // GIVEN
+        let source = source().with_defaults(
+            Some(FilterExpr::parse("key=prod").expect("filter should've been parsed")),
+            Some(DeploymentState::Finished),
+        );
+
+        // WHEN
+        let (state, filter) = source
+            .resolve(DeploymentsQuery::default())
+            .expect("query should've been resolved");
+
+        // THEN
+        let filter = filter.expect("filter should've been present");
+        assert_eq!(state, Some(DeploymentState::Finished));
+        assert!(filter.matches(&DeploymentDetails::dummy_running("auth-api", "prod")));
+    }
+
+    #[test]
+    fn queries_can_refer_to_named_filters() {
+        // GIVEN
+        let source = source();
+        let query = DeploymentsQuery {
+            where_filter: Some("@failing".to_string()),
+            state: None,
+        };
+
+        // WHEN
+        let (_, filter) = source
+            .resolve(query)
+            .expect("query should've been resolved");
+
+        // THEN
+        let filter = filter.expect("filter should've been present");
+        assert!(filter.matches(&DeploymentDetails::dummy_failing("auth-api", "prod")));
+        assert!(!filter.matches(&DeploymentDetails::dummy_running("auth-api", "prod")));
+    }
+
+    #[test]
+    fn results_are_cached_per_state() {
+        // GIVEN
+        let source = source();
+
+        // WHEN
+        let failing = source.cache(Some(DeploymentState::Failing));
+        let failing_again = source.cache(Some(DeploymentState::Failing));
+        let all = source.cache(None);
+
+        // THEN
+        assert!(Arc::ptr_eq(&failing, &failing_again));
+        assert!(!Arc::ptr_eq(&failing, &all));
+    }
+
+    #[test]
+    fn redacted_responses_leave_out_arns() {
+        // GIVEN
+        let response = GetDeploymentsResponse::new(
+            vec![DeploymentDetails::dummy_running("auth-api", "prod")],
+            vec![DeploymentError {
+                service_name: "payments-api".to_string(),
+                error: "User: arn:aws:sts::111111111111:assumed-role/viewer/ecscope is not authorized to perform: ecs:DescribeServices".to_string(),
+                cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+                keys: "prod".to_string(),
+                service_alias: None,
+            }],
+            UNIX_EPOCH,
+            Duration::ZERO,
+        );
+
+        // WHEN
+        let redacted = response.redacted();
+
+        // THEN
+        assert_eq!(redacted.deployments[0].cluster_arn, REDACTED);
+        assert_eq!(redacted.errors[0].cluster_arn, REDACTED);
+        assert_eq!(
+            redacted.errors[0].error,
+            "User: <REDACTED> is not authorized to perform: ecs:DescribeServices"
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn queries_with_invalid_states_are_rejected() {
+        // GIVEN
+        let source = source();
+        let query = DeploymentsQuery {
+            where_filter: None,
+            state: Some("sideways".to_string()),
+        };
+
+        // WHEN
+        let result = source.resolve(query);
+
+        // THEN
+        assert_eq!(
+            result.err(),
+            Some(ApiError {
+                error: "invalid state: sideways".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn queries_with_invalid_filters_are_rejected() {
+        // GIVEN
+        let source = source();
+        let query = DeploymentsQuery {
+            where_filter: Some("@unknown".to_string()),
+            state: None,
+        };
+
+        // WHEN
+        let result = source.resolve(query);
+
+        // THEN
+        assert!(result.is_err_and(|e| e.error.starts_with("invalid filter: ")));
+    }
+
+    #[test]
+    fn queries_with_overly_long_filters_are_rejected() {
+        // GIVEN
+        let source = source();
+        let query = DeploymentsQuery {
+            where_filter: Some("key=prod or ".repeat(100) + "key=qa"),
+            state: None,
+        };
+
+        // WHEN
+        let result = source.resolve(query);
+
+        // THEN
+        assert_eq!(
+            result.err(),
+            Some(ApiError {
+                error: "invalid filter: filter is longer than 1000 bytes".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use super::super::cache::ResultsCache;
+use super::serve::{ApiError, DeploymentsSource, REDACTED, redact_arns};
+use crate::config::ClusterConfig;
+use crate::domain::{
+    Arn, DeploymentDetails, DeploymentError, ServiceEvent, ServiceSummary, TaskSummary,
+};
+use crate::service::{get_service, get_services, get_tasks};
+use axum::Json;
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type ServicesResults = Result<(Vec<ServiceSummary>, Vec<DeploymentError>), String>;
+type ServiceResults =
+    Result<Option<(ServiceSummary, Vec<DeploymentDetails>, Vec<ServiceEvent>)>, String>;
+type TasksResults = Result<Vec<TaskSummary>, String>;
+type Fetch<T> = Box<dyn Fn() -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync>;
+type Cache<T> = ResultsCache<T, Fetch<T>>;
+/// A service, by the ARN of its cluster and its name
+type ServiceKey = (String, String);
+
+/// Caches for the services of a profile, and for the details and tasks of each service that's
+/// been asked for.
+#[derive(Default)]
+pub(super) struct ServiceCaches {
+    services: Mutex<Option<Arc<Cache<ServicesResults>>>>,
+    details: Mutex<HashMap<ServiceKey, Arc<Cache<ServiceResults>>>>,
+    tasks: Mutex<HashMap<ServiceKey, Arc<Cache<TasksResults>>>>,
+}
+
+/// Every service in a profile, as served by the API.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub(super) struct GetServicesResponse {
+    services: Vec<ServiceSummary>,
+    errors: Vec<DeploymentError>,
+    /// When the results were fetched from ECS (seconds since the Unix epoch)
+    fetched_at: u64,
+    /// How long the results had been cached for when they were served
+    cache_age_secs: u64,
+}
+
+/// A service, as served by the API.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub(super) struct GetServiceResponse {
+    service: ServiceSummary,
+    deployments: Vec<DeploymentDetails>,
+    /// Most recent first
+    events: Vec<ServiceEvent>,
+    /// When the results were fetched from ECS (seconds since the Unix epoch)
+    fetched_at: u64,
+    /// How long the results had been cached for when they were served
+    cache_age_secs: u64,
+}
+
+/// The tasks of a service, as served by the API.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub(super) struct GetTasksResponse {
+    tasks: Vec<TaskSummary>,
+    /// When the results were fetched from ECS (seconds since the Unix epoch)
+    fetched_at: u64,
+    /// How long the results had been cached for when they were served
+    cache_age_secs: u64,
+}
+
+/// The API behind the web view's service and task pages.
+pub(super) fn services_routes() -> Router<Arc<DeploymentsSource>> {
+    Router::new()
+        .route("/services", get(services_get))
+        .route("/services/{cluster}/{service}", get(service_get))
+        .route("/services/{cluster}/{service}/tasks", get(tasks_get))
+}
+
+async fn services_get(State(source): State<Arc<DeploymentsSource>>) -> Response {
+    let cache = {
+        let mut services = source
+            .service_caches
+            .services
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let cache = services.get_or_insert_with(|| {
+            let clusters = source.clusters.clone();
+            let clients_map = Arc::clone(&source.clients_map);
+            let fetch: Fetch<ServicesResults> = Box::new(move || {
+                Box::pin(get_services(clusters.clone(), Arc::clone(&clients_map)))
+            });
+            Arc::new(
+                ResultsCache::new(source.cache_config, fetch)
+                    .limited_by(Arc::clone(&source.refresh_limiter)),
+            )
+        });
+        Arc::clone(cache)
+    };
+
+    let results = cache.get().await;
+    let (services, errors) = match &results.value {
+        Ok(r) => r.clone(),
+        Err(error) => return internal_error(error, source.redact).into_response(),
+    };
+
+    let mut response = GetServicesResponse {
+        services,
+        errors,
+        fetched_at: epoch_secs(results.fetched_at),
+        cache_age_secs: results.age().as_secs(),
+    };
+    if source.redact {
+        response.services.iter_mut().for_each(redact_service);
+        for error in &mut response.errors {
+            error.cluster_arn = REDACTED.to_string();
+            error.error = redact_arns(&error.error);
+        }
+    }
+
+    Json(response).into_response()
+}
+
+async fn service_get(
+    State(source): State<Arc<DeploymentsSource>>,
+    Path((cluster, service)): Path<(String, String)>,
+) -> Response {
+    let cluster = match find_cluster(&source.clusters, &cluster, &service) {
+        Some(c) => c.clone(),
+        None => return service_not_found(&cluster, &service),
+    };
+
+    let cache = cache_for(
+        &source,
+        &source.service_caches.details,
+        &cluster,
+        &service,
+        |cluster, client, service| {
+            Box::pin(async move { get_service(&cluster, &client, &service).await })
+        },
+    );
+    let cache = match cache {
+        Ok(c) => c,
+        Err(error) => return error.into_response(),
+    };
+
+    let results = cache.get().await;
+    let (service_summary, deployments, events) = match &results.value {
+        Ok(Some(r)) => r.clone(),
+        Ok(None) => return service_not_found(&cluster.keys.join(","), &service),
+        Err(error) => return internal_error(error, source.redact).into_response(),
+    };
+
+    let mut response = GetServiceResponse {
+        service: service_summary,
+        deployments,
+        events,
+        fetched_at: epoch_secs(results.fetched_at),
+        cache_age_secs: results.age().as_secs(),
+    };
+    if source.redact {
+        redact_service(&mut response.service);
+        for deployment in &mut response.deployments {
+            deployment.cluster_arn = REDACTED.to_string();
+        }
+        for event in &mut response.events {
+            event.message = redact_arns(&event.message);
+        }
+    }
+
+    Json(response).into_response()
+}
+
+async fn tasks_get(
+    State(source): State<Arc<DeploymentsSource>>,
+    Path((cluster, service)): Path<(String, String)>,
+) -> Response {
+    let cluster = match find_cluster(&source.clusters, &cluster, &service) {
+        Some(c) => c.clone(),
+        None => return service_not_found(&cluster, &service),
+    };
+
+    let cache = cache_for(
+        &source,
+        &source.service_caches.tasks,
+        &cluster,
+        &service,
+        |cluster, client, service| {
+            Box::pin(async move { get_tasks(&cluster, &client, &service).await })
+        },
+    );
+    let cache = match cache {
+        Ok(c) => c,
+        Err(error) => return error.into_response(),
+    };
+
+    let results = cache.get().await;
+    let tasks = match &results.value {
+        Ok(tasks) => tasks.clone(),
+        Err(error) => return internal_error(error, source.redact).into_response(),
+    };
+
+    let mut response = GetTasksResponse {
+        tasks,
+        fetched_at: epoch_secs(results.fetched_at),
+        cache_age_secs: results.age().as_secs(),
+    };
+    if source.redact {
+        response.tasks.iter_mut().for_each(redact_task);
+    }
+
+    Json(response).into_response()
+}
+
+/// Returns the cache for a service, setting one up if there's none yet.
+fn cache_for<T, F>(
+    source: &DeploymentsSource,
+    caches: &Mutex<HashMap<ServiceKey, Arc<Cache<T>>>>,
+    cluster: &ClusterConfig,
+    service: &str,
+    fetch: F,
+) -> Result<Arc<Cache<T>>, ApiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(ClusterConfig, aws_sdk_ecs::Client, String) -> Pin<Box<dyn Future<Output = T> + Send>>
+        + Send
+        + Sync
+        + 'static,
+{
+    let mut caches = caches.lock().unwrap_or_else(PoisonError::into_inner);
+    let key = (cluster.arn.clone(), service.to_string());
+    if let Some(cache) = caches.get(&key) {
+        return Ok(Arc::clone(cache));
+    }
+
+    let client = source
+        .clients_map
+        .get(&cluster.client_key())
+        .ok_or_else(|| internal_error("clients_map did not have entry for cluster", source.redact))?
+        .clone();
+    let cluster = cluster.clone();
+    let service = service.to_string();
+    let fetch: Fetch<T> = Box::new(move || fetch(cluster.clone(), client.clone(), service.clone()));
+    let cache = Arc::new(
+        ResultsCache::new(source.cache_config, fetch)
+            .limited_by(Arc::clone(&source.refresh_limiter)),
+    );
+    caches.insert(key, Arc::clone(&cache));
+
+    Ok(cache)
+}
+
+/// Finds the cluster a service (as configured in the profile) runs in. Clusters are referred to
+/// by their keys, as they show up in results (eg. `prod` or `prod,eu`), or by their names.
+fn find_cluster<'a>(
+    clusters: &'a [ClusterConfig],
+    cluster: &str,
+    service: &str,
+) -> Option<&'a ClusterConfig> {
+    clusters.iter().find(|c| {
+        let matches_cluster = c.keys.join(",") == cluster
+            || Arn::parse(&c.arn).is_some_and(|arn| arn.resource_name() == cluster);
+
+        matches_cluster && c.services.iter().any(|s| s.name == service)
+    })
+}
+
+fn redact_service(service: &mut ServiceSummary) {
+    service.cluster_arn = REDACTED.to_string();
+}
+
+fn redact_task(task: &mut TaskSummary) {
+    task.task_arn = REDACTED.to_string();
+    task.stopped_reason = task.stopped_reason.as_deref().map(redact_arns);
+    for container in &mut task.containers {
+        // ECR image URIs carry the account ID
+        container.image = container.image.as_ref().map(|_| REDACTED.to_string());
+        container.reason = container.reason.as_deref().map(redact_arns);
+    }
+}
+
+fn service_not_found(cluster: &str, service: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ApiError {
+            error: format!(r#"no service "{service}" in cluster "{cluster}" in this profile"#),
+        }),
+    )
+        .into_response()
+}
+
+fn internal_error(error: &str, redact: bool) -> ApiError {
+    ApiError {
+        error: if redact {
+            redact_arns(error)
+        } else {
+            error.to_string()
+        },
+    }
+}
+
+fn epoch_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigSource, ServiceConfig};
+    use crate::domain::ContainerSummary;
+
+    fn cluster(keys: &[&str], name: &str, services: &[&str]) -> ClusterConfig {
+        ClusterConfig {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            arn: format!("arn:aws:ecs:eu-central-1:111111111111:cluster/{name}"),
+            services: services
+                .iter()
+                .map(|s| ServiceConfig {
+                    name: s.to_string(),
+                    metadata: Default::default(),
+                })
+                .collect(),
+            config_source: ConfigSource::Env,
+            region: None,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn clusters_can_be_found_by_keys_or_name() {
+        // GIVEN
+        let clusters = vec![
+            cluster(&["qa"], "qa-cluster", &["auth-api"]),
+            cluster(
+                &["prod", "eu"],
+                "prod-cluster",
+                &["auth-api", "payments-api"],
+            ),
+        ];
+
+        // WHEN
+        let by_keys = find_cluster(&clusters, "prod,eu", "payments-api");
+        let by_name = find_cluster(&clusters, "qa-cluster", "auth-api");
+
+        // THEN
+        assert_eq!(
+            by_keys.map(|c| c.keys.join(",")),
+            Some("prod,eu".to_string())
+        );
+        assert_eq!(by_name.map(|c| c.keys.join(",")), Some("qa".to_string()));
+    }
+
+    #[test]
+    fn redacted_tasks_leave_out_arns_and_images() {
+        // GIVEN
+        let mut task = TaskSummary {
+            task_arn: "arn:aws:ecs:eu-central-1:111111111111:task/prod/0123abcd".to_string(),
+            task_id: "0123abcd".to_string(),
+            task_definition: Some("payments-api:42".to_string()),
+            last_status: Some("STOPPED".to_string()),
+            desired_status: Some("STOPPED".to_string()),
+            health_status: None,
+            launch_type: Some("FARGATE".to_string()),
+            cpu: Some("256".to_string()),
+            memory: Some("512".to_string()),
+            started_at: None,
+            stopped_reason: Some(
+                "Task failed ELB health checks in arn:aws:elasticloadbalancing:eu-central-1:111111111111:targetgroup/payments/abc".to_string(),
+            ),
+            containers: vec![
+                ContainerSummary {
+                    name: "app".to_string(),
+                    image: Some(
+                        "111111111111.dkr.ecr.eu-central-1.amazonaws.com/payments-api:42"
+                            .to_string(),
+                    ),
+                    last_status: Some("STOPPED".to_string()),
+                    health_status: None,
+                    exit_code: Some(137),
+                    reason: None,
+                },
+                ContainerSummary {
+                    name: "proxy".to_string(),
+                    image: Some("nginx:1.27".to_string()),
+                    last_status: Some("STOPPED".to_string()),
+                    health_status: None,
+                    exit_code: Some(0),
+                    reason: None,
+                },
+            ],
+        };
+
+        // WHEN
+        redact_task(&mut task);
+
+        // THEN
+        assert_eq!(task.task_arn, REDACTED);
+        assert_eq!(task.task_id, "0123abcd");
+        assert_eq!(
+            task.stopped_reason.as_deref(),
+            Some("Task failed ELB health checks in <REDACTED>")
+        );
+        let images = task
+            .containers
+            .iter()
+            .map(|c| c.image.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(images, vec![Some(REDACTED), Some(REDACTED)]);
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn services_not_in_the_profile_are_not_found() {
+        // GIVEN
+        let clusters = vec![
+            cluster(&["qa"], "qa-cluster", &["auth-api"]),
+            cluster(&["prod"], "prod-cluster", &["payments-api"]),
+        ];
+
+        // WHEN
+        let unlisted_service = find_cluster(&clusters, "qa", "payments-api");
+        let unknown_cluster = find_cluster(&clusters, "staging", "auth-api");
+
+        // THEN
+        assert!(unlisted_service.is_none());
+        assert!(unknown_cluster.is_none());
+    }
+}
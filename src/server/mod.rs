@@ -1,4 +1,11 @@
+mod auth;
+mod cache;
+mod dashboard;
 mod deployments;
+mod rate_limit;
 mod utils;
 
+pub use auth::{Auth, AuthConfigError};
+pub use cache::CacheConfig;
+pub use dashboard::serve_dashboard;
 pub use deployments::*;
@@ -0,0 +1,182 @@
+use super::auth::AuthenticatedUser;
+use axum::Json;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+// keeps clients that come and go from piling up
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Limits how many requests to the API each client can make per minute. Clients are told apart by
+/// the user they were authenticated as, or by their IP address when that's not known.
+#[derive(Debug)]
+pub struct ClientRateLimiter {
+    max_requests: u32,
+    /// When each client's current window started, and how many requests it's made in it
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl ClientRateLimiter {
+    pub fn new(max_requests_per_minute: u32) -> Self {
+        Self {
+            max_requests: max_requests_per_minute,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request made by a client; returns how long the client needs to wait for if it's
+    /// made too many.
+    fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        if windows.len() >= MAX_TRACKED_CLIENTS && !windows.contains_key(client) {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < RATE_LIMIT_WINDOW);
+            if windows.len() >= MAX_TRACKED_CLIENTS {
+                return Ok(());
+            }
+        }
+
+        let (started, count) = windows.entry(client.to_string()).or_insert((now, 0));
+        let elapsed = now.duration_since(*started);
+        if elapsed >= RATE_LIMIT_WINDOW {
+            *started = now;
+            *count = 0;
+        } else if *count >= self.max_requests {
+            return Err(RATE_LIMIT_WINDOW - elapsed);
+        }
+        *count += 1;
+
+        Ok(())
+    }
+
+    /// Returns how long a client needs to wait for if it's made too many requests already, without
+    /// counting a request.
+    pub(super) fn retry_after(&self, client: &str, now: Instant) -> Option<Duration> {
+        let windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        let (started, count) = windows.get(client)?;
+        let elapsed = now.duration_since(*started);
+        (elapsed < RATE_LIMIT_WINDOW && *count >= self.max_requests)
+            .then(|| RATE_LIMIT_WINDOW - elapsed)
+    }
+
+    /// Counts a request made by a client, whether or not it's made too many.
+    pub(super) fn count(&self, client: &str, now: Instant) {
+        let _ = self.check(client, now);
+    }
+}
+
+/// Turns away requests to the API from clients that have made too many of them; pages and assets
+/// are let through, since they don't result in calls to ECS.
+pub async fn limit_api_requests(
+    State(limiter): State<Arc<ClientRateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !request
+        .uri()
+        .path()
+        .split('/')
+        .any(|segment| segment == "api")
+    {
+        return next.run(request).await;
+    }
+
+    let client = match request.extensions().get::<AuthenticatedUser>() {
+        Some(user) => format!("user {}", user.0),
+        None => client_address(&request),
+    };
+
+    match limiter.check(&client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => too_many_requests(retry_after),
+    }
+}
+
+/// The IP address a request came from, if known.
+pub(super) fn client_address(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| address.ip().to_string())
+        .unwrap_or_default()
+}
+
+pub(super) fn too_many_requests(retry_after: Duration) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(
+            header::RETRY_AFTER,
+            retry_after.as_secs().max(1).to_string(),
+        )],
+        Json(serde_json::json!({ "error": "too many requests" })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn clients_are_limited_independently() {
+        // GIVEN
+        let limiter = ClientRateLimiter::new(2);
+        let now = Instant::now();
+
+        // WHEN
+        let alice = [
+            limiter.check("alice", now),
+            limiter.check("alice", now),
+            limiter.check("alice", now + Duration::from_secs(45)),
+        ];
+        let bob = limiter.check("bob", now);
+
+        // THEN
+        assert_eq!(alice, [Ok(()), Ok(()), Err(Duration::from_secs(15))]);
+        assert_eq!(bob, Ok(()));
+    }
+
+    #[test]
+    fn clients_can_make_requests_again_once_the_window_is_over() {
+        // GIVEN
+        let limiter = ClientRateLimiter::new(1);
+        let now = Instant::now();
+        let _ = limiter.check("alice", now);
+
+        // WHEN
+        let too_soon = limiter.check("alice", now + Duration::from_secs(59));
+        let later = limiter.check("alice", now + Duration::from_secs(60));
+
+        // THEN
+        assert_eq!(too_soon, Err(Duration::from_secs(1)));
+        assert_eq!(later, Ok(()));
+    }
+
+    #[test]
+    fn checking_whether_clients_need_to_wait_doesnt_count_as_a_request() {
+        // GIVEN
+        let limiter = ClientRateLimiter::new(2);
+        let now = Instant::now();
+        limiter.count("alice", now);
+
+        // WHEN
+        let after_one = limiter.retry_after("alice", now);
+        limiter.count("alice", now);
+        let after_two = limiter.retry_after("alice", now + Duration::from_secs(50));
+        let unknown = limiter.retry_after("bob", now);
+
+        // THEN
+        assert_eq!(after_one, None);
+        assert_eq!(after_two, Some(Duration::from_secs(10)));
+        assert_eq!(unknown, None);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::common::{DeploymentState, UNKNOWN};
-use crate::config::{ClusterConfig, ConfigSource};
+use crate::config::{ClientKey, ClusterConfig};
 use crate::domain::{DeploymentDetails, DeploymentError, DeploymentResult};
 use aws_sdk_ecs::Client as ECSClient;
 use std::collections::HashMap;
@@ -13,7 +13,7 @@ const DEPLOYMENT_STATUS_PRIMARY: &str = "PRIMARY";
 
 pub async fn get_deployments(
     clusters: Vec<ClusterConfig>,
-    clients_map: Arc<HashMap<ConfigSource, ECSClient>>,
+    clients_map: Arc<HashMap<ClientKey, ECSClient>>,
     state: Option<DeploymentState>,
 ) -> Result<(Vec<DeploymentDetails>, Vec<DeploymentError>), String> {
     let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
@@ -22,7 +22,7 @@ pub async fn get_deployments(
     for cluster in clusters {
         let client = Arc::new(
             clients_map
-                .get(&cluster.config_source)
+                .get(&cluster.client_key())
                 .ok_or("clients_map did not have entry for cluster".to_string())?
                 .clone(),
         );
@@ -69,7 +69,7 @@ async fn deployments_for_cluster(
     let servs_result = client
         .describe_services()
         .cluster(&cluster.arn)
-        .set_services(Some(cluster.services.clone()))
+        .set_services(Some(cluster.service_names()))
         .send()
         .await;
 
@@ -97,8 +97,9 @@ async fn deployments_for_cluster(
                         continue;
                     }
 
+                    let service_name = service.service_name().unwrap_or(UNKNOWN);
                     results.push(Ok(DeploymentDetails {
-                        service_name: service.service_name().unwrap_or(UNKNOWN).to_string(),
+                        service_name: service_name.to_string(),
                         keys: cluster.keys.join(","),
                         cluster_arn: cluster.arn.clone(),
                         deployment_id: dep.id().unwrap_or(UNKNOWN).to_string(),
@@ -107,6 +108,7 @@ async fn deployments_for_cluster(
                         desired_count: dep.desired_count(),
                         pending_count: dep.pending_count(),
                         failed_count: dep.failed_tasks(),
+                        service_alias: cluster.service_metadata(service_name).alias,
                     }));
                 }
             }
@@ -115,10 +117,11 @@ async fn deployments_for_cluster(
             let error = anyhow::anyhow!(error);
             for service in &cluster.services {
                 results.push(Err(DeploymentError {
-                    service_name: service.to_string(),
+                    service_name: service.name.clone(),
                     error: format!("{error:?}"),
                     cluster_arn: cluster.arn.clone(),
                     keys: cluster.keys.join(","),
+                    service_alias: service.metadata.alias.clone(),
                 }));
             }
         }
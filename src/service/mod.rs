@@ -1,3 +1,5 @@
 mod deployments;
+mod services;
 
 pub use deployments::*;
+pub use services::*;
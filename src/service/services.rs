@@ -0,0 +1,265 @@
+use crate::common::UNKNOWN;
+use crate::config::{ClientKey, ClusterConfig};
+use crate::domain::{
+    Arn, ContainerSummary, DeploymentDetails, DeploymentError, ServiceEvent, ServiceSummary,
+    TaskSummary,
+};
+use aws_sdk_ecs::Client as ECSClient;
+use aws_sdk_ecs::types::{Container, Service, Task};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_FETCHES: usize = 10;
+
+// https://docs.aws.amazon.com/AmazonECS/latest/APIReference/API_Deployment.html
+const DEPLOYMENT_STATUS_PRIMARY: &str = "PRIMARY";
+
+pub async fn get_services(
+    clusters: Vec<ClusterConfig>,
+    clients_map: Arc<HashMap<ClientKey, ECSClient>>,
+) -> Result<(Vec<ServiceSummary>, Vec<DeploymentError>), String> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut tasks = Vec::new();
+
+    for cluster in clusters {
+        let client = clients_map
+            .get(&cluster.client_key())
+            .ok_or("clients_map did not have entry for cluster".to_string())?
+            .clone();
+        let semaphore = Arc::clone(&semaphore);
+        tasks.push(tokio::task::spawn(async move {
+            services_for_cluster(cluster, client, semaphore).await
+        }));
+    }
+
+    let mut services = Vec::new();
+    let mut errors = Vec::new();
+
+    for task in tasks {
+        for result in task
+            .await
+            .map_err(|e| format!("couldn't join task: {e}"))??
+        {
+            match result {
+                Ok(s) => services.push(s),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    services.sort();
+    errors.sort();
+
+    Ok((services, errors))
+}
+
+async fn services_for_cluster(
+    cluster: ClusterConfig,
+    client: ECSClient,
+    semaphore: Arc<Semaphore>,
+) -> Result<Vec<Result<ServiceSummary, DeploymentError>>, String> {
+    let _permit = semaphore
+        .acquire()
+        .await
+        .map_err(|e| format!("couldn't acquire semaphore permit: {e}"))?;
+
+    let servs_result = client
+        .describe_services()
+        .cluster(&cluster.arn)
+        .set_services(Some(cluster.service_names()))
+        .send()
+        .await;
+
+    let results = match servs_result {
+        Ok(output) => output
+            .services()
+            .iter()
+            .map(|service| Ok(service_summary(service, &cluster)))
+            .collect(),
+        Err(error) => {
+            let error = anyhow::anyhow!(error);
+            cluster
+                .services
+                .iter()
+                .map(|service| {
+                    Err(DeploymentError {
+                        service_name: service.name.clone(),
+                        error: format!("{error:?}"),
+                        cluster_arn: cluster.arn.clone(),
+                        keys: cluster.keys.join(","),
+                        service_alias: service.metadata.alias.clone(),
+                    })
+                })
+                .collect()
+        }
+    };
+
+    Ok(results)
+}
+
+/// Returns a service along with its deployments and most recent events; or nothing, if ECS
+/// doesn't know of the service.
+pub async fn get_service(
+    cluster: &ClusterConfig,
+    client: &ECSClient,
+    service_name: &str,
+) -> Result<Option<(ServiceSummary, Vec<DeploymentDetails>, Vec<ServiceEvent>)>, String> {
+    let output = client
+        .describe_services()
+        .cluster(&cluster.arn)
+        .services(service_name)
+        .send()
+        .await
+        .map_err(|error| format!("{:?}", anyhow::anyhow!(error)))?;
+
+    let Some(service) = output.services().first() else {
+        return Ok(None);
+    };
+
+    let service_alias = cluster.service_metadata(service_name).alias;
+    let deployments = service
+        .deployments()
+        .iter()
+        .map(|dep| DeploymentDetails {
+            service_name: service_name.to_string(),
+            keys: cluster.keys.join(","),
+            cluster_arn: cluster.arn.clone(),
+            deployment_id: dep.id().unwrap_or(UNKNOWN).to_string(),
+            status: dep.status().unwrap_or(UNKNOWN).to_string(),
+            running_count: dep.running_count(),
+            desired_count: dep.desired_count(),
+            pending_count: dep.pending_count(),
+            failed_count: dep.failed_tasks(),
+            service_alias: service_alias.clone(),
+        })
+        .collect();
+
+    let events = service
+        .events()
+        .iter()
+        .map(|event| ServiceEvent {
+            id: event.id().unwrap_or(UNKNOWN).to_string(),
+            created_at: event.created_at().map(|t| t.secs()),
+            message: event.message().unwrap_or_default().to_string(),
+        })
+        .collect();
+
+    Ok(Some((
+        service_summary(service, cluster),
+        deployments,
+        events,
+    )))
+}
+
+/// Returns the tasks of a service, along with their containers.
+pub async fn get_tasks(
+    cluster: &ClusterConfig,
+    client: &ECSClient,
+    service_name: &str,
+) -> Result<Vec<TaskSummary>, String> {
+    let task_arns = client
+        .list_tasks()
+        .cluster(&cluster.arn)
+        .service_name(service_name)
+        .send()
+        .await
+        .map_err(|error| format!("{:?}", anyhow::anyhow!(error)))?
+        .task_arns
+        .unwrap_or_default();
+
+    // DescribeTasks doesn't accept an empty list of tasks
+    if task_arns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = client
+        .describe_tasks()
+        .cluster(&cluster.arn)
+        .set_tasks(Some(task_arns))
+        .send()
+        .await
+        .map_err(|error| format!("{:?}", anyhow::anyhow!(error)))?;
+
+    Ok(output.tasks().iter().map(task_summary).collect())
+}
+
+fn service_summary(service: &Service, cluster: &ClusterConfig) -> ServiceSummary {
+    let service_name = service.service_name().unwrap_or(UNKNOWN);
+    let task_definition = service
+        .deployments()
+        .iter()
+        .find(|d| d.status() == Some(DEPLOYMENT_STATUS_PRIMARY))
+        .and_then(|d| d.task_definition())
+        .or(service.task_definition())
+        .map(task_definition_name);
+
+    ServiceSummary {
+        service_name: service_name.to_string(),
+        keys: cluster.keys.join(","),
+        cluster_arn: cluster.arn.clone(),
+        metadata: cluster.service_metadata(service_name),
+        status: service.status().unwrap_or(UNKNOWN).to_string(),
+        running_count: service.running_count(),
+        desired_count: service.desired_count(),
+        pending_count: service.pending_count(),
+        failed_count: failed_count(service),
+        last_deployed_at: last_deployed_at(service),
+        task_definition,
+    }
+}
+
+fn task_summary(task: &Task) -> TaskSummary {
+    let task_arn = task.task_arn().unwrap_or(UNKNOWN);
+
+    TaskSummary {
+        task_arn: task_arn.to_string(),
+        task_id: Arn::parse(task_arn)
+            .map(|arn| arn.resource_name())
+            .unwrap_or(task_arn)
+            .to_string(),
+        task_definition: task.task_definition_arn().map(task_definition_name),
+        last_status: task.last_status().map(str::to_string),
+        desired_status: task.desired_status().map(str::to_string),
+        health_status: task.health_status().map(|s| s.as_str().to_string()),
+        launch_type: task.launch_type().map(|t| t.as_str().to_string()),
+        cpu: task.cpu().map(str::to_string),
+        memory: task.memory().map(str::to_string),
+        started_at: task.started_at().map(|t| t.secs()),
+        stopped_reason: task.stopped_reason().map(str::to_string),
+        containers: task.containers().iter().map(container_summary).collect(),
+    }
+}
+
+fn container_summary(container: &Container) -> ContainerSummary {
+    ContainerSummary {
+        name: container.name().unwrap_or(UNKNOWN).to_string(),
+        image: container.image().map(str::to_string),
+        last_status: container.last_status().map(str::to_string),
+        health_status: container.health_status().map(|s| s.as_str().to_string()),
+        exit_code: container.exit_code(),
+        reason: container.reason().map(str::to_string),
+    }
+}
+
+/// Turns the ARN of a task definition into its family and revision, eg. `payments-api:42`.
+fn task_definition_name(arn: &str) -> String {
+    Arn::parse(arn)
+        .map(|arn| arn.resource_name())
+        .unwrap_or(arn)
+        .to_string()
+}
+
+pub fn failed_count(service: &Service) -> i32 {
+    service.deployments().iter().map(|d| d.failed_tasks()).sum()
+}
+
+/// Returns when the most recent of the service's deployments was last updated.
+pub fn last_deployed_at(service: &Service) -> Option<i64> {
+    service
+        .deployments()
+        .iter()
+        .filter_map(|d| d.updated_at().or(d.created_at()))
+        .map(|t| t.secs())
+        .max()
+}
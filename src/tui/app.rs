@@ -1,15 +1,20 @@
 use super::command::Command;
 use super::common::*;
+use super::config::TuiConfig;
 use super::event::get_event_handling_msg;
 use super::handle::handle_command;
 use super::message::Message;
 use super::model::*;
+use super::session::SessionStore;
 use super::update::update;
 use super::view::view;
-use crate::config::{ClusterConfig, ConfigSource};
+use crate::config::{ClientKey, ClusterConfig, NamedFilters};
+use crate::domain::FilterExpr;
 use aws_sdk_ecs::Client as ECSClient;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::execute;
 use std::collections::HashMap;
 use std::io::Error as IOError;
 use std::sync::Arc;
@@ -22,10 +27,21 @@ pub const REFRESH_RESULTS_INTERVAL_SECS: u64 = 10;
 
 pub async fn run_tui(
     profile_name: String,
-    clients_map: HashMap<ConfigSource, ECSClient>,
+    clients_map: HashMap<ClientKey, ECSClient>,
     clusters: Vec<ClusterConfig>,
+    filter: Option<FilterExpr>,
+    named_filters: NamedFilters,
+    tui_config: TuiConfig,
+    session_store: SessionStore,
 ) -> anyhow::Result<()> {
-    let mut tui = AppTui::new(profile_name, clusters)?;
+    let mut tui = AppTui::new(
+        profile_name,
+        clusters,
+        filter,
+        named_filters,
+        tui_config,
+        session_store,
+    )?;
     tui.run(clients_map).await?;
 
     Ok(())
@@ -37,11 +53,25 @@ struct AppTui {
     pub(super) event_rx: Receiver<Message>,
     pub(super) model: Model,
     pub(super) initial_commands: Vec<Command>,
+    pub(super) mouse_capture: Option<MouseCapture>,
+    pub(super) session_store: SessionStore,
 }
 
 impl AppTui {
-    pub fn new(profile_name: String, clusters: Vec<ClusterConfig>) -> anyhow::Result<Self> {
+    pub fn new(
+        profile_name: String,
+        clusters: Vec<ClusterConfig>,
+        filter: Option<FilterExpr>,
+        named_filters: NamedFilters,
+        tui_config: TuiConfig,
+        session_store: SessionStore,
+    ) -> anyhow::Result<Self> {
         let terminal = ratatui::try_init()?;
+        let mouse_capture = if tui_config.mouse {
+            Some(MouseCapture::enable()?)
+        } else {
+            None
+        };
         let (event_tx, event_rx) = mpsc::channel(10);
         let mut initial_commands = Vec::new();
         for cluster in &clusters {
@@ -55,13 +85,30 @@ impl AppTui {
         let debug = std::env::var("ECSCOPE_DEBUG").unwrap_or_default().trim() == "1";
         let redact_mode = std::env::var("ECSCOPE_REDACT").unwrap_or("0".to_string()) == "1";
 
-        let model = Model::new(
+        // a broken session file shouldn't keep the TUI from starting; it's overwritten on exit
+        let (session, session_error) = match session_store.load() {
+            Ok(session) => (session.unwrap_or_default(), None),
+            Err(e) => (Default::default(), Some(e)),
+        };
+
+        let mut model = Model::new(
             profile_name,
             clusters,
             terminal_dimensions,
-            debug,
-            redact_mode,
+            ModelOptions {
+                filter,
+                named_filters,
+                tui_config,
+                session,
+                debug,
+                redact_mode,
+            },
         );
+        if let Some(e) = session_error {
+            model.user_message = Some(UserMessage::error(&format!(
+                "couldn't restore the last session: {e}"
+            )));
+        }
 
         Ok(Self {
             terminal,
@@ -69,12 +116,14 @@ impl AppTui {
             event_rx,
             model,
             initial_commands,
+            mouse_capture,
+            session_store,
         })
     }
 
     pub async fn run(
         &mut self,
-        clients_map: HashMap<ConfigSource, ECSClient>,
+        clients_map: HashMap<ClientKey, ECSClient>,
     ) -> anyhow::Result<()> {
         let message_clear_duration = Duration::from_secs(CLEAR_USER_MESSAGE_LOOP_INTERVAL_SECS);
         let refresh_results_duration = Duration::from_secs(REFRESH_RESULTS_INTERVAL_SECS);
@@ -110,6 +159,7 @@ impl AppTui {
 
                     if self.model.running_state == RunningState::Done {
                         self.exit()?;
+                        self.session_store.save(&self.model.session_state())?;
                         return Ok(());
                     }
 
@@ -141,6 +191,25 @@ impl AppTui {
     }
 
     fn exit(&mut self) -> Result<(), IOError> {
+        self.mouse_capture = None;
         ratatui::try_restore()
     }
 }
+
+/// Captures mouse events for as long as it's around; capture is disabled once it's dropped, so
+/// that the terminal isn't left reporting mouse events however the TUI exits (errors and panics
+/// included).
+struct MouseCapture;
+
+impl MouseCapture {
+    fn enable() -> Result<Self, IOError> {
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for MouseCapture {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    }
+}
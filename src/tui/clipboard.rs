@@ -0,0 +1,39 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io::Write;
+
+/// Copies text to the clipboard via the terminal (using OSC 52), which works over SSH as well;
+/// failures are ignored, as the terminal doesn't report back either way.
+pub fn copy_to_clipboard(text: &str) {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(osc52(text).as_bytes());
+    let _ = stdout.flush();
+}
+
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn osc52_sequence_is_built_correctly() {
+        // GIVEN
+        let text = "arn:aws:ecs:eu-central-1:111111111111:service/prod/payments-api";
+
+        // WHEN
+        let sequence = osc52(text);
+
+        // THEN
+        assert_eq!(
+            sequence,
+            "\x1b]52;c;YXJuOmF3czplY3M6ZXUtY2VudHJhbC0xOjExMTExMTExMTExMTpzZXJ2aWNlL3Byb2QvcGF5bWVudHMtYXBp\x07"
+        );
+    }
+}
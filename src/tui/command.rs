@@ -1,23 +1,30 @@
+use super::notify::{Notification, NotificationsConfig};
 use crate::config::ClusterConfig;
 use crate::domain::ServiceDetails;
 
 #[derive(Clone, Debug)]
 pub(super) enum Command {
     GetServices(ClusterConfig),
-    RefreshService((ServiceDetails, usize)),
+    RefreshService(ServiceDetails),
     GetTasks((ServiceDetails, bool)),
+    Notify((Notification, NotificationsConfig)),
+    CopyToClipboard(String),
+    OpenUrl(String),
 }
 
 impl std::fmt::Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Command::GetServices(_) => write!(f, "get services"),
-            Command::RefreshService((service_details, _)) => {
+            Command::RefreshService(service_details) => {
                 write!(f, "refresh service: {}", service_details.name)
             }
             Command::GetTasks((service_details, _)) => {
                 write!(f, "get tasks for service: {}", service_details.name)
             }
+            Command::Notify((notification, _)) => write!(f, "notify: {}", notification.message),
+            Command::CopyToClipboard(_) => write!(f, "copy to clipboard"),
+            Command::OpenUrl(url) => write!(f, "open url: {url}"),
         }
     }
 }
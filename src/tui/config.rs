@@ -0,0 +1,177 @@
+use super::keymap::{KeyMap, KeyMapError};
+use super::notify::{EscapeSequence, NotificationsConfig};
+use super::theme::{Theme, ThemeError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Error as IOError;
+use std::path::Path;
+use toml::de::Error as TomlError;
+
+const TUI_CONFIG_FILE: &str = "tui.toml";
+
+/// Settings for the TUI, read from tui.toml in ecscope's config directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuiConfig {
+    pub key_map: KeyMap,
+    pub theme: Theme,
+    /// Whether the TUI captures mouse events; turning this off lets the terminal handle text
+    /// selection
+    pub mouse: bool,
+    pub notifications: NotificationsConfig,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            key_map: KeyMap::default(),
+            theme: Theme::default(),
+            mouse: true,
+            notifications: NotificationsConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TuiConfigFile {
+    mouse: Option<bool>,
+    #[serde(default)]
+    keys: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    theme: ThemeConfig,
+    #[serde(default)]
+    notifications: NotificationsFileConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThemeConfig {
+    base: Option<String>,
+    #[serde(flatten)]
+    colours: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NotificationsFileConfig {
+    bell: Option<bool>,
+    escape_sequence: Option<EscapeSequence>,
+    command: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TuiConfigError {
+    #[error("couldn't read {TUI_CONFIG_FILE}: {0}")]
+    CouldntReadFile(IOError),
+    #[error("{TUI_CONFIG_FILE} is invalid: {0}")]
+    FileInvalid(#[from] TomlError),
+    #[error("{TUI_CONFIG_FILE} has invalid keys: {0}")]
+    KeysInvalid(#[from] KeyMapError),
+    #[error("{TUI_CONFIG_FILE} has an invalid theme: {0}")]
+    ThemeInvalid(#[from] ThemeError),
+}
+
+/// Reads the TUI's config from the config directory; falls back to the defaults if there isn't
+/// one.
+pub fn get_tui_config(config_dir: &Path) -> Result<TuiConfig, TuiConfigError> {
+    let path = config_dir.join(TUI_CONFIG_FILE);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(TuiConfig::default()),
+        Err(e) => return Err(TuiConfigError::CouldntReadFile(e)),
+    };
+
+    parse_tui_config(&contents)
+}
+
+fn parse_tui_config(contents: &str) -> Result<TuiConfig, TuiConfigError> {
+    let file: TuiConfigFile = toml::from_str(contents)?;
+
+    Ok(TuiConfig {
+        key_map: KeyMap::with_overrides(&file.keys)?,
+        theme: Theme::with_overrides(file.theme.base.as_deref(), &file.theme.colours)?,
+        mouse: file.mouse.unwrap_or(true),
+        notifications: NotificationsConfig {
+            bell: file.notifications.bell.unwrap_or(true),
+            escape_sequence: file.notifications.escape_sequence,
+            command: file.notifications.command.filter(|c| !c.trim().is_empty()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_tui_config_works() {
+        // GIVEN
+        let contents = r##"
+mouse = false
+
+[keys]
+next_item = ["n", "down"]
+
+[theme]
+base = "light"
+primary = "#458588"
+
+[notifications]
+bell = false
+escape_sequence = "osc777"
+command = 'notify-send "$ECSCOPE_TITLE" "$ECSCOPE_MESSAGE"'
+"##;
+
+        // WHEN
+        let config = parse_tui_config(contents).expect("config should've been parsed");
+
+        // THEN
+        assert_eq!(config.theme.primary, Color::Rgb(0x45, 0x85, 0x88));
+        assert_eq!(config.theme.background, Theme::light().background);
+        assert!(config.key_map.help().contains("n / ↓"));
+        assert!(!config.mouse);
+        assert_eq!(
+            config.notifications,
+            NotificationsConfig {
+                bell: false,
+                escape_sequence: Some(EscapeSequence::Osc777),
+                command: Some(r#"notify-send "$ECSCOPE_TITLE" "$ECSCOPE_MESSAGE""#.to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_tui_config_falls_back_to_the_defaults() {
+        // GIVEN
+        let config_dir = tempfile::tempdir().expect("temporary directory should've been created");
+
+        // WHEN
+        let config = get_tui_config(config_dir.path()).expect("config should've been read");
+
+        // THEN
+        assert_eq!(config, TuiConfig::default());
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_tui_config_with_unknown_sections_fails() {
+        // GIVEN
+        let contents = r#"
+[colors]
+primary = "blue"
+"#;
+
+        // WHEN
+        let result = parse_tui_config(contents);
+
+        // THEN
+        assert!(matches!(result, Err(TuiConfigError::FileInvalid(_))));
+    }
+}
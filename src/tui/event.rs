@@ -1,230 +1,223 @@
 use super::common::*;
+use super::keymap::Action;
 use super::message::Message;
 use super::model::*;
-use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::Position;
 
 pub fn get_event_handling_msg(model: &Model, event: Event) -> Option<Message> {
     match event {
         Event::Key(key_event) => match model.terminal_too_small {
             true => match key_event.kind {
-                KeyEventKind::Press => match key_event.code {
-                    KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
+                KeyEventKind::Press => match model.key_map.action(&key_event)? {
+                    Action::Quit => Some(Message::GoBackOrQuit),
                     _ => None,
                 },
                 _ => None,
             },
             false => match key_event.kind {
-                KeyEventKind::Press => match model.active_pane {
-                    Pane::ServicesList => match key_event.code {
-                        KeyCode::Char('2') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('3') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
-                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
-                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
-                        KeyCode::Char('G') => Some(Message::GoToLastListItem),
-                        KeyCode::Right => Some(Message::GoToPane(Pane::ServiceDetails)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::ServiceDetails))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('m') => Some(Message::ToggleServiceRefresh),
-                        KeyCode::Char('R') => Some(Message::ToggleAutoRefresh),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::ServiceDetails => match key_event.code {
-                        KeyCode::Char('2') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('3') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Left => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::TaskDetails)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::ServicesList))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::TasksList => match key_event.code {
-                        KeyCode::Char('1') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('3') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('4') => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
-                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
-                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
-                        KeyCode::Char('G') => Some(Message::GoToLastListItem),
-                        KeyCode::Right => Some(Message::GoToPane(Pane::TaskDetails)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::TaskDetails))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::TaskDetails => match key_event.code {
-                        KeyCode::Char('1') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('3') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('4') => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Left => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::TasksList))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::ServiceDetails)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::ContainersList => match key_event.code {
-                        KeyCode::Char('1') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('2') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('4') => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
-                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
-                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
-                        KeyCode::Char('G') => Some(Message::GoToLastListItem),
-                        KeyCode::Right => Some(Message::GoToPane(Pane::ContainerDetails)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::ContainerDetails))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::ContainerDetails => match key_event.code {
-                        KeyCode::Char('1') => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::Char('2') => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('3') => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Left => Some(Message::GoToPane(Pane::ContainersList)),
-                        KeyCode::Char('J') => Some(Message::GoToPane(Pane::ServiceDetails)),
-                        KeyCode::Char('L') | KeyCode::Char('H') => {
-                            Some(Message::GoToPane(Pane::ContainersList))
-                        }
-                        KeyCode::Char('K') => Some(Message::GoToPane(Pane::TaskDetails)),
-                        KeyCode::Tab => Some(Message::GoToPane(Pane::ServicesList)),
-                        KeyCode::BackTab => Some(Message::GoToPane(Pane::TasksList)),
-                        KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
-                        KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
-                        KeyCode::Char('r') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::RefreshResultsForMarkedServices)
-                            } else {
-                                Some(Message::RefreshResultsForCurrentItem)
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
-                    Pane::Help => match key_event.code {
-                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
-                            Some(Message::GoBackOrQuit)
-                        }
-                        KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                Some(Message::QuitImmediately)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    },
+                KeyEventKind::Press if model.filter_input.is_some() => match key_event.code {
+                    KeyCode::Enter => Some(Message::ApplyFilterInput),
+                    KeyCode::Esc => Some(Message::CancelFilterInput),
+                    KeyCode::Backspace => Some(Message::FilterInputBackspace),
+                    KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                        Some(Message::QuitImmediately)
+                    }
+                    KeyCode::Char(c) => Some(Message::FilterInputChar(c)),
+                    _ => None,
+                },
+                KeyEventKind::Press if model.search_input_active => match key_event.code {
+                    KeyCode::Enter | KeyCode::Down | KeyCode::Up => Some(Message::ConfirmSearch),
+                    KeyCode::Esc => Some(Message::ClearSearch),
+                    KeyCode::Backspace => Some(Message::SearchInputBackspace),
+                    KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                        Some(Message::QuitImmediately)
+                    }
+                    KeyCode::Char(c) => Some(Message::SearchInputChar(c)),
+                    _ => None,
                 },
+                KeyEventKind::Press => {
+                    get_action_handling_msg(model, model.key_map.action(&key_event)?)
+                }
                 _ => None,
             },
         },
+        Event::Mouse(mouse_event)
+            if !model.terminal_too_small
+                && model.filter_input.is_none()
+                && !model.search_input_active =>
+        {
+            get_mouse_handling_msg(model, mouse_event)
+        }
         Event::Resize(w, h) => Some(Message::TerminalResize(w, h)),
         _ => None,
     }
 }
+
+fn get_mouse_handling_msg(model: &Model, event: MouseEvent) -> Option<Message> {
+    let (column, row) = (event.column, event.row);
+
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if model.title_area.contains(Position::new(column, row)) {
+                return match model.active_pane {
+                    Pane::Help => Some(Message::GoBackOrQuit),
+                    _ => Some(Message::GoToPane(Pane::Help)),
+                };
+            }
+
+            let (pane, area) = model.pane_at(column, row)?;
+            if matches!(
+                pane,
+                Pane::ServicesList | Pane::TasksList | Pane::ContainersList
+            ) {
+                // a list's top border and padding take up its first two rows
+                let first_item_row = area.y + 2;
+                let bottom_border_row = (area.y + area.height).saturating_sub(1);
+                if (first_item_row..bottom_border_row).contains(&row) {
+                    return Some(Message::ClickListItem(
+                        pane,
+                        (row - first_item_row) as usize,
+                    ));
+                }
+            }
+
+            match pane {
+                Pane::Overview | Pane::Help => None,
+                p if p == model.active_pane => None,
+                p => Some(Message::GoToPane(p)),
+            }
+        }
+        MouseEventKind::ScrollDown => Some(Message::ScrollPaneDown(model.pane_at(column, row)?.0)),
+        MouseEventKind::ScrollUp => Some(Message::ScrollPaneUp(model.pane_at(column, row)?.0)),
+        _ => None,
+    }
+}
+
+fn get_action_handling_msg(model: &Model, action: Action) -> Option<Message> {
+    let pane = model.active_pane;
+
+    if pane == Pane::Help {
+        return match action {
+            Action::Help | Action::Quit => Some(Message::GoBackOrQuit),
+            Action::QuitImmediately => Some(Message::QuitImmediately),
+            _ => None,
+        };
+    }
+
+    let is_details = matches!(
+        pane,
+        Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails
+    );
+    // details panes showing full objects are navigated like lists
+    let is_list = matches!(
+        pane,
+        Pane::Overview | Pane::ServicesList | Pane::TasksList | Pane::ContainersList
+    ) || (is_details && model.full_details);
+
+    match action {
+        Action::Help => Some(Message::GoToPane(Pane::Help)),
+        Action::Quit if pane == Pane::ServicesList && model.service_items.search.is_some() => {
+            Some(Message::ClearSearch)
+        }
+        Action::Quit => Some(Message::GoBackOrQuit),
+        Action::QuitImmediately => Some(Message::QuitImmediately),
+        Action::NextItem if is_list => Some(Message::GoToNextListItem),
+        Action::PreviousItem if is_list => Some(Message::GoToPreviousListItem),
+        Action::FirstItem if is_list => Some(Message::GoToFirstListItem),
+        Action::LastItem if is_list => Some(Message::GoToLastListItem),
+        Action::Open if pane == Pane::Overview => Some(Message::OpenOverviewEntry),
+        Action::Open if pane == Pane::ServicesList => Some(Message::ToggleServiceGroup),
+        Action::Open | Action::ToggleGroup if is_details && model.full_details => {
+            Some(Message::ToggleObjectNode(pane))
+        }
+        Action::ToggleFullDetails => Some(Message::ToggleFullDetails),
+        Action::PaneLeft | Action::PaneRight => Some(Message::GoToPane(pane_beside(pane))),
+        Action::PaneBelow => Some(Message::GoToPane(pane_below(pane))),
+        Action::PaneAbove => Some(Message::GoToPane(pane_above(pane))),
+        Action::NextList => Some(Message::GoToPane(next_list(pane))),
+        Action::PreviousList => Some(Message::GoToPane(previous_list(pane))),
+        Action::GoToOverview => Some(Message::GoToPane(Pane::Overview)),
+        Action::GoToServices => Some(Message::GoToPane(Pane::ServicesList)),
+        Action::GoToTasks => Some(Message::GoToPane(Pane::TasksList)),
+        Action::GoToContainers => Some(Message::GoToPane(Pane::ContainersList)),
+        Action::GoToContainerDetails => Some(Message::GoToPane(Pane::ContainerDetails)),
+        Action::Refresh => Some(Message::RefreshResultsForCurrentItem),
+        Action::Yank => Some(Message::YankSelected),
+        Action::OpenInConsole => Some(Message::OpenSelectedInConsole),
+        Action::RefreshAll => Some(Message::RefreshResultsForMarkedServices),
+        Action::ToggleAutoRefresh if matches!(pane, Pane::Overview | Pane::ServicesList) => {
+            Some(Message::ToggleAutoRefresh)
+        }
+        Action::MarkService if pane == Pane::ServicesList => Some(Message::ToggleServiceRefresh),
+        Action::Filter if pane == Pane::ServicesList => Some(Message::OpenFilterInput),
+        Action::Search if pane == Pane::ServicesList => Some(Message::OpenSearchInput),
+        Action::CycleLayout if pane == Pane::ServicesList => Some(Message::CycleServicesLayout),
+        Action::CycleSort if pane == Pane::ServicesList => Some(Message::CycleServicesSort),
+        Action::ToggleGroup if pane == Pane::ServicesList => Some(Message::ToggleServiceGroup),
+        _ => None,
+    }
+}
+
+/// Lists and their details are laid out side by side.
+fn pane_beside(pane: Pane) -> Pane {
+    match pane {
+        Pane::Overview => Pane::Overview,
+        Pane::ServicesList => Pane::ServiceDetails,
+        Pane::ServiceDetails => Pane::ServicesList,
+        Pane::TasksList => Pane::TaskDetails,
+        Pane::TaskDetails => Pane::TasksList,
+        Pane::ContainersList => Pane::ContainerDetails,
+        Pane::ContainerDetails => Pane::ContainersList,
+        Pane::Help => Pane::Help,
+    }
+}
+
+fn pane_below(pane: Pane) -> Pane {
+    match pane {
+        Pane::Overview => Pane::ServicesList,
+        Pane::ServicesList => Pane::TasksList,
+        Pane::ServiceDetails => Pane::TaskDetails,
+        Pane::TasksList => Pane::ContainersList,
+        Pane::TaskDetails => Pane::ContainerDetails,
+        Pane::ContainersList => Pane::ServicesList,
+        Pane::ContainerDetails => Pane::ServiceDetails,
+        Pane::Help => Pane::Help,
+    }
+}
+
+fn pane_above(pane: Pane) -> Pane {
+    match pane {
+        Pane::Overview => Pane::Overview,
+        Pane::ServicesList => Pane::ContainersList,
+        Pane::ServiceDetails => Pane::ContainerDetails,
+        Pane::TasksList => Pane::ServicesList,
+        Pane::TaskDetails => Pane::ServiceDetails,
+        Pane::ContainersList => Pane::TasksList,
+        Pane::ContainerDetails => Pane::TaskDetails,
+        Pane::Help => Pane::Help,
+    }
+}
+
+/// Lists (and their details) are cycled through in the order they're laid out in.
+fn next_list(pane: Pane) -> Pane {
+    match pane {
+        Pane::Overview => Pane::ServicesList,
+        Pane::ServicesList | Pane::ServiceDetails => Pane::TasksList,
+        Pane::TasksList | Pane::TaskDetails => Pane::ContainersList,
+        Pane::ContainersList | Pane::ContainerDetails => Pane::ServicesList,
+        Pane::Help => Pane::Help,
+    }
+}
+
+fn previous_list(pane: Pane) -> Pane {
+    match pane {
+        Pane::Overview => Pane::Overview,
+        Pane::ServicesList | Pane::ServiceDetails => Pane::ContainersList,
+        Pane::TasksList | Pane::TaskDetails => Pane::ServicesList,
+        Pane::ContainersList | Pane::ContainerDetails => Pane::TasksList,
+        Pane::Help => Pane::Help,
+    }
+}
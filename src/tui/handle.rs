@@ -1,15 +1,18 @@
-use crate::config::{ClusterConfig, ConfigSource};
-use crate::domain::{ServiceDetails, ServiceError, ServiceResult};
+use crate::config::{ClientKey, ClusterConfig};
+use crate::domain::{ObjectTree, ServiceDetails, ServiceError, ServiceResult};
+use crate::service::{failed_count, last_deployed_at};
 
+use super::clipboard::copy_to_clipboard;
 use super::command::Command;
 use super::message::Message;
+use super::notify::send_notification;
 use aws_sdk_ecs::Client as ECSClient;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 
 pub(super) async fn handle_command(
-    client: Arc<HashMap<ConfigSource, ECSClient>>,
+    client: Arc<HashMap<ClientKey, ECSClient>>,
     command: Command,
     event_tx: Sender<Message>,
 ) {
@@ -20,10 +23,10 @@ pub(super) async fn handle_command(
                 handle_get_services(clients_map, cluster, event_tx).await;
             });
         }
-        Command::RefreshService((service_details, index)) => {
+        Command::RefreshService(service_details) => {
             let clients_map = Arc::clone(&client);
             tokio::spawn(async move {
-                handle_refresh_service(clients_map, service_details, index, event_tx).await;
+                handle_refresh_service(clients_map, service_details, event_tx).await;
             });
         }
         Command::GetTasks((service_details, refresh)) => {
@@ -32,23 +35,30 @@ pub(super) async fn handle_command(
                 handle_get_tasks(clients_map, service_details, event_tx, refresh).await;
             });
         }
+        Command::Notify((notification, config)) => send_notification(&notification, &config),
+        Command::CopyToClipboard(text) => copy_to_clipboard(&text),
+        Command::OpenUrl(url) => {
+            if open::that_detached(&url).is_err() {
+                let _ = event_tx.try_send(Message::CouldntOpenBrowser);
+            }
+        }
     }
 }
 
 async fn handle_get_services(
-    client: Arc<HashMap<ConfigSource, ECSClient>>,
+    client: Arc<HashMap<ClientKey, ECSClient>>,
     cluster: ClusterConfig,
     event_tx: Sender<Message>,
 ) {
     let mut si = Vec::new();
-    let client = client.get(&cluster.config_source);
+    let client = client.get(&cluster.client_key());
 
     let client = match client {
         Some(c) => c,
         None => {
             for service in &cluster.services {
                 si.push(ServiceResult::Err(ServiceError {
-                    service_name: service.to_string(),
+                    service_name: service.name.clone(),
                     error: "unexpected error".to_string(),
                     cluster_keys: cluster.keys.clone(),
                 }));
@@ -61,7 +71,7 @@ async fn handle_get_services(
     let servs_result = client
         .describe_services()
         .cluster(&cluster.arn)
-        .set_services(Some(cluster.services.clone()))
+        .set_services(Some(cluster.service_names()))
         .send()
         .await;
 
@@ -83,6 +93,8 @@ async fn handle_get_services(
                 let desired_count = service.desired_count();
                 let running_count = service.running_count();
                 let pending_count = service.pending_count();
+                let failed_count = failed_count(service);
+                let last_deployed_at = last_deployed_at(service);
 
                 let sr = ServiceResult::Ok(ServiceDetails {
                     name: service_name.into(),
@@ -90,9 +102,13 @@ async fn handle_get_services(
                     desired_count,
                     running_count,
                     pending_count,
+                    failed_count,
                     cluster_keys: cluster.keys.clone(),
                     cluster_arn: cluster.arn.to_string(),
-                    config_source: cluster.config_source.clone(),
+                    client_key: cluster.client_key(),
+                    metadata: cluster.service_metadata(service_name),
+                    last_deployed_at,
+                    object: Arc::new(ObjectTree::new(service)),
                 });
 
                 si.push(sr);
@@ -104,7 +120,7 @@ async fn handle_get_services(
             let error = anyhow::anyhow!(sdk_error);
             for service in &cluster.services {
                 si.push(ServiceResult::Err(ServiceError {
-                    service_name: service.to_string(),
+                    service_name: service.name.clone(),
                     error: format!("{error:?}"),
                     cluster_keys: cluster.keys.clone(),
                 }));
@@ -115,12 +131,11 @@ async fn handle_get_services(
 }
 
 async fn handle_refresh_service(
-    client: Arc<HashMap<ConfigSource, ECSClient>>,
+    client: Arc<HashMap<ClientKey, ECSClient>>,
     service_details: ServiceDetails,
-    index: usize,
     event_tx: Sender<Message>,
 ) {
-    let client = client.get(&service_details.config_source);
+    let client = client.get(&service_details.client_key);
 
     let client = match client {
         Some(c) => c,
@@ -132,8 +147,7 @@ async fn handle_refresh_service(
                     error: "unexpected error".to_string(),
                     cluster_keys: service_details.cluster_keys.clone(),
                 }),
-                service_details,
-                index,
+                Box::new(service_details),
             )));
             return;
         }
@@ -151,12 +165,11 @@ async fn handle_refresh_service(
             if describe_servs_output.services().len() != 1 {
                 let _ = event_tx.try_send(Message::ServiceDetailsRefreshed((
                     ServiceResult::Err(ServiceError {
-                        service_name: "unknown".to_string(),
+                        service_name: service_details.name.clone(),
                         error: "service name returned was empty".to_string(),
                         cluster_keys: service_details.cluster_keys.clone(),
                     }),
-                    service_details,
-                    index,
+                    Box::new(service_details),
                 )));
                 return;
             }
@@ -168,12 +181,11 @@ async fn handle_refresh_service(
                 None => {
                     let _ = event_tx.try_send(Message::ServiceDetailsRefreshed((
                         ServiceResult::Err(ServiceError {
-                            service_name: "unknown".to_string(),
+                            service_name: service_details.name.clone(),
                             error: "service name returned was empty".to_string(),
                             cluster_keys: service_details.cluster_keys.clone(),
                         }),
-                        service_details,
-                        index,
+                        Box::new(service_details),
                     )));
                     return;
                 }
@@ -183,6 +195,8 @@ async fn handle_refresh_service(
             let desired_count = service.desired_count();
             let running_count = service.running_count();
             let pending_count = service.pending_count();
+            let failed_count = failed_count(service);
+            let last_deployed_at = last_deployed_at(service);
 
             let sr = ServiceResult::Ok(ServiceDetails {
                 name: service_name.into(),
@@ -190,15 +204,18 @@ async fn handle_refresh_service(
                 desired_count,
                 running_count,
                 pending_count,
+                failed_count,
                 cluster_keys: service_details.cluster_keys.clone(),
                 cluster_arn: service_details.cluster_arn.to_string(),
-                config_source: service_details.config_source.clone(),
+                client_key: service_details.client_key.clone(),
+                metadata: service_details.metadata.clone(),
+                last_deployed_at,
+                object: Arc::new(ObjectTree::new(service)),
             });
 
             let _ = event_tx.try_send(Message::ServiceDetailsRefreshed((
                 sr,
-                service_details,
-                index,
+                Box::new(service_details),
             )));
         }
         Err(sdk_error) => {
@@ -209,22 +226,21 @@ async fn handle_refresh_service(
                     error: format!("{error:?}"),
                     cluster_keys: service_details.cluster_keys.clone(),
                 }),
-                service_details,
-                index,
+                Box::new(service_details),
             )));
         }
     }
 }
 
 async fn handle_get_tasks(
-    client: Arc<HashMap<ConfigSource, ECSClient>>,
+    client: Arc<HashMap<ClientKey, ECSClient>>,
     service_details: ServiceDetails,
     event_tx: Sender<Message>,
     refresh: bool,
 ) {
     std::thread::sleep(std::time::Duration::from_millis(2000));
 
-    let client = client.get(&service_details.config_source);
+    let client = client.get(&service_details.client_key);
     let client = match client {
         Some(c) => c,
         None => return,
@@ -0,0 +1,605 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+const KEY_COLUMN_WIDTH: usize = 20;
+
+/// Something a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Help,
+    Quit,
+    QuitImmediately,
+    NextItem,
+    PreviousItem,
+    FirstItem,
+    LastItem,
+    Open,
+    PaneLeft,
+    PaneBelow,
+    PaneAbove,
+    PaneRight,
+    NextList,
+    PreviousList,
+    GoToOverview,
+    GoToServices,
+    GoToTasks,
+    GoToContainers,
+    GoToContainerDetails,
+    Refresh,
+    RefreshAll,
+    ToggleAutoRefresh,
+    Yank,
+    OpenInConsole,
+    ToggleFullDetails,
+    MarkService,
+    Filter,
+    Search,
+    CycleLayout,
+    CycleSort,
+    ToggleGroup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    General,
+    MainView,
+    ServicesList,
+}
+
+impl Section {
+    fn title(&self) -> &'static str {
+        match self {
+            Section::General => "General",
+            Section::MainView => "Main View",
+            Section::ServicesList => "Services List",
+        }
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 31] = [
+        Action::Help,
+        Action::Quit,
+        Action::QuitImmediately,
+        Action::NextItem,
+        Action::PreviousItem,
+        Action::FirstItem,
+        Action::LastItem,
+        Action::Open,
+        Action::PaneLeft,
+        Action::PaneBelow,
+        Action::PaneAbove,
+        Action::PaneRight,
+        Action::NextList,
+        Action::PreviousList,
+        Action::GoToOverview,
+        Action::GoToServices,
+        Action::GoToTasks,
+        Action::GoToContainers,
+        Action::GoToContainerDetails,
+        Action::Refresh,
+        Action::RefreshAll,
+        Action::ToggleAutoRefresh,
+        Action::Yank,
+        Action::OpenInConsole,
+        Action::ToggleFullDetails,
+        Action::MarkService,
+        Action::Filter,
+        Action::Search,
+        Action::CycleLayout,
+        Action::CycleSort,
+        Action::ToggleGroup,
+    ];
+
+    /// The name used for the action in tui.toml.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Help => "help",
+            Action::Quit => "quit",
+            Action::QuitImmediately => "quit_immediately",
+            Action::NextItem => "next_item",
+            Action::PreviousItem => "previous_item",
+            Action::FirstItem => "first_item",
+            Action::LastItem => "last_item",
+            Action::Open => "open",
+            Action::PaneLeft => "pane_left",
+            Action::PaneBelow => "pane_below",
+            Action::PaneAbove => "pane_above",
+            Action::PaneRight => "pane_right",
+            Action::NextList => "next_list",
+            Action::PreviousList => "previous_list",
+            Action::GoToOverview => "go_to_overview",
+            Action::GoToServices => "go_to_services",
+            Action::GoToTasks => "go_to_tasks",
+            Action::GoToContainers => "go_to_containers",
+            Action::GoToContainerDetails => "go_to_container_details",
+            Action::Refresh => "refresh",
+            Action::RefreshAll => "refresh_all",
+            Action::ToggleAutoRefresh => "toggle_auto_refresh",
+            Action::Yank => "yank",
+            Action::OpenInConsole => "open_in_console",
+            Action::ToggleFullDetails => "toggle_full_details",
+            Action::MarkService => "mark_service",
+            Action::Filter => "filter",
+            Action::Search => "search",
+            Action::CycleLayout => "cycle_layout",
+            Action::CycleSort => "cycle_sort",
+            Action::ToggleGroup => "toggle_group",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Action::Help => "show/hide help view",
+            Action::Quit => "go back/exit",
+            Action::QuitImmediately => "exit immediately",
+            Action::NextItem => "go down in a list",
+            Action::PreviousItem => "go up in a list",
+            Action::FirstItem => "go to the top of a list",
+            Action::LastItem => "go to the bottom of a list",
+            Action::Open => {
+                "open the selected overview entry, or collapse/expand the selected group"
+            }
+            Action::PaneLeft => "move to the pane to the left",
+            Action::PaneBelow => "move to the pane below",
+            Action::PaneAbove => "move to the pane above",
+            Action::PaneRight => "move to the pane to the right",
+            Action::NextList => "move to the next list",
+            Action::PreviousList => "move to the previous list",
+            Action::GoToOverview => "go to the overview",
+            Action::GoToServices => "go to the services list",
+            Action::GoToTasks => "go to the tasks list",
+            Action::GoToContainers => "go to the containers list",
+            Action::GoToContainerDetails => "go to the container details",
+            Action::Refresh => "refresh details for current item",
+            Action::RefreshAll => "refresh data (either the ones marked, or all)",
+            Action::ToggleAutoRefresh => "toggle auto refresh (for either the ones marked, or all)",
+            Action::Yank => {
+                "copy the selected item's ARN (or the container's image, from its details)"
+            }
+            Action::OpenInConsole => "open the selected item in the AWS console",
+            Action::ToggleFullDetails => {
+                "show/hide all the fields of the selected items in the details panes"
+            }
+            Action::MarkService => "mark service for auto refresh",
+            Action::Filter => "filter services (enter to apply, esc to cancel)",
+            Action::Search => {
+                "search services by name or cluster key (enter to keep, esc to clear)"
+            }
+            Action::CycleLayout => "switch layout (flat, by key, by cluster, across keys)",
+            Action::CycleSort => {
+                "switch sort order (name, unhealthy first, cluster key, last deployed, task count)"
+            }
+            Action::ToggleGroup => "collapse/expand the selected group",
+        }
+    }
+
+    fn section(&self) -> Section {
+        match self {
+            Action::Help | Action::Quit | Action::QuitImmediately => Section::General,
+            Action::MarkService
+            | Action::Filter
+            | Action::Search
+            | Action::CycleLayout
+            | Action::CycleSort
+            | Action::ToggleGroup => Section::ServicesList,
+            _ => Section::MainView,
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Help => &["?"],
+            Action::Quit => &["esc", "q"],
+            Action::QuitImmediately => &["ctrl+c"],
+            Action::NextItem => &["j", "down"],
+            Action::PreviousItem => &["k", "up"],
+            Action::FirstItem => &["g"],
+            Action::LastItem => &["G"],
+            Action::Open => &["enter"],
+            Action::PaneLeft => &["H", "left"],
+            Action::PaneBelow => &["J"],
+            Action::PaneAbove => &["K"],
+            Action::PaneRight => &["L", "right"],
+            Action::NextList => &["tab"],
+            Action::PreviousList => &["backtab"],
+            Action::GoToOverview => &["0"],
+            Action::GoToServices => &["1"],
+            Action::GoToTasks => &["2"],
+            Action::GoToContainers => &["3"],
+            Action::GoToContainerDetails => &["4"],
+            Action::Refresh => &["r"],
+            Action::RefreshAll => &["ctrl+r"],
+            Action::ToggleAutoRefresh => &["R"],
+            Action::Yank => &["y"],
+            Action::OpenInConsole => &["o"],
+            Action::ToggleFullDetails => &["d"],
+            Action::MarkService => &["m"],
+            Action::Filter => &["f"],
+            Action::Search => &["/"],
+            Action::CycleLayout => &["v"],
+            Action::CycleSort => &["s"],
+            Action::ToggleGroup => &["space"],
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL.into_iter().find(|a| a.name() == s).ok_or(())
+    }
+}
+
+/// A key, optionally pressed along with ctrl; eg. "j", "G", "enter", "ctrl+r".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl KeyBinding {
+    fn matches(&self, event: &KeyEvent) -> bool {
+        self.code == event.code && self.ctrl == event.modifiers.contains(KeyModifiers::CONTROL)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ctrl, key) = match s.strip_prefix("ctrl+") {
+            Some(k) => (true, k),
+            None => (false, s),
+        };
+
+        let code = match key {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" | "shift+tab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            k => {
+                let mut chars = k.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!(r#""{s}" is not a valid key"#)),
+                }
+            }
+        };
+
+        Ok(Self { code, ctrl })
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self.code {
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "<S-Tab>".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            code => code.to_string(),
+        };
+
+        if self.ctrl {
+            write!(f, "<ctrl+{key}>")
+        } else {
+            write!(f, "{key}")
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum KeyMapError {
+    #[error(r#""{0}" is not a known action"#)]
+    UnknownAction(String),
+    #[error("invalid key for \"{0}\": {1}")]
+    InvalidKey(String, String),
+    #[error(r#"key "{0}" is bound to both "{1}" and "{2}""#)]
+    KeyBoundTwice(String, String, String),
+    #[error(r#""{0}" needs to be bound to at least one key, for there to be a way to exit"#)]
+    QuitUnbound(String),
+}
+
+/// The keys bound to each action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .filter_map(|k| KeyBinding::from_str(k).ok())
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Returns the default key map, with the keys for the actions in `overrides` replaced.
+    /// A key that's been bound to an action in `overrides` is removed from the action it's bound
+    /// to by default.
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<String>>) -> Result<Self, KeyMapError> {
+        let mut parsed: Vec<(Action, Vec<KeyBinding>)> = Vec::new();
+        for (name, keys) in overrides {
+            let action =
+                Action::from_str(name).map_err(|_| KeyMapError::UnknownAction(name.to_string()))?;
+            let keys = keys
+                .iter()
+                .map(|k| KeyBinding::from_str(k))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| KeyMapError::InvalidKey(name.to_string(), e))?;
+
+            for key in &keys {
+                if let Some((other, _)) = parsed.iter().find(|(_, ks)| ks.contains(key)) {
+                    return Err(KeyMapError::KeyBoundTwice(
+                        key.to_string(),
+                        other.name().to_string(),
+                        name.to_string(),
+                    ));
+                }
+            }
+
+            parsed.push((action, keys));
+        }
+
+        let mut key_map = Self::default();
+        for (action, keys) in key_map.bindings.iter_mut() {
+            match parsed.iter().find(|(a, _)| a == action) {
+                Some((_, overridden)) => *keys = overridden.clone(),
+                None => keys.retain(|k| !parsed.iter().any(|(_, ks)| ks.contains(k))),
+            }
+        }
+
+        // whether by being overridden or by having their keys taken away
+        for (action, keys) in &key_map.bindings {
+            if matches!(action, Action::Quit | Action::QuitImmediately) && keys.is_empty() {
+                return Err(KeyMapError::QuitUnbound(action.name().to_string()));
+            }
+        }
+
+        Ok(key_map)
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Returns the help text for the bound keys.
+    pub fn help(&self) -> String {
+        let mut lines = vec!["Keymaps".to_string(), "---".to_string()];
+
+        for section in [Section::General, Section::MainView, Section::ServicesList] {
+            lines.push(String::new());
+            lines.push(section.title().to_string());
+            for (action, keys) in self.bindings.iter().filter(|(a, _)| a.section() == section) {
+                if keys.is_empty() {
+                    continue;
+                }
+
+                let keys = keys
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                lines.push(format!(
+                    "    {keys:KEY_COLUMN_WIDTH$} {}",
+                    action.description()
+                ));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use ratatui::crossterm::event::KeyEventKind;
+
+    fn key_event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        }
+    }
+
+    fn overrides(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(a, keys)| (a.to_string(), keys.iter().map(|k| k.to_string()).collect()))
+            .collect()
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn help_is_generated_from_the_default_bindings() {
+        // GIVEN
+        let key_map = KeyMap::default();
+
+        // WHEN
+        let help = key_map.help();
+
+        // THEN
+        assert_snapshot!(help, @r"
+        Keymaps
+        ---
+
+        General
+            ?                    show/hide help view
+            Esc / q              go back/exit
+            <ctrl+c>             exit immediately
+
+        Main View
+            j / ↓                go down in a list
+            k / ↑                go up in a list
+            g                    go to the top of a list
+            G                    go to the bottom of a list
+            enter                open the selected overview entry, or collapse/expand the selected group
+            H / ←                move to the pane to the left
+            J                    move to the pane below
+            K                    move to the pane above
+            L / →                move to the pane to the right
+            Tab                  move to the next list
+            <S-Tab>              move to the previous list
+            0                    go to the overview
+            1                    go to the services list
+            2                    go to the tasks list
+            3                    go to the containers list
+            4                    go to the container details
+            r                    refresh details for current item
+            <ctrl+r>             refresh data (either the ones marked, or all)
+            R                    toggle auto refresh (for either the ones marked, or all)
+            y                    copy the selected item's ARN (or the container's image, from its details)
+            o                    open the selected item in the AWS console
+            d                    show/hide all the fields of the selected items in the details panes
+
+        Services List
+            m                    mark service for auto refresh
+            f                    filter services (enter to apply, esc to cancel)
+            /                    search services by name or cluster key (enter to keep, esc to clear)
+            v                    switch layout (flat, by key, by cluster, across keys)
+            s                    switch sort order (name, unhealthy first, cluster key, last deployed, task count)
+            space                collapse/expand the selected group
+        ");
+    }
+
+    #[test]
+    fn ctrl_bindings_are_told_apart_from_plain_ones() {
+        // GIVEN
+        let key_map = KeyMap::default();
+
+        // WHEN
+        let plain = key_map.action(&key_event(KeyCode::Char('r'), KeyModifiers::NONE));
+        let ctrl = key_map.action(&key_event(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        let shifted = key_map.action(&key_event(KeyCode::Char('R'), KeyModifiers::SHIFT));
+
+        // THEN
+        assert_eq!(plain, Some(Action::Refresh));
+        assert_eq!(ctrl, Some(Action::RefreshAll));
+        assert_eq!(shifted, Some(Action::ToggleAutoRefresh));
+    }
+
+    #[test]
+    fn tab_cycles_through_lists_rather_than_panes() {
+        // GIVEN
+        let key_map = KeyMap::default();
+
+        // WHEN
+        let tab = key_map.action(&key_event(KeyCode::Tab, KeyModifiers::NONE));
+        let backtab = key_map.action(&key_event(KeyCode::BackTab, KeyModifiers::SHIFT));
+
+        // THEN
+        assert_eq!(tab, Some(Action::NextList));
+        assert_eq!(backtab, Some(Action::PreviousList));
+    }
+
+    #[test]
+    fn overriding_an_action_takes_its_keys_away_from_the_defaults() {
+        // GIVEN
+        let overrides = overrides(&[("next_item", &["n"]), ("refresh", &["j", "ctrl+l"])]);
+
+        // WHEN
+        let key_map = KeyMap::with_overrides(&overrides).expect("overrides should've been valid");
+
+        // THEN
+        let action = |c, m| key_map.action(&key_event(KeyCode::Char(c), m));
+        assert_eq!(action('n', KeyModifiers::NONE), Some(Action::NextItem));
+        assert_eq!(action('j', KeyModifiers::NONE), Some(Action::Refresh));
+        assert_eq!(action('l', KeyModifiers::CONTROL), Some(Action::Refresh));
+        assert_eq!(action('r', KeyModifiers::NONE), None);
+        assert_eq!(
+            key_map.action(&key_event(KeyCode::Down, KeyModifiers::NONE)),
+            None
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn overriding_with_invalid_bindings_fails() {
+        // GIVEN
+        let cases = [
+            (
+                overrides(&[("launch_rockets", &["x"])]),
+                KeyMapError::UnknownAction("launch_rockets".to_string()),
+            ),
+            (
+                overrides(&[("refresh", &["ctrl+"])]),
+                KeyMapError::InvalidKey(
+                    "refresh".to_string(),
+                    r#""ctrl+" is not a valid key"#.to_string(),
+                ),
+            ),
+            (
+                overrides(&[("refresh", &["x"]), ("search", &["x"])]),
+                KeyMapError::KeyBoundTwice(
+                    "x".to_string(),
+                    "refresh".to_string(),
+                    "search".to_string(),
+                ),
+            ),
+            (
+                overrides(&[("quit", &[]), ("quit_immediately", &[])]),
+                KeyMapError::QuitUnbound("quit".to_string()),
+            ),
+            (
+                overrides(&[("help", &["ctrl+c"])]),
+                KeyMapError::QuitUnbound("quit_immediately".to_string()),
+            ),
+        ];
+
+        for (overrides, expected) in cases {
+            // WHEN
+            let result = KeyMap::with_overrides(&overrides);
+
+            // THEN
+            assert_eq!(result, Err(expected));
+        }
+    }
+}
@@ -9,7 +9,7 @@ pub enum Message {
     GoToFirstListItem,
     GoToLastListItem,
     ServicesFetched(Vec<ServiceResult>),
-    ServiceDetailsRefreshed((ServiceResult, ServiceDetails, usize)),
+    ServiceDetailsRefreshed((ServiceResult, Box<ServiceDetails>)),
     TasksFetched((ServiceDetails, Vec<Task>, bool)),
     ClearUserMsg,
     RefreshResultsForMarkedServices,
@@ -19,4 +19,26 @@ pub enum Message {
     GoBackOrQuit,
     QuitImmediately,
     GoToPane(Pane),
+    ClickListItem(Pane, usize),
+    ScrollPaneDown(Pane),
+    ScrollPaneUp(Pane),
+    OpenFilterInput,
+    FilterInputChar(char),
+    FilterInputBackspace,
+    ApplyFilterInput,
+    CancelFilterInput,
+    OpenSearchInput,
+    SearchInputChar(char),
+    SearchInputBackspace,
+    ConfirmSearch,
+    ClearSearch,
+    CycleServicesLayout,
+    CycleServicesSort,
+    ToggleServiceGroup,
+    OpenOverviewEntry,
+    YankSelected,
+    OpenSelectedInConsole,
+    CouldntOpenBrowser,
+    ToggleFullDetails,
+    ToggleObjectNode(Pane),
 }
@@ -1,11 +1,19 @@
 mod app;
+mod clipboard;
 mod command;
 mod common;
+mod config;
 mod event;
 mod handle;
+mod keymap;
 mod message;
 mod model;
+mod notify;
+mod session;
+mod theme;
 mod update;
 mod view;
 
 pub use app::run_tui;
+pub use config::{TuiConfig, TuiConfigError, get_tui_config};
+pub use session::SessionStore;
@@ -1,4 +1,5 @@
 use super::super::common::*;
+use crate::domain::ObjectTree;
 use aws_sdk_ecs::types::Container;
 use ratatui::{
     text::Line,
@@ -9,11 +10,14 @@ use ratatui::{
 pub struct ContainerItem {
     pub container: Container,
     pub status: bool,
+    /// All of the container's fields
+    pub object: ObjectTree,
 }
 
 impl ContainerItem {
     fn new(container: Container) -> Self {
         Self {
+            object: ObjectTree::new(&container),
             container,
             status: false,
         }
@@ -1,15 +1,25 @@
 mod containers;
+mod object_view;
+mod overview;
 mod services;
 mod tasks;
 
 pub use containers::*;
+pub use object_view::*;
+pub use overview::*;
 pub use services::*;
 pub use tasks::*;
 
 use super::common::*;
-use crate::config::ClusterConfig;
+use super::config::TuiConfig;
+use super::keymap::KeyMap;
+use super::notify::NotificationsConfig;
+use super::session::{PendingRestore, ServiceRef, SessionState};
+use super::theme::Theme;
+use crate::config::{ClusterConfig, NamedFilters};
 use crate::domain::*;
-use aws_sdk_ecs::types::Task;
+use aws_sdk_ecs::types::{Container, Task};
+use ratatui::layout::{Position, Rect};
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -44,10 +54,15 @@ pub struct Model {
     pub active_pane: Pane,
     pub last_active_pane: Option<Pane>,
     pub running_state: RunningState,
-    pub task_results_cache: HashMap<ServiceDetails, Vec<Task>>,
+    pub task_results_cache: HashMap<ServiceId, Vec<Task>>,
     pub num_fetches_in_flight: usize,
     pub num_errors: usize,
     pub service_items: ServiceItems,
+    pub named_filters: NamedFilters,
+    /// What's been typed in the filter prompt, while it's open
+    pub filter_input: Option<String>,
+    /// Whether keys typed go to the services list's search
+    pub search_input_active: bool,
     pub task_items: Option<TaskItems>,
     pub container_items: ContainerItems,
     pub user_message: Option<UserMessage>,
@@ -57,6 +72,33 @@ pub struct Model {
     pub event_counter: u64,
     pub num_services_marked_for_refresh: usize,
     pub auto_refresh: bool,
+    /// Index of the selected entry in the overview
+    pub overview_selected: usize,
+    pub transitions: Transitions,
+    pub key_map: KeyMap,
+    pub theme: Theme,
+    pub notifications: NotificationsConfig,
+    /// Where each pane was last rendered; used to figure out what the mouse is pointing at
+    pub pane_areas: Vec<(Pane, Rect)>,
+    pub title_area: Rect,
+    /// How far down the details panes (and the help view) have been scrolled
+    pub details_scroll: HashMap<Pane, u16>,
+    /// Whether the details panes show all the fields of the selected items
+    pub full_details: bool,
+    pub object_views: HashMap<Pane, ObjectView>,
+    pub pending_restore: PendingRestore,
+    /// The filter to save for the next session; it's only changed by filters entered in the TUI
+    pub session_filter: Option<String>,
+    pub debug: bool,
+    pub redact_mode: bool,
+}
+
+/// What the TUI starts off with, besides the clusters being monitored
+pub struct ModelOptions {
+    pub filter: Option<FilterExpr>,
+    pub named_filters: NamedFilters,
+    pub tui_config: TuiConfig,
+    pub session: SessionState,
     pub debug: bool,
     pub redact_mode: bool,
 }
@@ -66,31 +108,78 @@ impl Model {
         profile_name: String,
         clusters: Vec<ClusterConfig>,
         terminal_dimensions: TerminalDimensions,
-        debug: bool,
-        redact_mode: bool,
+        options: ModelOptions,
     ) -> Self {
+        let ModelOptions {
+            filter,
+            named_filters,
+            tui_config,
+            session,
+            debug,
+            redact_mode,
+        } = options;
         let terminal_too_small = terminal_dimensions.width < MIN_TERMINAL_WIDTH
             || terminal_dimensions.height < MIN_TERMINAL_HEIGHT;
+        let key_order = clusters.iter().flat_map(|c| c.keys.clone()).collect();
+
+        // a filter passed in takes precedence over the one from the last session, but is a one-off,
+        // so the last session's filter is carried over to the next one
+        let mut user_message = None;
+        let mut session_filter = session.filter.clone();
+        let filter = match (filter, &session.filter) {
+            (Some(filter), _) => Some(filter),
+            (None, Some(source)) => match FilterExpr::parse_with_named(source, &named_filters)
+                .and_then(FilterExpr::supported_by::<ServiceDetails>)
+            {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    user_message = Some(UserMessage::error(&format!(
+                        "couldn't restore the last session's filter: {e}"
+                    )));
+                    session_filter = None;
+                    None
+                }
+            },
+            (None, None) => None,
+        };
 
         Self {
             profile_name,
             clusters,
-            active_pane: Pane::ServicesList,
+            active_pane: Pane::Overview,
             last_active_pane: None,
             running_state: RunningState::Running,
             task_results_cache: HashMap::new(),
             num_fetches_in_flight: 0,
             num_errors: 0,
-            service_items: ServiceItems::empty(),
+            service_items: ServiceItems::empty()
+                .with_key_order(key_order)
+                .with_sort(session.sort)
+                .with_filter(filter),
+            named_filters,
+            filter_input: None,
+            search_input_active: false,
             task_items: None,
             container_items: ContainerItems::empty(),
-            user_message: None,
+            user_message,
             terminal_dimensions,
             terminal_too_small,
             render_counter: 0,
             event_counter: 0,
             num_services_marked_for_refresh: 0,
-            auto_refresh: false,
+            auto_refresh: session.auto_refresh,
+            overview_selected: 0,
+            transitions: Transitions::default(),
+            key_map: tui_config.key_map,
+            theme: tui_config.theme,
+            notifications: tui_config.notifications,
+            pane_areas: Vec::new(),
+            title_area: Rect::default(),
+            details_scroll: HashMap::new(),
+            full_details: false,
+            object_views: HashMap::new(),
+            pending_restore: PendingRestore::from(&session),
+            session_filter,
             debug,
             redact_mode,
         }
@@ -99,52 +188,64 @@ impl Model {
     pub(super) fn go_back_or_quit(&mut self) {
         let active_pane = Some(self.active_pane);
         match self.active_pane {
-            Pane::ServicesList => self.running_state = RunningState::Done,
+            Pane::Overview => self.running_state = RunningState::Done,
+            Pane::ServicesList => self.active_pane = Pane::Overview,
             Pane::ServiceDetails => self.active_pane = Pane::ServicesList,
             Pane::TasksList => self.active_pane = Pane::ServicesList,
             Pane::TaskDetails => self.active_pane = Pane::TasksList,
             Pane::ContainersList => self.active_pane = Pane::TasksList,
             Pane::ContainerDetails => self.active_pane = Pane::ContainersList,
-            Pane::Help => self.active_pane = self.last_active_pane.unwrap_or(Pane::ServicesList),
+            Pane::Help => self.active_pane = self.last_active_pane.unwrap_or(Pane::Overview),
         }
 
         self.last_active_pane = active_pane;
     }
 
-    pub(super) fn select_next_list_item(&mut self) {
-        match self.active_pane {
+    pub(super) fn select_next_list_item(&mut self, pane: Pane) {
+        match pane {
+            Pane::Overview => {
+                let last = self.overview().len().saturating_sub(1);
+                self.overview_selected = (self.overview_selected + 1).min(last);
+            }
             Pane::ServicesList => self.service_items.state.select_next(),
-            Pane::ServiceDetails => {}
             Pane::TasksList => {
                 if let Some(i) = &mut self.task_items {
                     i.state.select_next()
                 }
             }
-            Pane::TaskDetails => {}
             Pane::ContainersList => self.container_items.state.select_next(),
-            Pane::ContainerDetails => {}
+            Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails => {
+                self.update_object_view(pane, |view, tree| {
+                    let num_rows = view.rows(tree).len();
+                    view.select_next(num_rows);
+                });
+            }
             Pane::Help => {}
         }
     }
 
-    pub(super) fn select_previous_list_item(&mut self) {
-        match self.active_pane {
+    pub(super) fn select_previous_list_item(&mut self, pane: Pane) {
+        match pane {
+            Pane::Overview => self.overview_selected = self.overview_selected.saturating_sub(1),
             Pane::ServicesList => self.service_items.state.select_previous(),
-            Pane::ServiceDetails => {}
             Pane::TasksList => {
                 if let Some(i) = &mut self.task_items {
                     i.state.select_previous()
                 }
             }
-            Pane::TaskDetails => {}
             Pane::ContainersList => self.container_items.state.select_previous(),
-            Pane::ContainerDetails => {}
+            Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails => {
+                if self.object_tree(pane).is_some() {
+                    self.object_views.entry(pane).or_default().select_previous();
+                }
+            }
             Pane::Help => {}
         }
     }
 
     pub(super) fn select_first_list_item(&mut self) {
         match self.active_pane {
+            Pane::Overview => self.overview_selected = 0,
             Pane::ServicesList => self.service_items.state.select_first(),
             Pane::TasksList => {
                 if let Some(i) = &mut self.task_items {
@@ -152,11 +253,21 @@ impl Model {
                 }
             }
             Pane::ContainersList => self.container_items.state.select_first(),
+            pane @ (Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails)
+                if self.object_tree(pane).is_some() =>
+            {
+                self.object_views
+                    .entry(pane)
+                    .or_default()
+                    .state
+                    .select_first();
+            }
             _ => {}
         }
     }
     pub(super) fn select_last_list_item(&mut self) {
         match self.active_pane {
+            Pane::Overview => self.overview_selected = self.overview().len().saturating_sub(1),
             Pane::ServicesList => self.service_items.state.select_last(),
             Pane::TasksList => {
                 if let Some(i) = &mut self.task_items {
@@ -164,12 +275,236 @@ impl Model {
                 }
             }
             Pane::ContainersList => self.container_items.state.select_last(),
+            pane @ (Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails) => {
+                self.update_object_view(pane, |view, tree| {
+                    let num_rows = view.rows(tree).len();
+                    view.state.select(num_rows.checked_sub(1));
+                });
+            }
             _ => {}
         }
     }
 
+    /// Selects the item at `row` (counted from the first one on screen) in the given list, if
+    /// there's one there.
+    pub(super) fn select_list_item_on_screen(&mut self, pane: Pane, row: usize) {
+        let (state, num_items) = match pane {
+            Pane::ServicesList => (&mut self.service_items.state, self.service_items.rows.len()),
+            Pane::TasksList => match &mut self.task_items {
+                Some(i) => (&mut i.state, i.items.len()),
+                None => return,
+            },
+            Pane::ContainersList => (
+                &mut self.container_items.state,
+                self.container_items.items.len(),
+            ),
+            _ => return,
+        };
+
+        let index = state.offset() + row;
+        if index < num_items {
+            state.select(Some(index));
+        }
+    }
+
+    pub(super) fn scroll_pane(&mut self, pane: Pane, down: bool) {
+        match pane {
+            Pane::Overview | Pane::ServicesList | Pane::TasksList | Pane::ContainersList => {
+                match down {
+                    true => self.select_next_list_item(pane),
+                    false => self.select_previous_list_item(pane),
+                }
+            }
+            // scrolling past the end is corrected when rendering, as that's when the length of
+            // the contents is known
+            Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails
+                if self.object_tree(pane).is_some() =>
+            {
+                match down {
+                    true => self.select_next_list_item(pane),
+                    false => self.select_previous_list_item(pane),
+                }
+            }
+            Pane::ServiceDetails | Pane::TaskDetails | Pane::ContainerDetails | Pane::Help => {
+                let scroll = self.details_scroll.entry(pane).or_default();
+                *scroll = match down {
+                    true => scroll.saturating_add(1),
+                    false => scroll.saturating_sub(1),
+                };
+            }
+        }
+    }
+
+    /// Returns all the fields of the item shown in the given details pane, when the details
+    /// panes are showing those.
+    pub fn object_tree(&self, pane: Pane) -> Option<&ObjectTree> {
+        // the full objects hold ARNs, images, environment variables, etc.
+        if !self.full_details || self.redact_mode {
+            return None;
+        }
+
+        match pane {
+            Pane::ServiceDetails => match self.get_selected_service()? {
+                (Ok(service), _) => Some(service.object.as_ref()),
+                (Err(_), _) => None,
+            },
+            Pane::TaskDetails => {
+                let task_items = self.task_items.as_ref()?;
+                Some(&task_items.items.get(task_items.state.selected()?)?.object)
+            }
+            Pane::ContainerDetails => {
+                self.task_items.as_ref()?;
+                Some(
+                    &self
+                        .container_items
+                        .items
+                        .get(self.container_items.state.selected()?)?
+                        .object,
+                )
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn toggle_object_node(&mut self, pane: Pane) {
+        self.update_object_view(pane, |view, tree| view.toggle_selected(tree));
+    }
+
+    /// Updates the view of the object shown in the given details pane, if there's one.
+    fn update_object_view(
+        &mut self,
+        pane: Pane,
+        update: impl FnOnce(&mut ObjectView, &ObjectTree),
+    ) {
+        let mut view = self.object_views.remove(&pane).unwrap_or_default();
+        if let Some(tree) = self.object_tree(pane) {
+            update(&mut view, tree);
+        }
+        self.object_views.insert(pane, view);
+    }
+
+    pub fn overview(&self) -> Overview {
+        Overview::new(&self.service_items, self.transitions.items.len())
+    }
+
+    /// Selects the service(s) behind the selected overview entry in the services list, and
+    /// moves there.
+    pub(super) fn open_overview_entry(&mut self) {
+        let Some(entry) = self.overview().entry(self.overview_selected) else {
+            return;
+        };
+
+        let found = match entry {
+            OverviewEntry::Key(key) => {
+                if self.service_items.layout != ServicesLayout::ByKey {
+                    self.service_items.set_layout(ServicesLayout::ByKey);
+                }
+                self.service_items.select_group(&key)
+            }
+            OverviewEntry::Deploying(index) => self.service_items.select_item(index),
+            OverviewEntry::Transition(index) => {
+                match self
+                    .transitions
+                    .items
+                    .get(index)
+                    .and_then(|t| self.service_items.position_of_item(&t.item))
+                {
+                    Some(index) => self.service_items.select_item(index),
+                    None => false,
+                }
+            }
+        };
+
+        if !found {
+            self.user_message = Some(UserMessage::error(
+                "couldn't find that in the services list (is it hidden by the filter?)",
+            ));
+            return;
+        }
+
+        self.last_active_pane = Some(self.active_pane);
+        self.active_pane = Pane::ServicesList;
+    }
+
+    /// Returns the service the active pane is about, if any; in the overview, that's the service
+    /// behind the selected entry.
+    fn active_service(&self) -> Option<&ServiceDetails> {
+        let index = match self.active_pane {
+            Pane::Overview => match self.overview().entry(self.overview_selected)? {
+                OverviewEntry::Key(_) => None,
+                OverviewEntry::Deploying(index) => Some(index),
+                OverviewEntry::Transition(index) => self
+                    .transitions
+                    .items
+                    .get(index)
+                    .and_then(|t| self.service_items.position_of_item(&t.item)),
+            },
+            Pane::Help => None,
+            _ => self.service_items.selected_index(),
+        }?;
+
+        self.service_items
+            .items
+            .get(index)
+            .and_then(|i| i.service.as_ref().ok())
+    }
+
+    /// Returns what gets copied for the active pane: what it is, along with its value.
+    pub(super) fn selected_identifier(&self) -> Option<(&'static str, String)> {
+        match self.active_pane {
+            Pane::Overview | Pane::ServicesList | Pane::ServiceDetails => {
+                let service = self.active_service()?;
+                Some((
+                    "service ARN",
+                    service_arn(&service.cluster_arn, &service.name)?,
+                ))
+            }
+            Pane::TasksList | Pane::TaskDetails => Some((
+                "task ARN",
+                self.get_selected_task()?.task_arn()?.to_string(),
+            )),
+            Pane::ContainersList => Some((
+                "container ARN",
+                self.get_selected_container()?.container_arn()?.to_string(),
+            )),
+            Pane::ContainerDetails => {
+                Some(("image", self.get_selected_container()?.image()?.to_string()))
+            }
+            Pane::Help => None,
+        }
+    }
+
+    /// Returns the AWS console's URL for what's selected in the active pane, along with the AWS
+    /// account it's in.
+    pub(super) fn selected_console_url(&self) -> Option<(String, String)> {
+        let (url, arn) = match self.active_pane {
+            Pane::Overview | Pane::ServicesList | Pane::ServiceDetails => {
+                let service = self.active_service()?;
+                (
+                    service_console_url(&service.cluster_arn, &service.name)?,
+                    service.cluster_arn.as_str(),
+                )
+            }
+            Pane::TasksList | Pane::TaskDetails | Pane::ContainersList | Pane::ContainerDetails => {
+                let task_arn = self.get_selected_task()?.task_arn()?;
+                (task_console_url(task_arn)?, task_arn)
+            }
+            Pane::Help => return None,
+        };
+
+        Some((url, Arn::parse(arn)?.account.to_string()))
+    }
+
+    /// Returns the pane at the given position on screen.
+    pub(super) fn pane_at(&self, column: u16, row: u16) -> Option<(Pane, Rect)> {
+        self.pane_areas
+            .iter()
+            .find(|(_, area)| area.contains(Position::new(column, row)))
+            .copied()
+    }
+
     pub fn get_selected_service(&self) -> Option<(&ServiceResult, usize)> {
-        let service_index = self.service_items.state.selected()?;
+        let service_index = self.service_items.selected_index()?;
 
         self.service_items
             .items
@@ -177,6 +512,98 @@ impl Model {
             .map(|si| (&si.service, service_index))
     }
 
+    /// Marks (and selects) the services from the last session that have been fetched since
+    /// this was last called.
+    pub(super) fn restore_session(&mut self) {
+        for item in &mut self.service_items.items {
+            if let Ok(service) = &item.service
+                && self
+                    .pending_restore
+                    .marked
+                    .remove(&ServiceRef::from(service))
+                && !item.marked_for_refresh
+            {
+                item.marked_for_refresh = true;
+                self.num_services_marked_for_refresh += 1;
+            }
+        }
+
+        let Some(selected) = &self.pending_restore.selected else {
+            return;
+        };
+        let position = self
+            .service_items
+            .items
+            .iter()
+            .position(|i| matches!(&i.service, Ok(s) if &ServiceRef::from(s) == selected));
+        if let Some(index) = position {
+            self.service_items.select_item(index);
+            self.pending_restore.selected = None;
+        }
+    }
+
+    /// Returns the state to carry over to the next session; services from the last session that
+    /// weren't fetched this time around are held on to.
+    pub fn session_state(&self) -> SessionState {
+        let mut marked_services: Vec<ServiceRef> = self
+            .service_items
+            .items
+            .iter()
+            .filter(|i| i.marked_for_refresh)
+            .filter_map(|i| i.service.as_ref().ok())
+            .map(ServiceRef::from)
+            .collect();
+        marked_services.extend(self.pending_restore.marked.iter().cloned());
+        marked_services.sort_by(|a, b| (&a.cluster_arn, &a.name).cmp(&(&b.cluster_arn, &b.name)));
+
+        let selected_service = match self.get_selected_service() {
+            Some((Ok(service), _)) => Some(ServiceRef::from(service)),
+            _ => self.pending_restore.selected.clone(),
+        };
+
+        SessionState {
+            auto_refresh: self.auto_refresh,
+            sort: self.service_items.sort,
+            filter: self.session_filter.clone(),
+            selected_service,
+            marked_services,
+        }
+    }
+
+    pub(super) fn apply_filter_input(&mut self) {
+        let Some(input) = self.filter_input.take() else {
+            return;
+        };
+
+        if input.trim().is_empty() {
+            self.service_items.set_filter(None);
+            self.session_filter = None;
+            return;
+        }
+
+        match FilterExpr::parse_with_named(&input, &self.named_filters)
+            .and_then(FilterExpr::supported_by::<ServiceDetails>)
+        {
+            Ok(filter) => {
+                self.session_filter = Some(filter.to_string());
+                self.service_items.set_filter(Some(filter));
+            }
+            Err(e) => {
+                self.user_message = Some(UserMessage::error(&format!("invalid filter: {e}")));
+                // keep the prompt open so the filter can be fixed
+                self.filter_input = Some(input);
+            }
+        }
+    }
+
+    pub fn get_selected_container(&self) -> Option<&Container> {
+        self.task_items.as_ref()?;
+        self.container_items
+            .items
+            .get(self.container_items.state.selected()?)
+            .map(|ci| &ci.container)
+    }
+
     pub fn get_selected_task(&self) -> Option<&Task> {
         match &self.task_items {
             Some(i) => match i.state.selected() {
@@ -0,0 +1,148 @@
+use crate::domain::{ObjectNode, ObjectTree};
+use ratatui::widgets::ListState;
+use std::collections::HashSet;
+
+/// A node of an object's tree, as shown in a details pane.
+pub struct ObjectRow<'a> {
+    pub depth: usize,
+    pub node: &'a ObjectNode,
+    pub expanded: bool,
+}
+
+/// The state of a details pane showing a full object; nodes start off collapsed.
+#[derive(Debug, Default)]
+pub struct ObjectView {
+    pub state: ListState,
+    /// Paths of the nodes that have been expanded
+    pub expanded: HashSet<String>,
+}
+
+impl ObjectView {
+    /// Returns the rows currently shown, along with their paths.
+    pub fn rows<'a>(&self, tree: &'a ObjectTree) -> Vec<(String, ObjectRow<'a>)> {
+        let mut rows = Vec::new();
+        self.push_rows(&tree.nodes, "", 0, &mut rows);
+
+        rows
+    }
+
+    fn push_rows<'a>(
+        &self,
+        nodes: &'a [ObjectNode],
+        parent: &str,
+        depth: usize,
+        rows: &mut Vec<(String, ObjectRow<'a>)>,
+    ) {
+        for node in nodes {
+            let path = format!("{parent}/{}", node.name);
+            let expanded = self.expanded.contains(&path);
+            rows.push((
+                path.clone(),
+                ObjectRow {
+                    depth,
+                    node,
+                    expanded,
+                },
+            ));
+            if expanded {
+                self.push_rows(&node.children, &path, depth + 1, rows);
+            }
+        }
+    }
+
+    /// Expands the selected node if it's collapsed, and vice versa.
+    pub fn toggle_selected(&mut self, tree: &ObjectTree) {
+        let rows = self.rows(tree);
+        let Some(selected) = self
+            .state
+            .selected()
+            .map(|s| s.min(rows.len().saturating_sub(1)))
+        else {
+            return;
+        };
+        let Some((path, row)) = rows.get(selected) else {
+            return;
+        };
+        if row.node.children.is_empty() {
+            return;
+        }
+
+        let path = path.clone();
+        if !self.expanded.remove(&path) {
+            self.expanded.insert(path);
+        }
+    }
+
+    pub fn select_next(&mut self, num_rows: usize) {
+        let next = self.state.selected().map_or(0, |s| s + 1);
+        self.state
+            .select((num_rows > 0).then(|| next.min(num_rows - 1)));
+    }
+
+    pub fn select_previous(&mut self) {
+        let previous = self.state.selected().map_or(0, |s| s.saturating_sub(1));
+        self.state.select(Some(previous));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, children: Vec<ObjectNode>) -> ObjectNode {
+        ObjectNode {
+            name: name.to_string(),
+            value: children.is_empty().then(|| "value".to_string()),
+            children,
+        }
+    }
+
+    fn names(view: &ObjectView, tree: &ObjectTree) -> Vec<String> {
+        view.rows(tree)
+            .iter()
+            .map(|(_, r)| format!("{}{}", "  ".repeat(r.depth), r.node.name))
+            .collect()
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn toggling_nodes_expands_and_collapses_them() {
+        // GIVEN
+        let tree = ObjectTree {
+            nodes: vec![
+                node("cpu", vec![]),
+                node(
+                    "containers",
+                    vec![node("[0] Container", vec![node("name", vec![])])],
+                ),
+                node("memory", vec![]),
+            ],
+        };
+        let mut view = ObjectView::default();
+
+        // WHEN
+        view.select_next(3);
+        view.select_next(3);
+        view.toggle_selected(&tree);
+        let expanded_once = names(&view, &tree);
+        view.select_next(4);
+        view.toggle_selected(&tree);
+        let expanded_twice = names(&view, &tree);
+        view.select_previous();
+        view.toggle_selected(&tree);
+
+        // THEN
+        assert_eq!(
+            expanded_once,
+            vec!["cpu", "containers", "  [0] Container", "memory"]
+        );
+        assert_eq!(
+            expanded_twice,
+            vec!["cpu", "containers", "  [0] Container", "    name", "memory"]
+        );
+        assert_eq!(names(&view, &tree), vec!["cpu", "containers", "memory"]);
+    }
+}
@@ -0,0 +1,295 @@
+use super::services::{ServiceHealth, ServiceItem, ServiceItems};
+use std::collections::VecDeque;
+
+/// How many state transitions are held on to for the overview.
+const MAX_TRANSITIONS: usize = 100;
+
+/// How the services in a cluster key are doing.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeyHealth {
+    pub key: String,
+    pub healthy: usize,
+    pub in_progress: usize,
+    pub failing: usize,
+    pub errored: usize,
+}
+
+/// A change in a service's health, seen while the TUI was running.
+#[derive(Debug)]
+pub struct Transition {
+    /// Seconds since the epoch
+    pub at: i64,
+    /// The service, as it was after the change
+    pub item: ServiceItem,
+    pub from: ServiceHealth,
+    pub to: ServiceHealth,
+}
+
+/// Something in the overview that can be selected, and drilled into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverviewEntry {
+    Key(String),
+    /// A service with a deployment in progress; holds its index into `ServiceItems::items`
+    Deploying(usize),
+    /// An index into `Transitions::items`
+    Transition(usize),
+}
+
+/// A fleet-wide summary of the services passing the filter (and the search); its entries are
+/// the cluster keys, followed by the services being deployed, followed by the transitions.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Overview {
+    pub keys: Vec<KeyHealth>,
+    /// Indices (into `ServiceItems::items`) of the services being deployed
+    pub deploying: Vec<usize>,
+    pub num_transitions: usize,
+}
+
+impl Overview {
+    pub fn new(service_items: &ServiceItems, num_transitions: usize) -> Self {
+        let mut keys: Vec<KeyHealth> = service_items
+            .key_order
+            .iter()
+            .map(|key| KeyHealth {
+                key: key.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut deploying = Vec::new();
+
+        for index in &service_items.visible {
+            let Some(item) = service_items.items.get(*index) else {
+                continue;
+            };
+            let health = item.health();
+
+            // a service in several cluster keys is counted in the first one, which is the group
+            // it's shown in when the services list is grouped by key
+            if let Some(key) = item.cluster_keys().first() {
+                let position = match keys.iter().position(|k| &k.key == key) {
+                    Some(i) => i,
+                    None => {
+                        keys.push(KeyHealth {
+                            key: key.clone(),
+                            ..Default::default()
+                        });
+                        keys.len() - 1
+                    }
+                };
+                let key_health = &mut keys[position];
+                match health {
+                    ServiceHealth::Healthy => key_health.healthy += 1,
+                    ServiceHealth::InProgress => key_health.in_progress += 1,
+                    ServiceHealth::Failing => key_health.failing += 1,
+                    ServiceHealth::Errored => key_health.errored += 1,
+                }
+            }
+
+            if health == ServiceHealth::InProgress {
+                deploying.push(*index);
+            }
+        }
+
+        // keys without any (visible) services have no group to open
+        keys.retain(|k| k.healthy + k.in_progress + k.failing + k.errored > 0);
+
+        Self {
+            keys,
+            deploying,
+            num_transitions,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len() + self.deploying.len() + self.num_transitions
+    }
+
+    pub fn entry(&self, index: usize) -> Option<OverviewEntry> {
+        if let Some(key_health) = self.keys.get(index) {
+            return Some(OverviewEntry::Key(key_health.key.clone()));
+        }
+
+        let index = index - self.keys.len();
+        if let Some(item_index) = self.deploying.get(index) {
+            return Some(OverviewEntry::Deploying(*item_index));
+        }
+
+        let index = index - self.deploying.len();
+        (index < self.num_transitions).then_some(OverviewEntry::Transition(index))
+    }
+}
+
+/// The transitions seen during the session, most recent first.
+#[derive(Debug, Default)]
+pub struct Transitions {
+    pub items: VecDeque<Transition>,
+}
+
+impl Transitions {
+    /// Records a transition, dropping the oldest one if there are too many.
+    pub fn record(&mut self, transition: Transition) {
+        self.items.push_front(transition);
+        self.items.truncate(MAX_TRANSITIONS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClientKey, ConfigSource};
+    use crate::domain::*;
+    use crate::tui::model::services::ServicesLayout;
+
+    fn service(name: &str, keys: &[&str], running: i32) -> ServiceResult {
+        Ok(ServiceDetails {
+            name: name.to_string(),
+            status: "ACTIVE".to_string(),
+            desired_count: 2,
+            running_count: running,
+            pending_count: 0,
+            failed_count: 0,
+            cluster_keys: keys.iter().map(|k| k.to_string()).collect(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/cluster".to_string(),
+            client_key: ClientKey {
+                config_source: ConfigSource::Env,
+                region: None,
+            },
+            metadata: Default::default(),
+            last_deployed_at: None,
+            object: Default::default(),
+        })
+    }
+
+    fn key_health(key: &str, counts: [usize; 4]) -> KeyHealth {
+        let [healthy, in_progress, failing, errored] = counts;
+        KeyHealth {
+            key: key.to_string(),
+            healthy,
+            in_progress,
+            failing,
+            errored,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn overview_counts_services_in_the_key_they_are_grouped_under() {
+        // GIVEN
+        let items = ServiceItems::from(&vec![
+            service("auth-api", &["qa", "prod"], 2),
+            service("payments-api", &["prod"], 1),
+            service("search-api", &["prod"], 0),
+            Err(ServiceError {
+                service_name: "billing-api".to_string(),
+                error: "access denied".to_string(),
+                cluster_keys: vec!["qa".to_string()],
+            }),
+        ])
+        .with_key_order(vec![
+            "qa".to_string(),
+            "staging".to_string(),
+            "prod".to_string(),
+        ]);
+
+        // WHEN
+        let overview = Overview::new(&items, 0);
+
+        // THEN
+        assert_eq!(
+            overview.keys,
+            vec![
+                key_health("qa", [1, 0, 0, 1]),
+                key_health("prod", [0, 1, 1, 0]),
+            ]
+        );
+        assert_eq!(
+            overview
+                .deploying
+                .iter()
+                .map(|i| items.items[*i].name())
+                .collect::<Vec<_>>(),
+            vec!["payments-api"]
+        );
+    }
+
+    #[test]
+    fn every_key_in_the_overview_has_a_group_when_grouping_by_key() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", &["qa", "prod"], 2),
+            service("payments-api", &["eu", "prod"], 1),
+        ])
+        .with_key_order(vec![
+            "qa".to_string(),
+            "staging".to_string(),
+            "prod".to_string(),
+            "eu".to_string(),
+        ]);
+        items.set_layout(ServicesLayout::ByKey);
+
+        // WHEN
+        let overview = Overview::new(&items, 0);
+
+        // THEN
+        let keys = overview
+            .keys
+            .iter()
+            .map(|k| k.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["qa", "eu"]);
+        for key in keys {
+            assert!(items.select_group(key), "{key} should've had a group");
+        }
+    }
+
+    #[test]
+    fn overview_entries_go_keys_then_deployments_then_transitions() {
+        // GIVEN
+        let items = ServiceItems::from(&vec![
+            service("auth-api", &["prod"], 2),
+            service("payments-api", &["prod"], 1),
+        ]);
+
+        // WHEN
+        let overview = Overview::new(&items, 2);
+
+        // THEN
+        assert_eq!(overview.len(), 4);
+        assert_eq!(
+            (0..5).map(|i| overview.entry(i)).collect::<Vec<_>>(),
+            vec![
+                Some(OverviewEntry::Key("prod".to_string())),
+                Some(OverviewEntry::Deploying(1)),
+                Some(OverviewEntry::Transition(0)),
+                Some(OverviewEntry::Transition(1)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn only_the_most_recent_transitions_are_kept() {
+        // GIVEN
+        let mut transitions = Transitions::default();
+
+        // WHEN
+        for at in 0..(MAX_TRANSITIONS as i64 + 5) {
+            transitions.record(Transition {
+                at,
+                item: ServiceItem::new(service("auth-api", &["prod"], 2)),
+                from: ServiceHealth::InProgress,
+                to: ServiceHealth::Healthy,
+            });
+        }
+
+        // THEN
+        assert_eq!(transitions.items.len(), MAX_TRANSITIONS);
+        assert_eq!(
+            transitions.items.front().map(|t| t.at),
+            Some(MAX_TRANSITIONS as i64 + 4)
+        );
+    }
+}
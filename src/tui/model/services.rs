@@ -1,8 +1,155 @@
+use super::super::common::REDACTED;
+use super::super::theme::Theme;
 use crate::domain::*;
 use ratatui::{
-    text::Line,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
     widgets::{ListItem, ListState},
 };
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
+
+const UNGROUPED: &str = "-";
+
+/// How the services list lays out its services.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ServicesLayout {
+    #[default]
+    Flat,
+    /// Services grouped under their (first) cluster key
+    ByKey,
+    /// Services grouped under the cluster they run in
+    ByCluster,
+    /// One row per service, with its state in every cluster key it's in
+    Pivot,
+}
+
+impl ServicesLayout {
+    pub fn next(self) -> Self {
+        match self {
+            ServicesLayout::Flat => ServicesLayout::ByKey,
+            ServicesLayout::ByKey => ServicesLayout::ByCluster,
+            ServicesLayout::ByCluster => ServicesLayout::Pivot,
+            ServicesLayout::Pivot => ServicesLayout::Flat,
+        }
+    }
+}
+
+impl std::fmt::Display for ServicesLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServicesLayout::Flat => write!(f, "flat"),
+            ServicesLayout::ByKey => write!(f, "by key"),
+            ServicesLayout::ByCluster => write!(f, "by cluster"),
+            ServicesLayout::Pivot => write!(f, "across keys"),
+        }
+    }
+}
+
+/// The order the services list keeps its services in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServicesSort {
+    #[default]
+    Name,
+    /// Errors and failing services first, then the ones with a deployment in progress
+    Unhealthy,
+    /// In the order the cluster keys appear in the profile
+    Key,
+    /// Most recently deployed first
+    LastDeployed,
+    /// Most running tasks first
+    Tasks,
+}
+
+impl ServicesSort {
+    pub fn next(self) -> Self {
+        match self {
+            ServicesSort::Name => ServicesSort::Unhealthy,
+            ServicesSort::Unhealthy => ServicesSort::Key,
+            ServicesSort::Key => ServicesSort::LastDeployed,
+            ServicesSort::LastDeployed => ServicesSort::Tasks,
+            ServicesSort::Tasks => ServicesSort::Name,
+        }
+    }
+}
+
+impl std::fmt::Display for ServicesSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServicesSort::Name => write!(f, "name"),
+            ServicesSort::Unhealthy => write!(f, "unhealthy first"),
+            ServicesSort::Key => write!(f, "cluster key"),
+            ServicesSort::LastDeployed => write!(f, "last deployed"),
+            ServicesSort::Tasks => write!(f, "task count"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ServiceHealth {
+    Failing,
+    /// The service's details couldn't be fetched
+    Errored,
+    InProgress,
+    Healthy,
+}
+
+impl ServiceHealth {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ServiceHealth::Healthy => "✓",
+            ServiceHealth::InProgress => "~",
+            ServiceHealth::Failing => "✗",
+            ServiceHealth::Errored => "!",
+        }
+    }
+}
+
+impl std::fmt::Display for ServiceHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceHealth::Healthy => write!(f, "healthy"),
+            ServiceHealth::InProgress => write!(f, "in progress"),
+            ServiceHealth::Failing => write!(f, "failing"),
+            ServiceHealth::Errored => write!(f, "errored"),
+        }
+    }
+}
+
+/// A row in the services list; `ServiceItems::state` indexes into these.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ServiceRow {
+    /// A single service, along with the positions of the characters matched by the search
+    Service {
+        index: usize,
+        highlights: Vec<usize>,
+    },
+    /// The header of a group of services
+    Group {
+        name: String,
+        members: Vec<usize>,
+        collapsed: bool,
+    },
+    /// A service across all the cluster keys it's in (ordered as per the profile)
+    Pivot {
+        members: Vec<usize>,
+        highlights: Vec<usize>,
+    },
+}
+
+impl ServiceRow {
+    fn contains(&self, item_index: usize) -> bool {
+        match self {
+            ServiceRow::Service { index, .. } => *index == item_index,
+            ServiceRow::Group {
+                members, collapsed, ..
+            } => *collapsed && members.contains(&item_index),
+            ServiceRow::Pivot { members, .. } => members.contains(&item_index),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ServiceItem {
@@ -17,11 +164,180 @@ impl ServiceItem {
             marked_for_refresh: false,
         }
     }
+
+    pub fn name(&self) -> &str {
+        match &self.service {
+            Ok(s) => s.display_name(),
+            Err(e) => &e.service_name,
+        }
+    }
+
+    pub fn cluster_keys(&self) -> &[String] {
+        match &self.service {
+            Ok(s) => &s.cluster_keys,
+            Err(e) => &e.cluster_keys,
+        }
+    }
+
+    /// Whether both items refer to the same service, regardless of their current state.
+    fn is_same_service(&self, other: &ServiceItem) -> bool {
+        match (&self.service, &other.service) {
+            (Ok(a), Ok(b)) => a.name == b.name && a.cluster_arn == b.cluster_arn,
+            (Err(a), Err(b)) => {
+                a.service_name == b.service_name && a.cluster_keys == b.cluster_keys
+            }
+            (Ok(a), Err(b)) | (Err(b), Ok(a)) => {
+                a.name == b.service_name && a.cluster_keys == b.cluster_keys
+            }
+        }
+    }
+
+    pub fn health(&self) -> ServiceHealth {
+        match &self.service {
+            Ok(s) if s.desired_count > 0 && s.running_count == 0 && s.pending_count == 0 => {
+                ServiceHealth::Failing
+            }
+            Ok(s) if s.desired_count != s.running_count || s.pending_count != 0 => {
+                ServiceHealth::InProgress
+            }
+            Ok(_) => ServiceHealth::Healthy,
+            Err(_) => ServiceHealth::Errored,
+        }
+    }
+
+    fn group_name(&self, layout: ServicesLayout) -> &str {
+        match (layout, &self.service) {
+            (ServicesLayout::ByCluster, Ok(s)) => {
+                s.cluster_arn.rsplit('/').next().unwrap_or(&s.cluster_arn)
+            }
+            // errors don't carry the cluster's ARN
+            (ServicesLayout::ByCluster, Err(_)) => UNGROUPED,
+            _ => self
+                .cluster_keys()
+                .first()
+                .map(|k| k.as_str())
+                .unwrap_or(UNGROUPED),
+        }
+    }
+
+    fn compare(&self, other: &ServiceItem, sort: ServicesSort, key_order: &[String]) -> Ordering {
+        let by_name = || match (&self.service, &other.service) {
+            (Ok(a), Ok(b)) => a.name.cmp(&b.name),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(a), Err(b)) => a.service_name.cmp(&b.service_name),
+        };
+
+        match sort {
+            ServicesSort::Name => by_name(),
+            ServicesSort::Unhealthy => self.health().cmp(&other.health()).then_with(by_name),
+            ServicesSort::Key => {
+                let position = |item: &ServiceItem| {
+                    let key = item.group_name(ServicesLayout::ByKey);
+                    (
+                        key_order
+                            .iter()
+                            .position(|k| k == key)
+                            .unwrap_or(key_order.len()),
+                        key.to_string(),
+                    )
+                };
+                position(self).cmp(&position(other)).then_with(by_name)
+            }
+            ServicesSort::LastDeployed => {
+                let deployed_at = |item: &ServiceItem| {
+                    Reverse(item.service.as_ref().ok().and_then(|s| s.last_deployed_at))
+                };
+                deployed_at(self)
+                    .cmp(&deployed_at(other))
+                    .then_with(by_name)
+            }
+            ServicesSort::Tasks => {
+                let running = |item: &ServiceItem| {
+                    Reverse(item.service.as_ref().ok().map(|s| s.running_count))
+                };
+                running(self).cmp(&running(other)).then_with(by_name)
+            }
+        }
+    }
+
+    /// Returns a list item for the service, highlighting the characters of its name at the
+    /// given (char) positions.
+    pub fn list_item(&self, highlights: &[usize], match_color: Color) -> ListItem<'static> {
+        ListItem::new(Line::from(self.spans(highlights, match_color)))
+    }
+
+    fn spans(&self, highlights: &[usize], match_color: Color) -> Vec<Span<'static>> {
+        let identifier = self
+            .cluster_keys()
+            .first()
+            .map(|k| k.get(..5).unwrap_or(k.as_str()))
+            .unwrap_or_default();
+
+        let prefix = match &self.service {
+            Ok(service_details) => {
+                let refresh_marker = match self.marked_for_refresh {
+                    true => "* ",
+                    false => "  ",
+                };
+                let pending_marker = if (service_details.desired_count
+                    != service_details.running_count)
+                    || service_details.pending_count != 0
+                {
+                    "~ "
+                } else {
+                    "  "
+                };
+
+                format!("{identifier:6}{refresh_marker}{pending_marker}")
+            }
+            Err(_) => format!("{identifier:6}  x "),
+        };
+
+        let mut spans = vec![Span::from(prefix)];
+        spans.extend(name_spans(self.name(), highlights, match_color));
+
+        spans
+    }
+}
+
+fn name_spans(name: &str, highlights: &[usize], match_color: Color) -> Vec<Span<'static>> {
+    if highlights.is_empty() {
+        return vec![Span::from(name.to_string())];
+    }
+
+    let match_style = Style::new().fg(match_color).bold();
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if highlights.contains(&i) {
+                Span::styled(c.to_string(), match_style)
+            } else {
+                Span::from(c.to_string())
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug)]
 pub struct ServiceItems {
     pub items: Vec<ServiceItem>,
+    /// Indices (into `items`) of the items that pass the filter and the search; `state` indexes
+    /// into this
+    pub visible: Vec<usize>,
+    /// Positions of the characters matched by the search, for each visible item
+    pub highlights: Vec<Vec<usize>>,
+    pub filter: Option<FilterExpr>,
+    pub search: Option<String>,
+    pub sort: ServicesSort,
+    pub layout: ServicesLayout,
+    /// The rows shown in the list, as per the layout; built from the visible items
+    pub rows: Vec<ServiceRow>,
+    /// Names of the groups that are collapsed
+    pub collapsed: HashSet<String>,
+    /// Cluster keys in the order they appear in the profile; used for ordering groups and pivot
+    /// columns
+    pub key_order: Vec<String>,
     pub state: ListState,
 }
 
@@ -32,20 +348,35 @@ impl From<&Vec<ServiceResult>> for ServiceItems {
             .map(|service| ServiceItem::new(service.clone()))
             .collect();
 
-        items.sort_by(|a, b| match &a.service {
-            Ok(sa) => match &b.service {
-                Ok(sb) => sa.name.cmp(&sb.name),
-                Err(_) => std::cmp::Ordering::Less,
-            },
-            Err(_) => std::cmp::Ordering::Greater,
-        });
+        items.sort_by(|a, b| a.compare(b, ServicesSort::default(), &[]));
 
         let mut state = ListState::default();
         if !services.is_empty() {
             state.select(Some(0))
         }
 
-        Self { items, state }
+        let visible = (0..items.len()).collect();
+        let highlights = vec![Vec::new(); items.len()];
+        let rows = (0..items.len())
+            .map(|index| ServiceRow::Service {
+                index,
+                highlights: Vec::new(),
+            })
+            .collect();
+
+        Self {
+            items,
+            visible,
+            highlights,
+            filter: None,
+            search: None,
+            sort: ServicesSort::default(),
+            layout: ServicesLayout::default(),
+            rows,
+            collapsed: HashSet::new(),
+            key_order: Vec::new(),
+            state,
+        }
     }
 }
 
@@ -57,81 +388,859 @@ impl ServiceItems {
             .collect();
 
         self.items.append(&mut new_items);
+        self.resort();
+    }
 
-        self.items.sort_by(|a, b| match &a.service {
-            Ok(sa) => match &b.service {
-                Ok(sb) => sa.name.cmp(&sb.name),
-                Err(_) => std::cmp::Ordering::Less,
-            },
-            Err(_) => std::cmp::Ordering::Greater,
-        });
-
-        let selected = match self.state.selected() {
-            Some(i) => Some(i),
-            None => {
-                if self.items.is_empty() {
-                    None
-                } else {
-                    Some(0)
+    /// Sorts the items as per the current sort; sorting moves items around, so the selection is
+    /// restored by service rather than by index.
+    pub fn resort(&mut self) {
+        let selected = self
+            .selected_index()
+            .and_then(|i| self.items.get(i))
+            .map(|i| ServiceItem::new(i.service.clone()));
+
+        let (sort, key_order) = (self.sort, &self.key_order);
+        self.items.sort_by(|a, b| a.compare(b, sort, key_order));
+
+        let keep_selected =
+            selected.and_then(|s| self.items.iter().position(|i| i.is_same_service(&s)));
+        self.update_visible(keep_selected);
+    }
+
+    pub fn set_sort(&mut self, sort: ServicesSort) {
+        self.sort = sort;
+        self.resort();
+    }
+
+    /// Returns the index (into `items`) of the given item's service, if it's still there.
+    pub fn position_of_item(&self, item: &ServiceItem) -> Option<usize> {
+        self.items.iter().position(|i| i.is_same_service(item))
+    }
+
+    /// Selects the row showing the given item (an index into `items`), clearing the search and
+    /// expanding its group if need be; returns false if the filter hides it.
+    pub fn select_item(&mut self, index: usize) -> bool {
+        let Some(item) = self.items.get(index) else {
+            return false;
+        };
+
+        if matches!(
+            self.layout,
+            ServicesLayout::ByKey | ServicesLayout::ByCluster
+        ) {
+            let group = item.group_name(self.layout).to_string();
+            self.collapsed.remove(&group);
+        }
+        if !self.visible.contains(&index) {
+            self.search = None;
+        }
+        self.update_visible(Some(index));
+
+        self.selected_members().contains(&index)
+    }
+
+    /// Selects the header of the given group; returns false if there's no such group.
+    pub fn select_group(&mut self, name: &str) -> bool {
+        let position = self
+            .rows
+            .iter()
+            .position(|r| matches!(r, ServiceRow::Group { name: n, .. } if n == name));
+
+        if position.is_some() {
+            self.state.select(position);
+        }
+
+        position.is_some()
+    }
+
+    /// Returns the index (into `items`) of the selected item; for a pivot row, that's the
+    /// service in the first cluster key. Group headers don't have one.
+    pub fn selected_index(&self) -> Option<usize> {
+        match self.selected_row()? {
+            ServiceRow::Service { index, .. } => Some(*index),
+            ServiceRow::Pivot { members, .. } => members.first().copied(),
+            ServiceRow::Group { .. } => None,
+        }
+    }
+
+    pub fn selected_row(&self) -> Option<&ServiceRow> {
+        self.state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    /// Returns the indices (into `items`) of all the services the selected row stands for.
+    pub fn selected_members(&self) -> Vec<usize> {
+        match self.selected_row() {
+            Some(ServiceRow::Service { index, .. }) => vec![*index],
+            Some(ServiceRow::Group { members, .. }) | Some(ServiceRow::Pivot { members, .. }) => {
+                members.clone()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn selected_group(&self) -> Option<&str> {
+        match self.selected_row()? {
+            ServiceRow::Group { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn set_layout(&mut self, layout: ServicesLayout) {
+        let selected = self.selected_index();
+        self.layout = layout;
+        self.update_visible(selected);
+    }
+
+    /// Collapses the selected group if it's expanded, and vice versa; does nothing if a group
+    /// header isn't selected.
+    pub fn toggle_selected_group(&mut self) {
+        let Some(name) = self.selected_group().map(|n| n.to_string()) else {
+            return;
+        };
+
+        if !self.collapsed.remove(&name) {
+            self.collapsed.insert(name);
+        }
+        self.update_visible(None);
+    }
+
+    /// Cluster names in group headers are left out when `redact` is set.
+    pub fn list_items(&self, theme: &Theme, redact: bool) -> Vec<ListItem<'static>> {
+        let pivot_name_width = self
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                ServiceRow::Pivot { members, .. } => members.first(),
+                _ => None,
+            })
+            .filter_map(|i| self.items.get(*i))
+            .map(|item| item.name().chars().count())
+            .max()
+            .unwrap_or_default();
+
+        self.rows
+            .iter()
+            .map(|row| match row {
+                ServiceRow::Service { index, highlights } => {
+                    match (self.layout, self.items.get(*index)) {
+                        (ServicesLayout::ByKey | ServicesLayout::ByCluster, Some(item)) => {
+                            let mut spans = vec![Span::from("  ")];
+                            spans.extend(item.spans(highlights, theme.search_match));
+                            ListItem::new(Line::from(spans))
+                        }
+                        (_, Some(item)) => item.list_item(highlights, theme.search_match),
+                        (_, None) => ListItem::new(""),
+                    }
+                }
+                ServiceRow::Group {
+                    name,
+                    members,
+                    collapsed,
+                } => {
+                    let name = if redact && self.layout == ServicesLayout::ByCluster {
+                        REDACTED
+                    } else {
+                        name
+                    };
+                    self.group_list_item(name, members, *collapsed)
                 }
+                ServiceRow::Pivot {
+                    members,
+                    highlights,
+                } => self.pivot_list_item(members, highlights, pivot_name_width, theme),
+            })
+            .collect()
+    }
+
+    fn group_list_item(&self, name: &str, members: &[usize], collapsed: bool) -> ListItem<'static> {
+        let marker = if collapsed { "▸" } else { "▾" };
+        let (mut healthy, mut in_progress, mut failing) = (0, 0, 0);
+        for item in members.iter().filter_map(|i| self.items.get(*i)) {
+            match item.health() {
+                ServiceHealth::Healthy => healthy += 1,
+                ServiceHealth::InProgress => in_progress += 1,
+                ServiceHealth::Failing | ServiceHealth::Errored => failing += 1,
             }
+        }
+
+        ListItem::new(Line::from(vec![
+            Span::from(format!("{marker} {name} ({})", members.len())).bold(),
+            Span::from(format!("  ✓{healthy} ~{in_progress} ✗{failing}")),
+        ]))
+    }
+
+    fn pivot_list_item(
+        &self,
+        members: &[usize],
+        highlights: &[usize],
+        name_width: usize,
+        theme: &Theme,
+    ) -> ListItem<'static> {
+        let Some(name) = members
+            .first()
+            .and_then(|i| self.items.get(*i))
+            .map(|i| i.name())
+        else {
+            return ListItem::new("");
+        };
+
+        let padding = name_width.saturating_sub(name.chars().count());
+        let mut spans = name_spans(name, highlights, theme.search_match);
+        spans.push(Span::from(" ".repeat(padding)));
+
+        for item in members.iter().filter_map(|i| self.items.get(*i)) {
+            let key = item.group_name(ServicesLayout::ByKey);
+            let marker = if item.marked_for_refresh { "*" } else { "" };
+            spans.push(Span::from(format!(
+                "  {key} {}{marker}",
+                item.health().symbol()
+            )));
+        }
+
+        ListItem::new(Line::from(spans))
+    }
+
+    pub fn set_filter(&mut self, filter: Option<FilterExpr>) {
+        let selected = self.selected_index();
+        self.filter = filter;
+        self.update_visible(selected);
+    }
+
+    /// Narrows down the visible items to the ones fuzzy matching the query; every
+    /// whitespace-separated term of the query needs to match either the service's name, or one
+    /// of its cluster keys.
+    pub fn set_search(&mut self, search: Option<String>) {
+        let selected = self.selected_index();
+        self.search = search.filter(|s| !s.trim().is_empty());
+        self.update_visible(selected);
+    }
+
+    /// Recomputes which items pass the filter and the search, keeping `keep_selected` (an index
+    /// into `items`) selected if it's still visible. Errors are always shown, unless they don't
+    /// match the search.
+    pub fn update_visible(&mut self, keep_selected: Option<usize>) {
+        let mut visible = Vec::new();
+        let mut highlights = Vec::new();
+
+        for (i, item) in self.items.iter().enumerate() {
+            if let (Some(f), Ok(service)) = (&self.filter, &item.service)
+                && !f.matches(service)
+            {
+                continue;
+            }
+
+            let item_highlights = match &self.search {
+                Some(query) => match search_match(query, item.name(), item.cluster_keys()) {
+                    Some(h) => h,
+                    None => continue,
+                },
+                None => Vec::new(),
+            };
+
+            visible.push(i);
+            highlights.push(item_highlights);
+        }
+
+        self.visible = visible;
+        self.highlights = highlights;
+
+        // group names are stable across rebuilds, unlike row indices
+        let selected_group = self.selected_group().map(|n| n.to_string());
+        self.rows = self.build_rows();
+
+        let selected = if self.rows.is_empty() {
+            None
+        } else {
+            keep_selected
+                .and_then(|s| self.rows.iter().position(|r| r.contains(s)))
+                .or_else(|| {
+                    selected_group.and_then(|g| {
+                        self.rows
+                            .iter()
+                            .position(|r| matches!(r, ServiceRow::Group { name, .. } if *name == g))
+                    })
+                })
+                .or_else(|| self.state.selected().map(|s| s.min(self.rows.len() - 1)))
+                .or(Some(0))
         };
 
         self.state = self.state.with_selected(selected);
     }
+
+    fn build_rows(&self) -> Vec<ServiceRow> {
+        let visible = self.visible.iter().copied().zip(self.highlights.iter());
+
+        match self.layout {
+            ServicesLayout::Flat => visible
+                .map(|(index, highlights)| ServiceRow::Service {
+                    index,
+                    highlights: highlights.clone(),
+                })
+                .collect(),
+            ServicesLayout::ByKey | ServicesLayout::ByCluster => {
+                let mut groups: Vec<(&str, Vec<ServiceRow>)> = Vec::new();
+                for (index, highlights) in visible {
+                    let Some(item) = self.items.get(index) else {
+                        continue;
+                    };
+                    let name = item.group_name(self.layout);
+                    let row = ServiceRow::Service {
+                        index,
+                        highlights: highlights.clone(),
+                    };
+                    match groups.iter_mut().find(|(n, _)| *n == name) {
+                        Some((_, members)) => members.push(row),
+                        None => groups.push((name, vec![row])),
+                    }
+                }
+
+                if self.layout == ServicesLayout::ByKey {
+                    groups.sort_by_key(|(name, _)| (self.key_position(name), name.to_string()));
+                } else {
+                    groups.sort_by_key(|(name, _)| (*name == UNGROUPED, name.to_string()));
+                }
+
+                let mut rows = Vec::new();
+                for (name, members) in groups {
+                    let collapsed = self.collapsed.contains(name);
+                    rows.push(ServiceRow::Group {
+                        name: name.to_string(),
+                        members: members
+                            .iter()
+                            .filter_map(|r| match r {
+                                ServiceRow::Service { index, .. } => Some(*index),
+                                _ => None,
+                            })
+                            .collect(),
+                        collapsed,
+                    });
+                    if !collapsed {
+                        rows.extend(members);
+                    }
+                }
+
+                rows
+            }
+            ServicesLayout::Pivot => {
+                let mut pivots: Vec<(&str, Vec<usize>, Vec<usize>)> = Vec::new();
+                for (index, highlights) in visible {
+                    let Some(item) = self.items.get(index) else {
+                        continue;
+                    };
+                    match pivots.iter_mut().find(|(n, _, _)| *n == item.name()) {
+                        Some((_, members, h)) => {
+                            members.push(index);
+                            h.extend(highlights);
+                        }
+                        None => pivots.push((item.name(), vec![index], highlights.clone())),
+                    }
+                }
+
+                pivots
+                    .into_iter()
+                    .map(|(_, mut members, mut highlights)| {
+                        members.sort_by_key(|i| {
+                            self.items.get(*i).map(|item| {
+                                self.key_position(item.group_name(ServicesLayout::ByKey))
+                            })
+                        });
+                        highlights.sort_unstable();
+                        highlights.dedup();
+                        ServiceRow::Pivot {
+                            members,
+                            highlights,
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn key_position(&self, key: &str) -> usize {
+        self.key_order
+            .iter()
+            .position(|k| k == key)
+            .unwrap_or(self.key_order.len())
+    }
 }
 
 impl ServiceItems {
     pub fn empty() -> Self {
         Self::from(&Vec::new())
     }
+
+    pub fn with_filter(mut self, filter: Option<FilterExpr>) -> Self {
+        self.set_filter(filter);
+        self
+    }
+
+    pub fn with_sort(mut self, sort: ServicesSort) -> Self {
+        self.set_sort(sort);
+        self
+    }
+
+    pub fn with_key_order(mut self, key_order: Vec<String>) -> Self {
+        self.key_order = key_order;
+        self
+    }
 }
 
-impl From<&ServiceItem> for ListItem<'_> {
-    fn from(value: &ServiceItem) -> Self {
-        let line = match &value.service {
-            Ok(service_details) => {
-                let refresh_marker = match value.marked_for_refresh {
-                    true => "* ",
-                    false => "  ",
-                };
-                let pending_marker = if (service_details.desired_count
-                    != service_details.running_count)
-                    || service_details.pending_count != 0
-                {
-                    "~ "
-                } else {
-                    "  "
-                };
+/// Returns the positions of the characters in `name` matched by the query, or `None` if the query
+/// doesn't match.
+fn search_match(query: &str, name: &str, cluster_keys: &[String]) -> Option<Vec<usize>> {
+    let mut highlights = Vec::new();
 
-                let identifier = if !service_details.cluster_keys.is_empty() {
-                    service_details.cluster_keys[0]
-                        .get(..5)
-                        .unwrap_or(service_details.cluster_keys[0].as_str())
-                } else {
-                    ""
-                };
+    for term in query.split_whitespace() {
+        match fuzzy_match(term, name) {
+            Some(positions) => highlights.extend(positions),
+            None if cluster_keys.iter().any(|k| fuzzy_match(term, k).is_some()) => {}
+            None => return None,
+        }
+    }
 
-                Line::from(format!(
-                    "{:6}{}{}{}",
-                    identifier, refresh_marker, pending_marker, service_details.name
-                ))
-            }
-            Err(service_error) => {
-                let identifier = if !service_error.cluster_keys.is_empty() {
-                    service_error.cluster_keys[0]
-                        .get(..5)
-                        .unwrap_or(service_error.cluster_keys[0].as_str())
-                } else {
-                    ""
-                };
+    highlights.sort_unstable();
+    highlights.dedup();
 
-                Line::from(format!(
-                    "{:6}  x {}",
-                    identifier, service_error.service_name
-                ))
-            }
+    Some(highlights)
+}
+
+/// Case insensitive subsequence match; returns the (char) positions in `haystack` that matched.
+fn fuzzy_match(needle: &str, haystack: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut haystack_chars = haystack.chars().enumerate();
+
+    for n in needle.chars() {
+        let (i, _) = haystack_chars.find(|(_, h)| h.to_lowercase().eq(n.to_lowercase()))?;
+        positions.push(i);
+    }
+
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClientKey, ConfigSource};
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::List, widgets::Widget};
+
+    fn service(name: &str, key: &str) -> ServiceResult {
+        Ok(ServiceDetails {
+            name: name.to_string(),
+            status: "ACTIVE".to_string(),
+            desired_count: 1,
+            running_count: 1,
+            pending_count: 0,
+            failed_count: 0,
+            cluster_keys: vec![key.to_string()],
+            cluster_arn: format!("arn:aws:ecs:eu-central-1:111111111111:cluster/{key}"),
+            client_key: ClientKey {
+                config_source: ConfigSource::Env,
+                region: None,
+            },
+            metadata: Default::default(),
+            last_deployed_at: None,
+            object: Default::default(),
+        })
+    }
+
+    fn service_deployed_at(name: &str, key: &str, deployed_at: i64) -> ServiceResult {
+        service(name, key).map(|s| ServiceDetails {
+            last_deployed_at: Some(deployed_at),
+            ..s
+        })
+    }
+
+    fn service_with_counts(name: &str, key: &str, running: i32) -> ServiceResult {
+        service(name, key).map(|s| ServiceDetails {
+            running_count: running,
+            desired_count: 2,
+            ..s
+        })
+    }
+
+    fn visible_names(items: &ServiceItems) -> Vec<&str> {
+        items
+            .visible
+            .iter()
+            .map(|i| items.items[*i].name())
+            .collect()
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn fuzzy_matching_works() {
+        // GIVEN
+        let cases = [
+            ("pay", "payments-api", Some(vec![0, 1, 2])),
+            ("PMA", "payments-api", Some(vec![0, 3, 9])),
+            ("api", "payments", None),
+            ("", "payments", Some(vec![])),
+        ];
+
+        for (query, text, expected) in cases {
+            // WHEN
+            let result = fuzzy_match(query, text);
+
+            // THEN
+            assert_eq!(result, expected, "query: {query}, text: {text}");
+        }
+    }
+
+    #[test]
+    fn searching_matches_service_names_and_cluster_keys() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("payments-api", "prod"),
+            service("payments-api", "qa"),
+            service("search-api", "prod"),
+        ]);
+
+        // WHEN
+        items.set_search(Some("pay prod".to_string()));
+
+        // THEN
+        assert_eq!(visible_names(&items), vec!["payments-api"]);
+        assert_eq!(items.highlights, vec![vec![0, 1, 2]]);
+        assert_eq!(
+            items
+                .selected_index()
+                .and_then(|i| items.items[i].cluster_keys().first().cloned()),
+            Some("prod".to_string())
+        );
+    }
+
+    #[test]
+    fn selection_stays_on_the_same_service_while_searching_and_refreshing() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("search-api", "prod"),
+        ]);
+        items.state.select(Some(2));
+
+        // WHEN
+        items.set_search(Some("api".to_string()));
+        items.append(&[service("accounts-api", "prod")]);
+
+        // THEN
+        assert_eq!(
+            items.selected_index().map(|i| items.items[i].name()),
+            Some("search-api")
+        );
+    }
+
+    #[test]
+    fn clearing_the_search_shows_all_services() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+        ]);
+        items.set_search(Some("auth".to_string()));
+
+        // WHEN
+        items.set_search(None);
+
+        // THEN
+        assert_eq!(visible_names(&items), vec!["auth-api", "payments-api"]);
+        assert_eq!(items.highlights, vec![Vec::<usize>::new(), Vec::new()]);
+    }
+
+    #[test]
+    fn grouping_by_key_follows_the_profile_order() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("auth-api", "qa"),
+            service("payments-api", "prod"),
+        ])
+        .with_key_order(vec!["qa".to_string(), "prod".to_string()]);
+
+        // WHEN
+        items.set_layout(ServicesLayout::ByKey);
+
+        // THEN
+        assert_eq!(
+            items.rows,
+            vec![
+                ServiceRow::Group {
+                    name: "qa".to_string(),
+                    members: vec![1],
+                    collapsed: false
+                },
+                ServiceRow::Service {
+                    index: 1,
+                    highlights: vec![]
+                },
+                ServiceRow::Group {
+                    name: "prod".to_string(),
+                    members: vec![0, 2],
+                    collapsed: false
+                },
+                ServiceRow::Service {
+                    index: 0,
+                    highlights: vec![]
+                },
+                ServiceRow::Service {
+                    index: 2,
+                    highlights: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cluster_names_in_group_headers_are_redacted_in_redact_mode() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("auth-api", "qa"),
+        ]);
+        items.set_layout(ServicesLayout::ByCluster);
+
+        // WHEN
+        let list = List::new(items.list_items(&Theme::default(), true));
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 40, 5));
+        Widget::render(list, buffer.area, &mut buffer);
+
+        // THEN
+        let lines = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(lines, @r"
+        ▾ <REDACTED> (2)  ✓2 ~0 ✗0
+          prod      auth-api
+          prod      payments-api
+        ▾ <REDACTED> (1)  ✓1 ~0 ✗0
+          qa        auth-api
+        ");
+    }
+
+    #[test]
+    fn collapsing_a_group_hides_its_services_and_keeps_the_header_selected() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("auth-api", "qa"),
+        ])
+        .with_key_order(vec!["qa".to_string(), "prod".to_string()]);
+        items.set_layout(ServicesLayout::ByKey);
+        items.state.select(Some(2));
+
+        // WHEN
+        items.toggle_selected_group();
+
+        // THEN
+        assert_eq!(items.rows.len(), 3);
+        assert_eq!(
+            items.selected_row(),
+            Some(&ServiceRow::Group {
+                name: "prod".to_string(),
+                members: vec![0, 2],
+                collapsed: true
+            })
+        );
+        assert_eq!(items.selected_index(), None);
+        assert_eq!(items.selected_members(), vec![0, 2]);
+    }
+
+    #[test]
+    fn selecting_an_item_clears_the_search_and_expands_its_group() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("auth-api", "qa"),
+        ])
+        .with_key_order(vec!["qa".to_string(), "prod".to_string()]);
+        items.set_layout(ServicesLayout::ByKey);
+        items.state.select(Some(2));
+        items.toggle_selected_group();
+        items.set_search(Some("qa".to_string()));
+
+        // WHEN
+        let found = items.select_item(2);
+
+        // THEN
+        assert!(found);
+        assert_eq!(items.search, None);
+        assert!(items.collapsed.is_empty());
+        assert_eq!(items.selected_index(), Some(2));
+    }
+
+    #[test]
+    fn selected_service_stays_selected_when_switching_layouts() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("payments-api", "qa"),
+        ]);
+        items.state.select(Some(2));
+
+        // WHEN
+        items.set_layout(ServicesLayout::ByKey);
+
+        // THEN
+        assert_eq!(items.state.selected(), Some(4));
+        assert_eq!(
+            items
+                .selected_index()
+                .and_then(|i| items.items[i].cluster_keys().first().cloned()),
+            Some("qa".to_string())
+        );
+    }
+
+    #[test]
+    fn pivoting_shows_a_service_across_keys_in_one_row() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service_with_counts("payments-api", "prod", 0),
+            service_with_counts("payments-api", "qa", 2),
+            service_with_counts("payments-api", "staging", 1),
+            service_with_counts("search-api", "prod", 2),
+        ])
+        .with_key_order(vec![
+            "qa".to_string(),
+            "staging".to_string(),
+            "prod".to_string(),
+        ]);
+
+        // WHEN
+        items.set_layout(ServicesLayout::Pivot);
+
+        // THEN
+        let health = |row: &ServiceRow| match row {
+            ServiceRow::Pivot { members, .. } => members
+                .iter()
+                .map(|i| {
+                    (
+                        items.items[*i].group_name(ServicesLayout::ByKey),
+                        items.items[*i].health(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
         };
-        ListItem::new(line)
+        assert_eq!(items.rows.len(), 2);
+        assert_eq!(
+            health(&items.rows[0]),
+            vec![
+                ("qa", ServiceHealth::Healthy),
+                ("staging", ServiceHealth::InProgress),
+                ("prod", ServiceHealth::Failing),
+            ]
+        );
+        assert_eq!(
+            health(&items.rows[1]),
+            vec![("prod", ServiceHealth::Healthy)]
+        );
+    }
+
+    #[test]
+    fn sorting_unhealthy_first_works() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service_with_counts("auth-api", "prod", 2),
+            service_with_counts("payments-api", "prod", 1),
+            service_with_counts("search-api", "prod", 0),
+            Err(ServiceError {
+                service_name: "billing-api".to_string(),
+                error: "access denied".to_string(),
+                cluster_keys: vec!["prod".to_string()],
+            }),
+        ]);
+
+        // WHEN
+        items.set_sort(ServicesSort::Unhealthy);
+
+        // THEN
+        assert_eq!(
+            visible_names(&items),
+            vec!["search-api", "billing-api", "payments-api", "auth-api"]
+        );
+    }
+
+    #[test]
+    fn sorting_by_last_deployment_puts_the_most_recent_first() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service_deployed_at("payments-api", "prod", 1_700_000_000),
+            service_deployed_at("search-api", "prod", 1_800_000_000),
+        ]);
+
+        // WHEN
+        items.set_sort(ServicesSort::LastDeployed);
+
+        // THEN
+        assert_eq!(
+            visible_names(&items),
+            vec!["search-api", "payments-api", "auth-api"]
+        );
+    }
+
+    #[test]
+    fn selection_stays_on_the_same_service_when_resorting() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service_with_counts("auth-api", "prod", 2),
+            service_with_counts("payments-api", "prod", 2),
+            service_with_counts("search-api", "prod", 2),
+        ]);
+        items.set_sort(ServicesSort::Unhealthy);
+        items.state.select(Some(1));
+
+        // WHEN
+        items.items[2] = ServiceItem::new(service_with_counts("search-api", "prod", 0));
+        items.resort();
+
+        // THEN
+        assert_eq!(
+            items.selected_index().map(|i| items.items[i].name()),
+            Some("payments-api")
+        );
+        assert_eq!(
+            visible_names(&items),
+            vec!["search-api", "auth-api", "payments-api"]
+        );
+    }
+
+    #[test]
+    fn services_are_found_whether_or_not_they_failed_to_refresh() {
+        // GIVEN
+        let mut items = ServiceItems::from(&vec![
+            service("auth-api", "prod"),
+            service("payments-api", "prod"),
+            service("payments-api", "qa"),
+        ]);
+        items.items[1] = ServiceItem::new(Err(ServiceError {
+            service_name: "payments-api".to_string(),
+            error: "throttled".to_string(),
+            cluster_keys: vec!["prod".to_string()],
+        }));
+
+        // WHEN
+        let failed = items.position_of_item(&ServiceItem::new(service("payments-api", "prod")));
+        let refreshed = items.position_of_item(&ServiceItem::new(service("payments-api", "qa")));
+        let removed = items.position_of_item(&ServiceItem::new(service("search-api", "prod")));
+
+        // THEN
+        assert_eq!(failed, Some(1));
+        assert_eq!(refreshed, Some(2));
+        assert_eq!(removed, None);
     }
 }
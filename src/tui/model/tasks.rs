@@ -1,4 +1,5 @@
 use super::super::common::*;
+use crate::domain::ObjectTree;
 use aws_sdk_ecs::types::Task;
 use ratatui::{
     text::Line,
@@ -15,11 +16,14 @@ pub struct TaskItems {
 pub struct TaskItem {
     pub task: Task,
     pub status: bool,
+    /// All of the task's fields
+    pub object: ObjectTree,
 }
 
 impl TaskItem {
     fn new(task: Task) -> Self {
         Self {
+            object: ObjectTree::new(&task),
             task,
             status: false,
         }
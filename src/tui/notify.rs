@@ -0,0 +1,240 @@
+use super::model::{ServiceHealth, ServiceItem};
+use crate::domain::ServiceResult;
+use serde::Deserialize;
+use std::io::Write;
+use std::process::Stdio;
+
+const NOTIFICATION_TITLE: &str = "ecscope";
+
+/// The escape sequence used to ask the terminal for a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EscapeSequence {
+    /// Supported by iTerm2, WezTerm, kitty, Windows Terminal, among others
+    Osc9,
+    /// Supported by urxvt, foot, and terminals based on VTE
+    Osc777,
+}
+
+/// How watched services changing state gets notified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationsConfig {
+    pub bell: bool,
+    pub escape_sequence: Option<EscapeSequence>,
+    /// Run via `sh -c`, with the notification in `ECSCOPE_TITLE` and `ECSCOPE_MESSAGE`
+    pub command: Option<String>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            escape_sequence: None,
+            command: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+}
+
+/// Returns a notification for a service marked for refresh, if it's changed in a way worth
+/// knowing about between two refreshes.
+pub fn notification_for(previous: &ServiceResult, current: &ServiceResult) -> Option<Notification> {
+    let item = ServiceItem::new(current.clone());
+
+    let changes = match (previous, current) {
+        (Ok(_), Err(e)) => vec![format!("refresh failed: {}", e.error)],
+        (Ok(p), Ok(c)) => {
+            let mut changes = Vec::new();
+            if ServiceItem::new(previous.clone()).health() == ServiceHealth::InProgress
+                && item.health() == ServiceHealth::Healthy
+            {
+                changes.push(format!(
+                    "deployment completed ({}/{} running)",
+                    c.running_count, c.desired_count
+                ));
+            }
+            if c.failed_count > p.failed_count {
+                changes.push(format!(
+                    "failed tasks went up from {} to {}",
+                    p.failed_count, c.failed_count
+                ));
+            }
+            if c.running_count < c.desired_count && p.running_count >= p.desired_count {
+                changes.push(format!(
+                    "running tasks dropped below desired ({}/{})",
+                    c.running_count, c.desired_count
+                ));
+            }
+            changes
+        }
+        // errors are reported when they start, not when they go on
+        (Err(_), _) => Vec::new(),
+    };
+
+    if changes.is_empty() {
+        return None;
+    }
+
+    Some(Notification {
+        title: NOTIFICATION_TITLE.to_string(),
+        message: format!(
+            "{} ({}): {}",
+            item.name(),
+            item.cluster_keys().join(", "),
+            changes.join("; ")
+        ),
+    })
+}
+
+/// Sends the notification out through the configured channels; failures are ignored, as
+/// there's nowhere to report them that'd be noticed.
+pub fn send_notification(notification: &Notification, config: &NotificationsConfig) {
+    let mut out = String::new();
+    if config.bell {
+        out.push('\x07');
+    }
+    if let Some(sequence) = config.escape_sequence {
+        out.push_str(&escape_sequence(sequence, notification));
+    }
+
+    if !out.is_empty() {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    if let Some(command) = &config.command {
+        // the child's output would mess up the TUI
+        let _ = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("ECSCOPE_TITLE", &notification.title)
+            .env("ECSCOPE_MESSAGE", &notification.message)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+    }
+}
+
+fn escape_sequence(sequence: EscapeSequence, notification: &Notification) -> String {
+    // control characters would end the sequence early
+    let clean = |s: &str| s.chars().filter(|c| !c.is_control()).collect::<String>();
+
+    match sequence {
+        EscapeSequence::Osc9 => format!("\x1b]9;{}\x07", clean(&notification.message)),
+        EscapeSequence::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            clean(&notification.title).replace(';', ","),
+            clean(&notification.message)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClientKey, ConfigSource};
+    use crate::domain::*;
+
+    fn service(running: i32, desired: i32, failed: i32) -> ServiceResult {
+        Ok(ServiceDetails {
+            name: "payments-api".to_string(),
+            status: "ACTIVE".to_string(),
+            desired_count: desired,
+            running_count: running,
+            pending_count: 0,
+            failed_count: failed,
+            cluster_keys: vec!["prod".to_string()],
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+            client_key: ClientKey {
+                config_source: ConfigSource::Env,
+                region: None,
+            },
+            metadata: Default::default(),
+            last_deployed_at: None,
+            object: Default::default(),
+        })
+    }
+
+    fn error() -> ServiceResult {
+        Err(ServiceError {
+            service_name: "payments-api".to_string(),
+            error: "access denied".to_string(),
+            cluster_keys: vec!["prod".to_string()],
+        })
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn notifications_are_raised_for_changes_worth_knowing_about() {
+        // GIVEN
+        let cases = [
+            (
+                service(3, 2, 0),
+                service(2, 2, 0),
+                Some("payments-api (prod): deployment completed (2/2 running)"),
+            ),
+            (
+                service(2, 2, 1),
+                service(2, 2, 3),
+                Some("payments-api (prod): failed tasks went up from 1 to 3"),
+            ),
+            (
+                service(2, 2, 0),
+                service(1, 2, 1),
+                Some(
+                    "payments-api (prod): failed tasks went up from 0 to 1; running tasks dropped below desired (1/2)",
+                ),
+            ),
+            (
+                service(2, 2, 0),
+                error(),
+                Some("payments-api (prod): refresh failed: access denied"),
+            ),
+            (service(2, 2, 0), service(2, 2, 0), None),
+            (service(1, 2, 0), service(1, 2, 0), None),
+            (error(), error(), None),
+        ];
+
+        for (previous, current, expected) in cases {
+            // WHEN
+            let notification = notification_for(&previous, &current);
+
+            // THEN
+            assert_eq!(
+                notification.as_ref().map(|n| n.message.as_str()),
+                expected,
+                "previous: {previous:?}, current: {current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn escape_sequences_are_built_correctly() {
+        // GIVEN
+        let notification = Notification {
+            title: "ecscope".to_string(),
+            message: "payments-api (prod): deployment\x07 completed".to_string(),
+        };
+
+        // WHEN
+        let osc9 = escape_sequence(EscapeSequence::Osc9, &notification);
+        let osc777 = escape_sequence(EscapeSequence::Osc777, &notification);
+
+        // THEN
+        assert_eq!(osc9, "\x1b]9;payments-api (prod): deployment completed\x07");
+        assert_eq!(
+            osc777,
+            "\x1b]777;notify;ecscope;payments-api (prod): deployment completed\x07"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use super::model::ServicesSort;
+use crate::domain::ServiceDetails;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
+
+const SESSIONS_DIR: &str = "sessions";
+
+/// The parts of the TUI's state that are carried over between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    #[serde(default)]
+    pub auto_refresh: bool,
+    #[serde(default)]
+    pub sort: ServicesSort,
+    /// The source of the filter in effect
+    pub filter: Option<String>,
+    pub selected_service: Option<ServiceRef>,
+    #[serde(default)]
+    pub marked_services: Vec<ServiceRef>,
+}
+
+/// Identifies a service across runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ServiceRef {
+    pub name: String,
+    pub cluster_arn: String,
+}
+
+impl From<&ServiceDetails> for ServiceRef {
+    fn from(service: &ServiceDetails) -> Self {
+        Self {
+            name: service.name.clone(),
+            cluster_arn: service.cluster_arn.clone(),
+        }
+    }
+}
+
+/// What's left to restore from the last session; services are fetched cluster by cluster, so
+/// they're restored as they come in.
+#[derive(Debug, Default)]
+pub struct PendingRestore {
+    pub marked: HashSet<ServiceRef>,
+    pub selected: Option<ServiceRef>,
+}
+
+impl From<&SessionState> for PendingRestore {
+    fn from(state: &SessionState) -> Self {
+        Self {
+            marked: state.marked_services.iter().cloned().collect(),
+            selected: state.selected_service.clone(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SessionError {
+    #[error("couldn't read {0}: {1}")]
+    CouldntReadFile(PathBuf, IOError),
+    #[error("{0} is invalid: {1}")]
+    FileInvalid(PathBuf, toml::de::Error),
+    #[error("couldn't serialize the session: {0}")]
+    CouldntSerialize(#[from] toml::ser::Error),
+    #[error("couldn't create directory {0}: {1}")]
+    CouldntCreateDir(PathBuf, IOError),
+    #[error("couldn't write to {0}: {1}")]
+    CouldntWriteFile(PathBuf, IOError),
+}
+
+/// Where the TUI's session is saved, per profile, in ecscope's data directory.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    path: PathBuf,
+    /// Whether the saved session is to be ignored; it's still overwritten on exit
+    fresh: bool,
+}
+
+impl SessionStore {
+    pub fn new(data_dir: &Path, profile_name: &str, fresh: bool) -> Self {
+        // profiles picked up from files can have just about any name; they're percent-encoded so
+        // that different names don't end up sharing a file
+        let file_stem: String = profile_name
+            .bytes()
+            .map(|b| match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                    char::from(b).to_string()
+                }
+                _ => format!("%{b:02X}"),
+            })
+            .collect();
+
+        Self {
+            path: data_dir
+                .join(SESSIONS_DIR)
+                .join(format!("{file_stem}.toml")),
+            fresh,
+        }
+    }
+
+    pub fn load(&self) -> Result<Option<SessionState>, SessionError> {
+        if self.fresh {
+            return Ok(None);
+        }
+
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SessionError::CouldntReadFile(self.path.clone(), e)),
+        };
+
+        toml::from_str(&contents)
+            .map(Some)
+            .map_err(|e| SessionError::FileInvalid(self.path.clone(), e))
+    }
+
+    pub fn save(&self, state: &SessionState) -> Result<(), SessionError> {
+        let contents = toml::to_string(state)?;
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| SessionError::CouldntCreateDir(dir.to_path_buf(), e))?;
+        }
+
+        std::fs::write(&self.path, contents)
+            .map_err(|e| SessionError::CouldntWriteFile(self.path.clone(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service_ref(name: &str) -> ServiceRef {
+        ServiceRef {
+            name: name.to_string(),
+            cluster_arn: "arn:aws:ecs:eu-central-1:111111111111:cluster/prod".to_string(),
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn saved_sessions_are_restored() {
+        // GIVEN
+        let data_dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let store = SessionStore::new(data_dir.path(), "prod", false);
+        let state = SessionState {
+            auto_refresh: true,
+            sort: ServicesSort::Unhealthy,
+            filter: Some("key=prod and failed>0".to_string()),
+            selected_service: Some(service_ref("payments-api")),
+            marked_services: vec![service_ref("auth-api"), service_ref("payments-api")],
+        };
+
+        // WHEN
+        store.save(&state).expect("session should've been saved");
+        let loaded = store.load().expect("session should've been loaded");
+
+        // THEN
+        assert_eq!(loaded, Some(state));
+        assert!(data_dir.path().join("sessions/prod.toml").exists());
+    }
+
+    #[test]
+    fn saved_sessions_are_ignored_when_starting_fresh() {
+        // GIVEN
+        let data_dir = tempfile::tempdir().expect("temporary directory should've been created");
+        SessionStore::new(data_dir.path(), "prod", false)
+            .save(&SessionState {
+                auto_refresh: true,
+                ..Default::default()
+            })
+            .expect("session should've been saved");
+        let store = SessionStore::new(data_dir.path(), "prod", true);
+
+        // WHEN
+        let loaded = store.load().expect("loading should've succeeded");
+
+        // THEN
+        assert_eq!(loaded, None);
+    }
+
+    #[test]
+    fn profile_names_are_turned_into_safe_file_names() {
+        // GIVEN
+        let data_dir = Path::new("/data");
+
+        // WHEN
+        let store = SessionStore::new(data_dir, "../prod eu", false);
+
+        // THEN
+        assert_eq!(
+            store.path,
+            PathBuf::from("/data/sessions/..%2Fprod%20eu.toml")
+        );
+    }
+
+    #[test]
+    fn similar_profile_names_dont_share_a_file() {
+        // GIVEN
+        let data_dir = Path::new("/data");
+
+        // WHEN
+        let paths = ["prod eu", "prod_eu", "prod%20eu"]
+            .map(|name| SessionStore::new(data_dir, name, false).path);
+
+        // THEN
+        assert_ne!(paths[0], paths[1]);
+        assert_ne!(paths[0], paths[2]);
+        assert_ne!(paths[1], paths[2]);
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn invalid_session_files_are_reported() {
+        // GIVEN
+        let data_dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let sessions_dir = data_dir.path().join(SESSIONS_DIR);
+        std::fs::create_dir_all(&sessions_dir).expect("directory should've been created");
+        std::fs::write(sessions_dir.join("prod.toml"), "sort = \"sideways\"")
+            .expect("file should've been written");
+        let store = SessionStore::new(data_dir.path(), "prod", false);
+
+        // WHEN
+        let result = store.load();
+
+        // THEN
+        assert!(matches!(result, Err(SessionError::FileInvalid(..))));
+    }
+}
@@ -0,0 +1,197 @@
+use ratatui::style::Color;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Colours used by the TUI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub section_title_fg: Color,
+    pub primary: Color,
+    pub secondary: Color,
+    pub message: Color,
+    pub inactive_pane_title_bg: Color,
+    pub inactive_pane_border: Color,
+    pub inactive_pane_selected: Color,
+    pub info_message: Color,
+    pub error_message: Color,
+    pub search_match: Color,
+    pub text: Color,
+    pub background: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ThemeError {
+    #[error(r#""{0}" is not a built-in theme; pick one of: dark, light, high-contrast"#)]
+    UnknownBaseTheme(String),
+    #[error(r#""{0}" is not a themeable colour"#)]
+    UnknownColour(String),
+    #[error(r#"invalid colour for "{0}": "{1}" (use a name like "blue", or hex like "\#83a598")"#)]
+    InvalidColour(String, String),
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            section_title_fg: Color::from_u32(0x282828),
+            primary: Color::from_u32(0xb8bb26),
+            secondary: Color::from_u32(0x83a598),
+            message: Color::from_u32(0xd3869b),
+            inactive_pane_title_bg: Color::from_u32(0xbdae93),
+            inactive_pane_border: Color::from_u32(0x928374),
+            inactive_pane_selected: Color::from_u32(0xfabd2f),
+            info_message: Color::from_u32(0x83a598),
+            error_message: Color::from_u32(0xfb4934),
+            search_match: Color::from_u32(0xfe8019),
+            text: Color::White,
+            background: Color::Black,
+        }
+    }
+
+    /// For terminals with a light background; leaves the background as is.
+    pub fn light() -> Self {
+        Self {
+            section_title_fg: Color::from_u32(0xfbf1c7),
+            primary: Color::from_u32(0x79740e),
+            secondary: Color::from_u32(0x076678),
+            message: Color::from_u32(0x8f3f71),
+            inactive_pane_title_bg: Color::from_u32(0x7c6f64),
+            inactive_pane_border: Color::from_u32(0x928374),
+            inactive_pane_selected: Color::from_u32(0xb57614),
+            info_message: Color::from_u32(0x076678),
+            error_message: Color::from_u32(0x9d0006),
+            search_match: Color::from_u32(0xaf3a03),
+            text: Color::from_u32(0x3c3836),
+            background: Color::Reset,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            section_title_fg: Color::from_u32(0x000000),
+            primary: Color::from_u32(0x00ff00),
+            secondary: Color::from_u32(0x00ffff),
+            message: Color::from_u32(0xff00ff),
+            inactive_pane_title_bg: Color::from_u32(0xffffff),
+            inactive_pane_border: Color::from_u32(0xffffff),
+            inactive_pane_selected: Color::from_u32(0xffff00),
+            info_message: Color::from_u32(0x00ffff),
+            error_message: Color::from_u32(0xff0000),
+            search_match: Color::from_u32(0xffff00),
+            text: Color::from_u32(0xffffff),
+            background: Color::from_u32(0x000000),
+        }
+    }
+
+    /// Returns the built-in theme `base` (dark if not provided), with the given colours replaced.
+    pub fn with_overrides(
+        base: Option<&str>,
+        colours: &BTreeMap<String, String>,
+    ) -> Result<Self, ThemeError> {
+        let mut theme = match base {
+            None | Some("dark") => Self::dark(),
+            Some("light") => Self::light(),
+            Some("high-contrast") => Self::high_contrast(),
+            Some(other) => return Err(ThemeError::UnknownBaseTheme(other.to_string())),
+        };
+
+        for (name, value) in colours {
+            let colour = Color::from_str(value)
+                .map_err(|_| ThemeError::InvalidColour(name.to_string(), value.to_string()))?;
+
+            let slot = match name.as_str() {
+                "section_title_fg" => &mut theme.section_title_fg,
+                "primary" => &mut theme.primary,
+                "secondary" => &mut theme.secondary,
+                "message" => &mut theme.message,
+                "inactive_pane_title_bg" => &mut theme.inactive_pane_title_bg,
+                "inactive_pane_border" => &mut theme.inactive_pane_border,
+                "inactive_pane_selected" => &mut theme.inactive_pane_selected,
+                "info_message" => &mut theme.info_message,
+                "error_message" => &mut theme.error_message,
+                "search_match" => &mut theme.search_match,
+                "text" => &mut theme.text,
+                "background" => &mut theme.background,
+                _ => return Err(ThemeError::UnknownColour(name.to_string())),
+            };
+            *slot = colour;
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colours(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn overriding_colours_of_a_built_in_theme_works() {
+        // GIVEN
+        let colours = colours(&[("primary", "#ff0000"), ("background", "reset")]);
+
+        // WHEN
+        let theme = Theme::with_overrides(Some("high-contrast"), &colours)
+            .expect("theme should've been valid");
+
+        // THEN
+        assert_eq!(
+            theme,
+            Theme {
+                primary: Color::Rgb(255, 0, 0),
+                background: Color::Reset,
+                ..Theme::high_contrast()
+            }
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn invalid_theme_overrides_fail() {
+        // GIVEN
+        let cases = [
+            (
+                Some("solarized"),
+                colours(&[]),
+                ThemeError::UnknownBaseTheme("solarized".to_string()),
+            ),
+            (
+                None,
+                colours(&[("border", "blue")]),
+                ThemeError::UnknownColour("border".to_string()),
+            ),
+            (
+                None,
+                colours(&[("primary", "#12345")]),
+                ThemeError::InvalidColour("primary".to_string(), "#12345".to_string()),
+            ),
+        ];
+
+        for (base, colours, expected) in cases {
+            // WHEN
+            let result = Theme::with_overrides(base, &colours);
+
+            // THEN
+            assert_eq!(result, Err(expected));
+        }
+    }
+}
@@ -2,21 +2,23 @@ use super::command::Command;
 use super::common::*;
 use super::message::Message;
 use super::model::*;
-use std::time::Instant;
+use super::notify::notification_for;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
-    let service_index_before_update = model.service_items.state.selected();
+    let service_index_before_update = model.service_items.selected_index();
     let task_index_before_update = match &model.task_items {
         Some(i) => i.state.selected(),
         None => None,
     };
+    let container_index_before_update = model.container_items.state.selected();
     let mut data_refresh = false;
 
     let mut cmds = Vec::new();
 
     match msg {
-        Message::GoToNextListItem => model.select_next_list_item(),
-        Message::GoToPreviousListItem => model.select_previous_list_item(),
+        Message::GoToNextListItem => model.select_next_list_item(model.active_pane),
+        Message::GoToPreviousListItem => model.select_previous_list_item(model.active_pane),
         Message::GoToFirstListItem => model.select_first_list_item(),
         Message::GoToLastListItem => model.select_last_list_item(),
         Message::TerminalResize(width, height) => {
@@ -48,12 +50,22 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
             model.last_active_pane = Some(model.active_pane);
             model.active_pane = pane;
         }
+        Message::ClickListItem(pane, row) => {
+            if model.active_pane != pane {
+                model.last_active_pane = Some(model.active_pane);
+                model.active_pane = pane;
+            }
+            model.select_list_item_on_screen(pane, row);
+        }
+        Message::ScrollPaneDown(pane) => model.scroll_pane(pane, true),
+        Message::ScrollPaneUp(pane) => model.scroll_pane(pane, false),
         Message::TasksFetched((service_details, tasks, refresh)) => {
-            model.task_results_cache.insert(service_details, tasks);
+            model.task_results_cache.insert(service_details.id(), tasks);
             data_refresh = refresh;
         }
         Message::ServicesFetched(service_results) => {
             model.service_items.append(&service_results);
+            model.restore_session();
             for service_result in &service_results {
                 if let Ok(service_details) = service_result {
                     cmds.push(Command::GetTasks((service_details.clone(), false)));
@@ -62,66 +74,97 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
                 }
             }
         }
-        Message::ServiceDetailsRefreshed((result, previous_service_details, index)) => {
-            if model.service_items.items.len() > index {
+        Message::ServiceDetailsRefreshed((result, previous_service_details)) => {
+            // items might've been re-sorted since the refresh was requested; a service that's not
+            // in the list anymore has nothing to update
+            let previous_item = ServiceItem::new(Ok(*previous_service_details.clone()));
+            if let Some(index) = model.service_items.position_of_item(&previous_item) {
                 let service_item = ServiceItem::new(result.clone());
+                let previous_health = model.service_items.items[index].health();
+                if service_item.health() != previous_health {
+                    model.transitions.record(Transition {
+                        at: SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|d| d.as_secs() as i64)
+                            .unwrap_or_default(),
+                        from: previous_health,
+                        to: service_item.health(),
+                        item: ServiceItem::new(result.clone()),
+                    });
+                }
                 let marked_for_refresh = model
                     .service_items
                     .items
                     .get(index)
                     .is_some_and(|i| i.marked_for_refresh);
+                if marked_for_refresh
+                    && let Some(notification) =
+                        notification_for(&model.service_items.items[index].service, &result)
+                {
+                    model.user_message = Some(UserMessage::info(&notification.message));
+                    cmds.push(Command::Notify((notification, model.notifications.clone())));
+                }
                 model.service_items.items[index] = service_item;
                 if result.is_ok() {
                     model.service_items.items[index].marked_for_refresh = marked_for_refresh;
-                    model.task_results_cache.remove(&previous_service_details);
+                    model
+                        .task_results_cache
+                        .remove(&previous_service_details.id());
                     data_refresh = true;
                 } else if marked_for_refresh {
                     model.service_items.items[index].marked_for_refresh = false;
                     model.num_services_marked_for_refresh -= 1;
                 }
             }
+
+            // the refreshed service might have moved, and might not pass the filter anymore (or
+            // might now)
+            model.service_items.resort();
         }
         Message::RefreshResultsForCurrentItem => match model.active_pane {
+            // group headers and pivot rows refresh all the services they stand for
             Pane::ServicesList | Pane::ServiceDetails => {
-                if let Some((Ok(service_details), index)) = model.get_selected_service() {
-                    cmds.push(Command::RefreshService((service_details.clone(), index)));
+                for index in model.service_items.selected_members() {
+                    if let Some(Ok(service_details)) =
+                        model.service_items.items.get(index).map(|i| &i.service)
+                    {
+                        cmds.push(Command::RefreshService(service_details.clone()));
+                    }
                 }
             }
             Pane::TasksList | Pane::TaskDetails | Pane::ContainersList | Pane::ContainerDetails => {
                 if let Some((Ok(service_details), _)) = model.get_selected_service() {
                     cmds.push(Command::GetTasks((service_details.clone(), true)));
-                    model.task_results_cache.remove(&service_details.clone());
+                    model.task_results_cache.remove(&service_details.id());
                     model.task_items = None;
                 }
             }
-            Pane::Help => {}
+            Pane::Overview | Pane::Help => {}
         },
         Message::RefreshResultsForMarkedServices => match model.active_pane {
             Pane::Help => {}
             _ => {
                 if model.num_services_marked_for_refresh == 0 {
-                    for (index, service_result) in model.service_items.items.iter_mut().enumerate()
-                    {
+                    for service_result in &model.service_items.items {
                         if let Ok(service_details) = &service_result.service {
-                            cmds.push(Command::RefreshService((service_details.clone(), index)));
-                            model.task_results_cache.remove(service_details);
+                            cmds.push(Command::RefreshService(service_details.clone()));
+                            model.task_results_cache.remove(&service_details.id());
                         }
                     }
                 } else {
-                    for (index, service_result) in model.service_items.items.iter_mut().enumerate()
-                    {
+                    for service_result in &model.service_items.items {
                         if service_result.marked_for_refresh
                             && let Ok(service_details) = &service_result.service
                         {
-                            cmds.push(Command::RefreshService((service_details.clone(), index)));
-                            model.task_results_cache.remove(service_details);
+                            cmds.push(Command::RefreshService(service_details.clone()));
+                            model.task_results_cache.remove(&service_details.id());
                         }
                     }
                 }
             }
         },
         Message::ToggleServiceRefresh => {
-            if let Some(index) = model.service_items.state.selected()
+            if let Some(index) = model.service_items.selected_index()
                 && let Some(service_item) = model.service_items.items.get_mut(index)
             {
                 if service_item.service.is_ok() {
@@ -142,17 +185,102 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
         Message::ToggleAutoRefresh => model.auto_refresh = !model.auto_refresh,
         Message::GoBackOrQuit => model.go_back_or_quit(),
         Message::QuitImmediately => model.running_state = RunningState::Done,
+        Message::OpenFilterInput => {
+            let current = model
+                .service_items
+                .filter
+                .as_ref()
+                .map(|f| f.to_string())
+                .unwrap_or_default();
+            model.filter_input = Some(current);
+        }
+        Message::FilterInputChar(c) => {
+            if let Some(input) = &mut model.filter_input {
+                input.push(c);
+            }
+        }
+        Message::FilterInputBackspace => {
+            if let Some(input) = &mut model.filter_input {
+                input.pop();
+            }
+        }
+        Message::ApplyFilterInput => model.apply_filter_input(),
+        Message::CancelFilterInput => model.filter_input = None,
+        Message::OpenSearchInput => model.search_input_active = true,
+        Message::SearchInputChar(c) => {
+            let mut search = model.service_items.search.clone().unwrap_or_default();
+            search.push(c);
+            model.service_items.set_search(Some(search));
+        }
+        Message::SearchInputBackspace => {
+            let mut search = model.service_items.search.clone().unwrap_or_default();
+            search.pop();
+            model.service_items.set_search(Some(search));
+        }
+        Message::ConfirmSearch => model.search_input_active = false,
+        Message::ClearSearch => {
+            model.search_input_active = false;
+            model.service_items.set_search(None);
+        }
+        Message::CycleServicesLayout => {
+            let layout = model.service_items.layout.next();
+            model.service_items.set_layout(layout);
+        }
+        Message::CycleServicesSort => {
+            let sort = model.service_items.sort.next();
+            model.service_items.set_sort(sort);
+        }
+        Message::ToggleServiceGroup => model.service_items.toggle_selected_group(),
+        Message::OpenOverviewEntry => model.open_overview_entry(),
+        Message::YankSelected => match (model.redact_mode, model.selected_identifier()) {
+            (true, _) => {
+                model.user_message = Some(UserMessage::error("copying is disabled in redact mode"));
+            }
+            (false, Some((what, value))) => {
+                cmds.push(Command::CopyToClipboard(value));
+                model.user_message = Some(UserMessage::info(&format!("copied {what}")));
+            }
+            (false, None) => {
+                model.user_message = Some(UserMessage::error("nothing to copy here"));
+            }
+        },
+        Message::OpenSelectedInConsole => match model.selected_console_url() {
+            Some((url, account)) => {
+                cmds.push(Command::OpenUrl(url));
+                let message = match model.redact_mode {
+                    true => "opening the AWS console".to_string(),
+                    false => format!("opening the AWS console (for account {account})"),
+                };
+                model.user_message = Some(UserMessage::info(&message));
+            }
+            None => {
+                model.user_message = Some(UserMessage::error("nothing to open here"));
+            }
+        },
+        Message::ToggleFullDetails => {
+            if model.redact_mode {
+                model.user_message =
+                    Some(UserMessage::error("full details are hidden in redact mode"));
+            } else {
+                model.full_details = !model.full_details;
+                model.object_views.clear();
+            }
+        }
+        Message::ToggleObjectNode(pane) => model.toggle_object_node(pane),
+        Message::CouldntOpenBrowser => {
+            model.user_message = Some(UserMessage::error("couldn't open the browser"));
+        }
     }
 
     let refresh_tasks_and_containers = data_refresh
         || model.task_items.is_none()
-        || service_index_before_update != model.service_items.state.selected();
+        || service_index_before_update != model.service_items.selected_index();
 
     if refresh_tasks_and_containers {
         if let Some((service_result, _)) = model.get_selected_service() {
             match service_result {
                 Ok(service) => {
-                    match model.task_results_cache.get(service) {
+                    match model.task_results_cache.get(&service.id()) {
                         Some(tr) => {
                             let task_items = TaskItems::from(tr);
                             model.task_items = Some(task_items);
@@ -182,5 +310,27 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
         model.container_items = container_items;
     }
 
+    // details of a newly selected item are shown from the top
+    let panes_to_reset: &[Pane] = if service_index_before_update
+        != model.service_items.selected_index()
+    {
+        &[
+            Pane::ServiceDetails,
+            Pane::TaskDetails,
+            Pane::ContainerDetails,
+        ]
+    } else if task_index_before_update != model.task_items.as_ref().and_then(|i| i.state.selected())
+    {
+        &[Pane::TaskDetails, Pane::ContainerDetails]
+    } else if container_index_before_update != model.container_items.state.selected() {
+        &[Pane::ContainerDetails]
+    } else {
+        &[]
+    };
+    for pane in panes_to_reset {
+        model.details_scroll.remove(pane);
+        model.object_views.remove(pane);
+    }
+
     cmds
 }
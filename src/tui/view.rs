@@ -1,29 +1,35 @@
 use super::common::*;
-use super::model::{Model, UserMessage};
+use super::model::{
+    KeyHealth, Model, ObjectView, OverviewEntry, ServiceHealth, ServiceItem, ServiceRow,
+    ServicesLayout, ServicesSort, Transition, UserMessage,
+};
+use crate::config::ServiceMetadata;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Style, Stylize},
+    style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, List, ListDirection, ListItem, Padding, Paragraph, Wrap},
+    widgets::{Block, List, ListDirection, ListItem, ListState, Padding, Paragraph, Wrap},
 };
+use std::time::{SystemTime, UNIX_EPOCH};
 
-const HELP_CONTENTS: &str = include_str!("static/help.txt");
-const REDACTED: &str = "<REDACTED>";
+const PROGRESS_BAR_WIDTH: usize = 20;
 
 pub fn view(model: &mut Model, frame: &mut Frame) {
     if model.terminal_too_small {
-        render_terminal_too_small_view(&model.terminal_dimensions, frame);
+        render_terminal_too_small_view(model, frame);
         return;
     }
 
     match model.active_pane {
         Pane::Help => render_help_view(model, frame),
+        Pane::Overview => render_overview_view(model, frame),
         _ => render_list_view(model, frame),
     }
 }
 
-fn render_terminal_too_small_view(dimensions: &TerminalDimensions, frame: &mut Frame) {
+fn render_terminal_too_small_view(model: &Model, frame: &mut Frame) {
+    let dimensions = &model.terminal_dimensions;
     let message = format!(
         r#"
 Terminal size too small:
@@ -39,7 +45,7 @@ Press (q/<ctrl+c>/<esc> to exit)
 
     let p = Paragraph::new(message)
         .block(Block::bordered())
-        .style(Style::new().fg(PRIMARY_COLOR))
+        .style(Style::new().fg(model.theme.primary))
         .wrap(Wrap { trim: false })
         .alignment(Alignment::Center);
 
@@ -52,52 +58,288 @@ fn render_help_view(model: &mut Model, frame: &mut Frame) {
         .constraints(vec![Constraint::Min(21), Constraint::Length(1)])
         .split(frame.area());
 
-    let lines: Vec<Line<'_>> = HELP_CONTENTS.lines().map(Line::from).collect();
+    model.pane_areas = vec![(Pane::Help, layout[0])];
+
+    let help = model.key_map.help();
+    let lines: Vec<Line<'_>> = help.lines().map(Line::from).collect();
+    let scroll = details_scroll(model, Pane::Help, &help);
 
     let p = Paragraph::new(lines)
         .block(
             Block::bordered()
-                .border_style(Style::default().fg(MESSAGE_COLOR))
+                .border_style(Style::default().fg(model.theme.message))
                 .title_style(
                     Style::new()
                         .bold()
-                        .bg(MESSAGE_COLOR)
-                        .fg(SECTION_TITLE_FG_COLOR),
+                        .bg(model.theme.message)
+                        .fg(model.theme.section_title_fg),
                 )
                 .title(" help ")
                 .padding(Padding::new(2, 0, 1, 0)),
         )
-        .style(Style::new().white())
+        .style(Style::new().fg(model.theme.text))
+        .scroll(scroll)
         .alignment(Alignment::Left);
 
     frame.render_widget(p, layout[0]);
     render_status_line(model, frame, layout[1]);
 }
 
-fn render_status_line(model: &Model, frame: &mut Frame, rect: Rect) {
+fn render_overview_view(model: &mut Model, frame: &mut Frame) {
+    let overview = model.overview();
+    model.overview_selected = model
+        .overview_selected
+        .min(overview.len().saturating_sub(1));
+    let selected = overview.entry(model.overview_selected);
+
+    let layout = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![
+            // borders and padding take up 3 rows
+            Constraint::Length((overview.keys.len() as u16 + 3).min(frame.area().height / 3)),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    model.pane_areas = vec![(
+        Pane::Overview,
+        Rect {
+            height: layout[0].height + layout[1].height + layout[2].height,
+            ..layout[0]
+        },
+    )];
+
+    let key_width = overview
+        .keys
+        .iter()
+        .map(|k| k.key.chars().count())
+        .max()
+        .unwrap_or_default();
+    let key_items: Vec<ListItem> = overview
+        .keys
+        .iter()
+        .map(|k| key_health_list_item(model, k, key_width))
+        .collect();
+    let key_selected = overview
+        .keys
+        .iter()
+        .position(|k| selected == Some(OverviewEntry::Key(k.key.clone())));
+    render_overview_list(
+        model,
+        frame,
+        layout[0],
+        " services by cluster key ".to_string(),
+        key_items,
+        key_selected,
+        "cluster keys will appear here",
+    );
+
+    let deploying: Vec<&ServiceItem> = overview
+        .deploying
+        .iter()
+        .filter_map(|i| model.service_items.items.get(*i))
+        .collect();
+    let name_width = deploying
+        .iter()
+        .map(|i| i.name().chars().count())
+        .max()
+        .unwrap_or_default();
+    let deploying_items: Vec<ListItem> = deploying
+        .iter()
+        .map(|i| deploying_list_item(model, i, name_width))
+        .collect();
+    let deploying_selected = match selected {
+        Some(OverviewEntry::Deploying(index)) => {
+            overview.deploying.iter().position(|i| *i == index)
+        }
+        _ => None,
+    };
+    render_overview_list(
+        model,
+        frame,
+        layout[1],
+        format!(" deploying ({}) ", deploying_items.len()),
+        deploying_items,
+        deploying_selected,
+        "no deployments in progress",
+    );
+
+    let transition_items: Vec<ListItem> = model
+        .transitions
+        .items
+        .iter()
+        .map(|t| transition_list_item(model, t))
+        .collect();
+    let transition_selected = match selected {
+        Some(OverviewEntry::Transition(index)) => Some(index),
+        _ => None,
+    };
+    render_overview_list(
+        model,
+        frame,
+        layout[2],
+        " recent changes ".to_string(),
+        transition_items,
+        transition_selected,
+        "changes in the state of services will appear here as they get refreshed",
+    );
+
+    render_status_line(model, frame, layout[3]);
+}
+
+fn render_overview_list(
+    model: &Model,
+    frame: &mut Frame,
+    rect: Rect,
+    title: String,
+    items: Vec<ListItem>,
+    selected: Option<usize>,
+    placeholder: &str,
+) {
+    let (border_color, title_color) = if selected.is_some() {
+        (model.theme.primary, model.theme.primary)
+    } else {
+        (
+            model.theme.inactive_pane_border,
+            model.theme.inactive_pane_title_bg,
+        )
+    };
+
+    let block = Block::bordered()
+        .border_style(Style::default().fg(border_color))
+        .title_style(
+            Style::new()
+                .bold()
+                .bg(title_color)
+                .fg(model.theme.section_title_fg),
+        )
+        .title(title);
+
+    if items.is_empty() {
+        let p = Paragraph::new(placeholder)
+            .block(block.padding(Padding::new(1, 0, 1, 0)))
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+            .alignment(Alignment::Left);
+
+        frame.render_widget(&p, rect);
+        return;
+    }
+
+    let list = List::new(items)
+        .block(block.padding(Padding::new(0, 0, 1, 0)))
+        .style(Style::new().fg(model.theme.text))
+        .highlight_symbol("> ")
+        .repeat_highlight_symbol(true)
+        .highlight_style(Style::new().fg(model.theme.primary))
+        .direction(ListDirection::TopToBottom);
+
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(list, rect, &mut state);
+}
+
+fn key_health_list_item(
+    model: &Model,
+    key_health: &KeyHealth,
+    key_width: usize,
+) -> ListItem<'static> {
+    let count = |health: ServiceHealth, count: usize| {
+        let span = Span::from(format!("  {} {count:>4}", health.symbol()));
+        match health {
+            ServiceHealth::Failing | ServiceHealth::Errored if count > 0 => {
+                span.fg(model.theme.error_message)
+            }
+            ServiceHealth::InProgress if count > 0 => span.fg(model.theme.message),
+            _ => span,
+        }
+    };
+
+    ListItem::new(Line::from(vec![
+        Span::from(format!("{:key_width$}", key_health.key)).bold(),
+        count(ServiceHealth::Healthy, key_health.healthy),
+        count(ServiceHealth::InProgress, key_health.in_progress),
+        count(ServiceHealth::Failing, key_health.failing),
+        count(ServiceHealth::Errored, key_health.errored),
+    ]))
+}
+
+fn deploying_list_item(model: &Model, item: &ServiceItem, name_width: usize) -> ListItem<'static> {
+    let Ok(service) = &item.service else {
+        return ListItem::new(item.name().to_string());
+    };
+
+    let (running, desired) = (service.running_count.max(0), service.desired_count.max(0));
+    let filled = match desired {
+        0 => 0,
+        d => (running.min(d) as usize * PROGRESS_BAR_WIDTH) / d as usize,
+    };
+
+    ListItem::new(Line::from(vec![
+        Span::from(format!("{:name_width$}", item.name())),
+        Span::from(format!("  {}  ", service.cluster_keys.join(", "))).fg(model.theme.secondary),
+        Span::from("█".repeat(filled)).fg(model.theme.primary),
+        Span::from("░".repeat(PROGRESS_BAR_WIDTH - filled)).fg(model.theme.inactive_pane_border),
+        Span::from(format!(" {running}/{desired}")),
+        Span::from(match service.pending_count {
+            0 => String::new(),
+            p => format!(" ({p} pending)"),
+        }),
+    ]))
+}
+
+fn transition_list_item(model: &Model, transition: &Transition) -> ListItem<'static> {
+    let health_color = |health: ServiceHealth| match health {
+        ServiceHealth::Healthy => model.theme.primary,
+        ServiceHealth::InProgress => model.theme.message,
+        ServiceHealth::Failing | ServiceHealth::Errored => model.theme.error_message,
+    };
+
+    ListItem::new(Line::from(vec![
+        Span::from(format!("{:>8}  ", time_since(transition.at))),
+        Span::from(transition.item.name().to_string()),
+        Span::from(format!("  {}  ", transition.item.cluster_keys().join(", ")))
+            .fg(model.theme.secondary),
+        Span::from(format!("{} {}", transition.from.symbol(), transition.from))
+            .fg(health_color(transition.from)),
+        Span::from(" → "),
+        Span::from(format!("{} {}", transition.to.symbol(), transition.to))
+            .fg(health_color(transition.to)),
+    ]))
+}
+
+fn render_status_line(model: &mut Model, frame: &mut Frame, rect: Rect) {
+    model.title_area = Rect {
+        width: (TITLE.chars().count() as u16).min(rect.width),
+        height: 1,
+        ..rect
+    };
+
     let mut status_bar_lines = vec![Span::styled(
         TITLE,
         Style::new()
             .bold()
-            .bg(PRIMARY_COLOR)
-            .fg(SECTION_TITLE_FG_COLOR),
+            .bg(model.theme.primary)
+            .fg(model.theme.section_title_fg),
     )];
 
-    status_bar_lines.push(Span::from(format!(" [{}]", &model.profile_name)).fg(SECONDARY_COLOR));
+    status_bar_lines
+        .push(Span::from(format!(" [{}]", &model.profile_name)).fg(model.theme.secondary));
 
     if model.auto_refresh {
         if model.num_services_marked_for_refresh == 0 {
             status_bar_lines
-                .push(Span::from(" auto refresh on for all services").fg(MESSAGE_COLOR));
+                .push(Span::from(" auto refresh on for all services").fg(model.theme.message));
         } else if model.num_services_marked_for_refresh == 1 {
-            status_bar_lines.push(Span::from(" auto refresh on for 1 service").fg(MESSAGE_COLOR));
+            status_bar_lines
+                .push(Span::from(" auto refresh on for 1 service").fg(model.theme.message));
         } else {
             status_bar_lines.push(
                 Span::from(format!(
                     " auto refresh on for {} services",
                     model.num_services_marked_for_refresh
                 ))
-                .fg(MESSAGE_COLOR),
+                .fg(model.theme.message),
             );
         }
     }
@@ -128,13 +370,44 @@ fn render_status_line(model: &Model, frame: &mut Frame, rect: Rect) {
         )));
     }
 
+    if model.service_items.sort != ServicesSort::Name {
+        status_bar_lines.push(
+            Span::from(format!(" [sort: {}]", model.service_items.sort)).fg(model.theme.secondary),
+        );
+    }
+
+    if model.service_items.layout != ServicesLayout::Flat {
+        status_bar_lines.push(
+            Span::from(format!(" [view: {}]", model.service_items.layout))
+                .fg(model.theme.secondary),
+        );
+    }
+
+    if let Some(filter) = &model.service_items.filter {
+        status_bar_lines.push(Span::from(format!(" [where: {filter}]")).fg(model.theme.secondary));
+    }
+
+    match (&model.service_items.search, model.search_input_active) {
+        (search, true) => status_bar_lines.push(
+            Span::from(format!(" /{}_", search.as_deref().unwrap_or_default()))
+                .fg(model.theme.primary),
+        ),
+        (Some(search), false) => status_bar_lines
+            .push(Span::from(format!(" [search: {search}]")).fg(model.theme.secondary)),
+        (None, false) => {}
+    }
+
+    if let Some(input) = &model.filter_input {
+        status_bar_lines.push(Span::from(format!(" filter: {input}_")).fg(model.theme.primary));
+    }
+
     if let Some(msg) = &model.user_message {
         let span = match msg {
             UserMessage::Info(m, _) => {
-                Span::styled(format!(" {m}"), Style::new().fg(INFO_MESSAGE_COLOR))
+                Span::styled(format!(" {m}"), Style::new().fg(model.theme.info_message))
             }
             UserMessage::Error(m, _) => {
-                Span::styled(format!(" {m}"), Style::new().fg(ERROR_MESSAGE_COLOR))
+                Span::styled(format!(" {m}"), Style::new().fg(model.theme.error_message))
             }
         };
 
@@ -149,27 +422,40 @@ fn render_status_line(model: &Model, frame: &mut Frame, rect: Rect) {
 }
 
 fn render_services_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
-    let title = " services ";
+    let title = match (&model.service_items.filter, &model.service_items.search) {
+        (None, None) => " services ".to_string(),
+        _ => format!(
+            " services ({}/{}) ",
+            model.service_items.visible.len(),
+            model.service_items.items.len()
+        ),
+    };
 
     let (border_color, title_color, highlight_color) = if model.active_pane == Pane::ServicesList {
-        (PRIMARY_COLOR, PRIMARY_COLOR, PRIMARY_COLOR)
+        (
+            model.theme.primary,
+            model.theme.primary,
+            model.theme.primary,
+        )
     } else {
         (
-            INACTIVE_PANE_BORDER_COLOR,
-            INACTIVE_PANE_TITLE_BG_COLOR,
-            INACTIVE_PANE_SELECTED_COLOR,
+            model.theme.inactive_pane_border,
+            model.theme.inactive_pane_title_bg,
+            model.theme.inactive_pane_selected,
         )
     };
 
     let items: Vec<ListItem> = model
         .service_items
-        .items
-        .iter()
-        .map(ListItem::from)
-        .collect();
+        .list_items(&model.theme, model.redact_mode);
 
     if items.is_empty() {
-        let details = Paragraph::new("services will appear here")
+        let placeholder = if model.service_items.items.is_empty() {
+            "services will appear here"
+        } else {
+            "no services match the filter/search"
+        };
+        let details = Paragraph::new(placeholder)
             .block(
                 Block::bordered()
                     .border_style(Style::default().fg(border_color))
@@ -177,12 +463,12 @@ fn render_services_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
                         Style::new()
                             .bold()
                             .bg(title_color)
-                            .fg(SECTION_TITLE_FG_COLOR),
+                            .fg(model.theme.section_title_fg),
                     )
                     .title(" tasks ")
                     .padding(Padding::new(1, 0, 1, 0)),
             )
-            .style(Style::new().white().on_black())
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
             .alignment(Alignment::Left);
 
         frame.render_widget(&details, rect);
@@ -199,11 +485,11 @@ fn render_services_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
                     Style::new()
                         .bold()
                         .bg(title_color)
-                        .fg(SECTION_TITLE_FG_COLOR),
+                        .fg(model.theme.section_title_fg),
                 )
                 .title(title),
         )
-        .style(Style::new().white())
+        .style(Style::new().fg(model.theme.text))
         .highlight_symbol("> ")
         .repeat_highlight_symbol(true)
         .highlight_style(Style::new().fg(highlight_color))
@@ -212,14 +498,18 @@ fn render_services_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
     frame.render_stateful_widget(list, rect, &mut model.service_items.state);
 }
 
-fn render_service_details(model: &Model, frame: &mut Frame, rect: Rect) {
+fn render_service_details(model: &mut Model, frame: &mut Frame, rect: Rect) {
     let color = if model.active_pane == Pane::ServiceDetails {
-        PRIMARY_COLOR
+        model.theme.primary
     } else {
-        INACTIVE_PANE_BORDER_COLOR
+        model.theme.inactive_pane_border
     };
 
-    let maybe_selected = model.service_items.state.selected();
+    if render_object_tree(model, frame, rect, Pane::ServiceDetails, color) {
+        return;
+    }
+
+    let maybe_selected = model.service_items.selected_index();
 
     if let Some(selected) = maybe_selected {
         let maybe_service_item = model.service_items.items.get(selected);
@@ -227,7 +517,7 @@ fn render_service_details(model: &Model, frame: &mut Frame, rect: Rect) {
             let (colorr, title) = if service_item.service.is_ok() {
                 (color, "details")
             } else {
-                (ERROR_MESSAGE_COLOR, "error")
+                (model.theme.error_message, "error")
             };
             let details = match &service_item.service {
                 Ok(service) => format!(
@@ -239,7 +529,9 @@ Status           {}
 Desired count    {}
 Running count    {}
 Pending count    {}
-"#,
+Failed tasks     {}
+Last deployed    {}
+{}"#,
                     &service.name,
                     &service.cluster_keys,
                     if model.redact_mode {
@@ -251,6 +543,12 @@ Pending count    {}
                     &service.desired_count,
                     &service.running_count,
                     &service.pending_count,
+                    &service.failed_count,
+                    service
+                        .last_deployed_at
+                        .map(time_since)
+                        .unwrap_or(UNKNOWN_VALUE.to_string()),
+                    service_metadata_details(&service.metadata),
                 ),
 
                 Err(err) => format!(
@@ -260,6 +558,7 @@ Error            {}
                     err.error
                 ),
             };
+            let scroll = details_scroll(model, Pane::ServiceDetails, &details);
             let details = Paragraph::new(details)
                 .block(
                     Block::bordered()
@@ -267,21 +566,28 @@ Error            {}
                         .title(title)
                         .padding(Padding::new(1, 0, 0, 0)),
                 )
-                .style(Style::new().white().on_black())
+                .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+                .scroll(scroll)
                 .wrap(Wrap { trim: false })
                 .alignment(Alignment::Left);
 
             frame.render_widget(&details, rect);
         };
     } else {
-        let details = Paragraph::new("")
+        let details = match model.service_items.selected_row() {
+            Some(ServiceRow::Group { name, members, .. }) => group_details(model, name, members),
+            _ => String::new(),
+        };
+        let scroll = details_scroll(model, Pane::ServiceDetails, &details);
+        let details = Paragraph::new(details)
             .block(
                 Block::bordered()
                     .border_style(Style::default().fg(color))
                     .title("details")
                     .padding(Padding::new(1, 0, 0, 0)),
             )
-            .style(Style::new().white().on_black())
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+            .scroll(scroll)
             .wrap(Wrap { trim: false })
             .alignment(Alignment::Left);
 
@@ -289,11 +595,163 @@ Error            {}
     }
 }
 
+fn group_details(model: &Model, name: &str, members: &[usize]) -> String {
+    let count = |health: ServiceHealth| {
+        members
+            .iter()
+            .filter_map(|i| model.service_items.items.get(*i))
+            .filter(|i| i.health() == health)
+            .count()
+    };
+
+    format!(
+        r#"
+Group            {}
+Services         {}
+Healthy          {}
+In progress      {}
+Failing          {}
+Errored          {}
+"#,
+        if model.redact_mode && model.service_items.layout == ServicesLayout::ByCluster {
+            REDACTED
+        } else {
+            name
+        },
+        members.len(),
+        count(ServiceHealth::Healthy),
+        count(ServiceHealth::InProgress),
+        count(ServiceHealth::Failing),
+        count(ServiceHealth::Errored),
+    )
+}
+
+/// Renders all the fields of the item shown in a details pane, if the details panes are showing
+/// those; returns whether it did.
+fn render_object_tree(
+    model: &mut Model,
+    frame: &mut Frame,
+    rect: Rect,
+    pane: Pane,
+    border_color: Color,
+) -> bool {
+    let Some(tree) = model.object_tree(pane) else {
+        return false;
+    };
+
+    let highlight_color = if model.active_pane == pane {
+        model.theme.primary
+    } else {
+        model.theme.inactive_pane_selected
+    };
+    let secondary = model.theme.secondary;
+    let default_view = ObjectView::default();
+    let view = model.object_views.get(&pane).unwrap_or(&default_view);
+    let mut state = view.state;
+
+    let items = view
+        .rows(tree)
+        .into_iter()
+        .map(|(_, row)| {
+            let marker = match (row.node.children.is_empty(), row.expanded) {
+                (true, _) => "  ",
+                (false, true) => "▾ ",
+                (false, false) => "▸ ",
+            };
+            let mut spans = vec![
+                Span::raw(format!("{}{marker}", "  ".repeat(row.depth))),
+                Span::styled(row.node.name.as_str(), Style::new().fg(secondary)),
+            ];
+            if let Some(value) = &row.node.value {
+                spans.push(Span::raw(format!(": {value}")));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect::<Vec<_>>();
+
+    let list = List::new(items)
+        .block(
+            Block::bordered()
+                .border_style(Style::default().fg(border_color))
+                .title("details (full)"),
+        )
+        .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+        .highlight_symbol("> ")
+        .highlight_style(Style::new().fg(highlight_color))
+        .direction(ListDirection::TopToBottom);
+
+    frame.render_stateful_widget(list, rect, &mut state);
+    model.object_views.entry(pane).or_default().state = state;
+
+    true
+}
+
+/// Returns how far down to scroll the contents of a pane, making sure it's not been scrolled past
+/// its last line.
+fn details_scroll(model: &mut Model, pane: Pane, contents: &str) -> (u16, u16) {
+    let Some(scroll) = model.details_scroll.get_mut(&pane) else {
+        return (0, 0);
+    };
+
+    let last_line = contents.lines().count().saturating_sub(1);
+    *scroll = (*scroll).min(u16::try_from(last_line).unwrap_or(u16::MAX));
+
+    (*scroll, 0)
+}
+
+fn time_since(timestamp: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let secs = now.saturating_sub(timestamp).max(0);
+
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn service_metadata_details(metadata: &ServiceMetadata) -> String {
+    let mut lines = Vec::new();
+
+    if let Some(alias) = &metadata.alias {
+        lines.push(format!("Alias            {alias}"));
+    }
+    if let Some(owner) = &metadata.owner {
+        lines.push(format!("Owner            {owner}"));
+    }
+    if let Some(runbook) = &metadata.runbook {
+        lines.push(format!("Runbook          {runbook}"));
+    }
+    if !metadata.tags.is_empty() {
+        let tags = metadata
+            .tags
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("Tags             {tags}"));
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    format!("\n{}\n", lines.join("\n"))
+}
+
 fn render_tasks_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
     let (color, highlight_color) = if model.active_pane == Pane::TasksList {
-        (PRIMARY_COLOR, PRIMARY_COLOR)
+        (model.theme.primary, model.theme.primary)
     } else {
-        (INACTIVE_PANE_BORDER_COLOR, INACTIVE_PANE_SELECTED_COLOR)
+        (
+            model.theme.inactive_pane_border,
+            model.theme.inactive_pane_selected,
+        )
     };
 
     match &mut model.task_items {
@@ -305,10 +763,15 @@ fn render_tasks_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
                     Block::bordered()
                         .border_style(Style::default().fg(color))
                         .padding(Padding::new(0, 0, 1, 0))
-                        .title_style(Style::new().bold().bg(color).fg(SECTION_TITLE_FG_COLOR))
+                        .title_style(
+                            Style::new()
+                                .bold()
+                                .bg(color)
+                                .fg(model.theme.section_title_fg),
+                        )
                         .title(" tasks "),
                 )
-                .style(Style::new().white())
+                .style(Style::new().fg(model.theme.text))
                 .highlight_symbol("> ")
                 .repeat_highlight_symbol(true)
                 .highlight_style(Style::new().fg(highlight_color))
@@ -321,11 +784,16 @@ fn render_tasks_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
                 .block(
                     Block::bordered()
                         .border_style(Style::default().fg(color))
-                        .title_style(Style::new().bold().bg(color).fg(SECTION_TITLE_FG_COLOR))
+                        .title_style(
+                            Style::new()
+                                .bold()
+                                .bg(color)
+                                .fg(model.theme.section_title_fg),
+                        )
                         .title(" tasks ")
                         .padding(Padding::new(1, 0, 1, 0)),
                 )
-                .style(Style::new().white().on_black())
+                .style(Style::new().fg(model.theme.text).bg(model.theme.background))
                 .alignment(Alignment::Left);
 
             frame.render_widget(&details, rect);
@@ -333,13 +801,17 @@ fn render_tasks_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
     }
 }
 
-fn render_task_details(model: &Model, frame: &mut Frame, rect: Rect) {
+fn render_task_details(model: &mut Model, frame: &mut Frame, rect: Rect) {
     let color = if model.active_pane == Pane::TaskDetails {
-        PRIMARY_COLOR
+        model.theme.primary
     } else {
-        INACTIVE_PANE_BORDER_COLOR
+        model.theme.inactive_pane_border
     };
 
+    if render_object_tree(model, frame, rect, Pane::TaskDetails, color) {
+        return;
+    }
+
     let selected_task = match model.get_selected_task() {
         Some(t) => t,
         None => {
@@ -350,7 +822,7 @@ fn render_task_details(model: &Model, frame: &mut Frame, rect: Rect) {
                         .title("details")
                         .padding(Padding::new(1, 0, 0, 0)),
                 )
-                .style(Style::new().white().on_black())
+                .style(Style::new().fg(model.theme.text).bg(model.theme.background))
                 .wrap(Wrap { trim: false })
                 .alignment(Alignment::Left);
 
@@ -382,6 +854,7 @@ Last Status      {}
         selected_task.last_status().unwrap_or(UNKNOWN_VALUE),
     );
 
+    let scroll = details_scroll(model, Pane::TaskDetails, &details);
     let paragraph = Paragraph::new(details)
         .block(
             Block::bordered()
@@ -389,7 +862,8 @@ Last Status      {}
                 .title("details")
                 .padding(Padding::new(1, 0, 0, 0)),
         )
-        .style(Style::new().white().on_black())
+        .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+        .scroll(scroll)
         .wrap(Wrap { trim: false })
         .alignment(Alignment::Left);
 
@@ -398,9 +872,12 @@ Last Status      {}
 
 fn render_containers_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
     let (color, highlight_color) = if model.active_pane == Pane::ContainersList {
-        (PRIMARY_COLOR, PRIMARY_COLOR)
+        (model.theme.primary, model.theme.primary)
     } else {
-        (INACTIVE_PANE_BORDER_COLOR, INACTIVE_PANE_SELECTED_COLOR)
+        (
+            model.theme.inactive_pane_border,
+            model.theme.inactive_pane_selected,
+        )
     };
 
     if model.task_items.is_none() {
@@ -408,11 +885,16 @@ fn render_containers_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
             .block(
                 Block::bordered()
                     .border_style(Style::default().fg(color))
-                    .title_style(Style::new().bold().bg(color).fg(SECTION_TITLE_FG_COLOR))
+                    .title_style(
+                        Style::new()
+                            .bold()
+                            .bg(color)
+                            .fg(model.theme.section_title_fg),
+                    )
                     .title(" containers ")
                     .padding(Padding::new(1, 0, 1, 0)),
             )
-            .style(Style::new().white().on_black())
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
             .alignment(Alignment::Left);
 
         frame.render_widget(&details, rect);
@@ -429,10 +911,15 @@ fn render_containers_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
             Block::bordered()
                 .border_style(Style::default().fg(color))
                 .padding(Padding::new(0, 0, 1, 0))
-                .title_style(Style::new().bold().bg(color).fg(SECTION_TITLE_FG_COLOR))
+                .title_style(
+                    Style::new()
+                        .bold()
+                        .bg(color)
+                        .fg(model.theme.section_title_fg),
+                )
                 .title(" containers "),
         )
-        .style(Style::new().white())
+        .style(Style::new().fg(model.theme.text))
         .highlight_symbol("> ")
         .repeat_highlight_symbol(true)
         .highlight_style(Style::new().fg(highlight_color))
@@ -442,11 +929,11 @@ fn render_containers_list(model: &mut Model, frame: &mut Frame, rect: Rect) {
     }
 }
 
-fn render_container_details(model: &Model, frame: &mut Frame, rect: Rect) {
+fn render_container_details(model: &mut Model, frame: &mut Frame, rect: Rect) {
     let border_color = if model.active_pane == Pane::ContainerDetails {
-        PRIMARY_COLOR
+        model.theme.primary
     } else {
-        INACTIVE_PANE_BORDER_COLOR
+        model.theme.inactive_pane_border
     };
 
     if model.task_items.is_none() {
@@ -457,7 +944,7 @@ fn render_container_details(model: &Model, frame: &mut Frame, rect: Rect) {
                     .title("details")
                     .padding(Padding::new(1, 0, 0, 0)),
             )
-            .style(Style::new().white().on_black())
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
             .wrap(Wrap { trim: false })
             .alignment(Alignment::Left);
 
@@ -466,6 +953,10 @@ fn render_container_details(model: &Model, frame: &mut Frame, rect: Rect) {
         return;
     }
 
+    if render_object_tree(model, frame, rect, Pane::ContainerDetails, border_color) {
+        return;
+    }
+
     let maybe_selected = model.container_items.state.selected();
 
     if let Some(selected) = maybe_selected {
@@ -496,6 +987,7 @@ Health Status    {}
                     .map(|s| s.as_str())
                     .unwrap_or(UNKNOWN_VALUE),
             );
+            let scroll = details_scroll(model, Pane::ContainerDetails, &details);
             let details = Paragraph::new(details)
                 .block(
                     Block::bordered()
@@ -503,7 +995,8 @@ Health Status    {}
                         .title("details")
                         .padding(Padding::new(1, 0, 0, 0)),
                 )
-                .style(Style::new().white().on_black())
+                .style(Style::new().fg(model.theme.text).bg(model.theme.background))
+                .scroll(scroll)
                 .wrap(Wrap { trim: false })
                 .alignment(Alignment::Left);
 
@@ -517,7 +1010,7 @@ Health Status    {}
                     .title("details")
                     .padding(Padding::new(1, 0, 0, 0)),
             )
-            .style(Style::new().white().on_black())
+            .style(Style::new().fg(model.theme.text).bg(model.theme.background))
             .wrap(Wrap { trim: false })
             .alignment(Alignment::Left);
 
@@ -551,6 +1044,15 @@ fn render_list_view(model: &mut Model, frame: &mut Frame) {
         .constraints(vec![Constraint::Percentage(30), Constraint::Percentage(70)])
         .split(layout[2]);
 
+    model.pane_areas = vec![
+        (Pane::ServicesList, service_layout[0]),
+        (Pane::ServiceDetails, service_layout[1]),
+        (Pane::TasksList, task_layout[0]),
+        (Pane::TaskDetails, task_layout[1]),
+        (Pane::ContainersList, container_layout[0]),
+        (Pane::ContainerDetails, container_layout[1]),
+    ];
+
     render_services_list(model, frame, service_layout[0]);
     render_service_details(model, frame, service_layout[1]);
     render_tasks_list(model, frame, task_layout[0]);
@@ -1,15 +1,19 @@
 use crate::aws::get_sdk_config;
 use crate::cmds::get_profile_path;
-use crate::config::{ClusterConfig, Config, ConfigSource};
+use crate::config::{
+    ClientKey, ClusterConfig, Config, NamedFilters, ResolveClusterError, TagFilter,
+};
+use crate::domain::Profile;
 use aws_sdk_ecs::Client as ECSClient;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Error as IOError;
 use std::path::Path;
 use std::path::PathBuf;
 use toml::de::Error as TomlError;
 
 const XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+const XDG_DATA_HOME: &str = "XDG_DATA_HOME";
 
 #[derive(thiserror::Error, Debug)]
 pub enum ConfigDirError {
@@ -42,6 +46,182 @@ pub fn get_config_dir() -> Result<PathBuf, ConfigDirError> {
     Ok(config_dir)
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum DataDirError {
+    #[cfg(target_family = "unix")]
+    #[error("{XDG_DATA_HOME} is not an absolute path")]
+    XDGDataHomeNotAbsolute,
+    #[error("couldn't get your data directory")]
+    CouldntGetDataDir,
+}
+
+pub fn get_data_dir() -> Result<PathBuf, DataDirError> {
+    #[cfg(target_family = "unix")]
+    let data_dir = match std::env::var_os(XDG_DATA_HOME).map(PathBuf::from) {
+        Some(p) => {
+            if p.is_absolute() {
+                Ok(p)
+            } else {
+                Err(DataDirError::XDGDataHomeNotAbsolute)
+            }
+        }
+        None => match dirs::data_dir() {
+            Some(p) => Ok(p),
+            None => Err(DataDirError::CouldntGetDataDir),
+        },
+    }?;
+
+    #[cfg(not(target_family = "unix"))]
+    let data_dir = dirs::data_dir().ok_or(DataDirError::CouldntGetDataDir)?;
+
+    Ok(data_dir)
+}
+
+const ENV_VAR_PROFILE: &str = "ECSCOPE_PROFILE";
+const PROJECT_PROFILE_FILE: &str = ".ecscope.toml";
+const PROJECT_PROFILES_DIR: &str = "ecscope";
+
+/// Where the profile being used was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileSource {
+    /// Passed explicitly via --profile-file
+    File(PathBuf),
+    /// A `.ecscope.toml` found in the current directory or one of its ancestors
+    ProjectFile(PathBuf),
+    /// A profile in an `ecscope/` directory found in the current directory or one of its ancestors
+    ProjectDir { name: String, path: PathBuf },
+    /// A profile in ecscope's config directory
+    ConfigDir { name: String, path: PathBuf },
+}
+
+impl ProfileSource {
+    pub fn path(&self) -> &Path {
+        match self {
+            ProfileSource::File(path) => path,
+            ProfileSource::ProjectFile(path) => path,
+            ProfileSource::ProjectDir { path, .. } => path,
+            ProfileSource::ConfigDir { path, .. } => path,
+        }
+    }
+
+    /// Name of the profile as shown to the user.
+    pub fn display_name(&self) -> String {
+        match self {
+            ProfileSource::File(path) | ProfileSource::ProjectFile(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            ProfileSource::ProjectDir { name, .. } | ProfileSource::ConfigDir { name, .. } => {
+                name.clone()
+            }
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            ProfileSource::File(path) | ProfileSource::ProjectFile(path) => {
+                path.to_string_lossy().to_string()
+            }
+            ProfileSource::ProjectDir { name, .. } | ProfileSource::ConfigDir { name, .. } => {
+                name.clone()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ProfileSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileSource::File(path) => write!(f, "profile file ({})", path.to_string_lossy()),
+            ProfileSource::ProjectFile(path) => {
+                write!(f, "project file ({})", path.to_string_lossy())
+            }
+            ProfileSource::ProjectDir { path, .. } => {
+                write!(f, "project directory ({})", path.to_string_lossy())
+            }
+            ProfileSource::ConfigDir { path, .. } => {
+                write!(f, "config directory ({})", path.to_string_lossy())
+            }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LocateProfileError {
+    #[error(
+        "no profile provided; pass one as an argument, set ECSCOPE_PROFILE, use --profile-file, or add a {PROJECT_PROFILE_FILE} to your project"
+    )]
+    NoProfileProvided,
+}
+
+/// Finds profiles by name, preferring the ones in a project's `ecscope/` directory over the ones
+/// in ecscope's config directory.
+pub struct ProfileLocator {
+    config_dir: PathBuf,
+    cwd: Option<PathBuf>,
+    profile_from_env: Option<String>,
+}
+
+impl ProfileLocator {
+    pub fn new(config_dir: &Path, cwd: Option<PathBuf>) -> Self {
+        let profile_from_env = std::env::var(ENV_VAR_PROFILE)
+            .ok()
+            .filter(|p| !p.trim().is_empty());
+
+        Self {
+            config_dir: config_dir.to_path_buf(),
+            cwd,
+            profile_from_env,
+        }
+    }
+
+    /// Determines the profile to use. In order of precedence: an explicit profile file, a profile
+    /// name passed as an argument, a profile name set via ECSCOPE_PROFILE, and finally a
+    /// `.ecscope.toml` in the current directory or one of its ancestors.
+    pub fn locate(
+        &self,
+        profile_name: Option<&str>,
+        profile_file: Option<&Path>,
+    ) -> Result<ProfileSource, LocateProfileError> {
+        if let Some(path) = profile_file {
+            return Ok(ProfileSource::File(path.to_path_buf()));
+        }
+
+        match profile_name.or(self.profile_from_env.as_deref()) {
+            Some(name) => Ok(self.find(name)),
+            None => self
+                .find_upwards(|dir| dir.join(PROJECT_PROFILE_FILE))
+                .map(ProfileSource::ProjectFile)
+                .ok_or(LocateProfileError::NoProfileProvided),
+        }
+    }
+
+    pub fn find(&self, profile_name: &str) -> ProfileSource {
+        let file_name = format!("{profile_name}.toml");
+        match self.find_upwards(|dir| dir.join(PROJECT_PROFILES_DIR).join(&file_name)) {
+            Some(path) => ProfileSource::ProjectDir {
+                name: profile_name.to_string(),
+                path,
+            },
+            None => ProfileSource::ConfigDir {
+                name: profile_name.to_string(),
+                path: get_profile_path(&self.config_dir, profile_name),
+            },
+        }
+    }
+
+    fn find_upwards<F>(&self, candidate: F) -> Option<PathBuf>
+    where
+        F: Fn(&Path) -> PathBuf,
+    {
+        self.cwd
+            .as_deref()?
+            .ancestors()
+            .map(candidate)
+            .find(|path| path.is_file())
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GetClustersError {
     #[error("profile doesn't exist")]
@@ -50,60 +230,411 @@ pub enum GetClustersError {
     CouldntReadProfileFile(IOError),
     #[error("config file is invalid: {0}")]
     ConfigFileInvalid(#[from] TomlError),
+    #[error(r#"profile "{0}" (included by "{1}") doesn't exist"#)]
+    IncludedProfileDoesntExist(String, String),
+    #[error(r#"profile "{0}" (included by "{1}") has an invalid name; {2}"#)]
+    IncludedProfileNameInvalid(String, String, String),
+    #[error(r#"included profile "{0}" is invalid: {1}"#)]
+    IncludedProfileInvalid(String, TomlError),
+    #[error("profiles include each other in a cycle: {0}")]
+    CircularInclude(String),
+    #[error(r#"profile "{0}" is invalid: {1}"#)]
+    ClusterConfigInvalid(String, ResolveClusterError),
 }
 
 pub async fn get_clusters(
-    config_dir: &Path,
-    profile_name: String,
+    locator: &ProfileLocator,
+    profile_source: &ProfileSource,
     service_name_filter: Option<Regex>,
     key_filter: Option<Regex>,
-) -> Result<Option<(HashMap<ConfigSource, ECSClient>, Vec<ClusterConfig>)>, GetClustersError> {
-    let profile_path = get_profile_path(config_dir, &profile_name);
-
-    if !profile_path.exists() {
-        return Err(GetClustersError::ProfileDoesntExist);
-    }
-    let config_bytes =
-        std::fs::read_to_string(profile_path).map_err(GetClustersError::CouldntReadProfileFile)?;
-    let app_config: Config = toml::from_str(&config_bytes)?;
+    tag_filters: &[TagFilter],
+) -> Result<
+    Option<(
+        HashMap<ClientKey, ECSClient>,
+        Vec<ClusterConfig>,
+        NamedFilters,
+    )>,
+    GetClustersError,
+> {
+    let (all_clusters, named_filters) = read_profile(locator, profile_source)?;
 
-    let mut clients_map: HashMap<ConfigSource, ECSClient> = HashMap::new();
+    let mut clients_map: HashMap<ClientKey, ECSClient> = HashMap::new();
 
     let clusters = match (service_name_filter, key_filter) {
-        (None, None) => app_config.clusters,
-        (None, Some(k)) => app_config
-            .clusters
+        (None, None) => all_clusters,
+        (None, Some(k)) => all_clusters
             .into_iter()
             .filter_map(|c| c.filter_by_cluster_key(&k))
             .collect::<Vec<_>>(),
-        (Some(s), None) => app_config
-            .clusters
+        (Some(s), None) => all_clusters
             .into_iter()
             .filter_map(|c| c.filter_by_service_name(&s))
             .collect::<Vec<_>>(),
-        (Some(s), Some(k)) => app_config
-            .clusters
+        (Some(s), Some(k)) => all_clusters
             .into_iter()
             .filter_map(|c| c.filter_by_service_name(&s))
             .filter_map(|c| c.filter_by_cluster_key(&k))
             .collect::<Vec<_>>(),
     };
 
+    let clusters = if tag_filters.is_empty() {
+        clusters
+    } else {
+        clusters
+            .into_iter()
+            .filter_map(|c| c.filter_by_tags(tag_filters))
+            .collect::<Vec<_>>()
+    };
+
     if clusters.is_empty() {
         return Ok(None);
     }
 
     for cluster in &clusters {
-        let config = &cluster.config_source;
-        match clients_map.get(&cluster.config_source) {
+        let client_key = cluster.client_key();
+        match clients_map.get(&client_key) {
             Some(_) => {}
             None => {
-                let sdk_config = get_sdk_config(config).await;
+                let sdk_config =
+                    get_sdk_config(&client_key.config_source, client_key.region.as_deref()).await;
                 let client = aws_sdk_ecs::Client::new(&sdk_config);
-                clients_map.insert(cluster.config_source.clone(), client);
+                clients_map.insert(client_key, client);
             }
         }
     }
 
-    Ok(Some((clients_map, clusters)))
+    Ok(Some((clients_map, clusters, named_filters)))
+}
+
+/// Reads a profile, and returns its clusters with defaults applied, followed by the clusters of
+/// every profile it includes (transitively), along with the named filters defined across all of
+/// them. Included profiles are looked up by name the same way the profile argument is. A profile
+/// that's included more than once only contributes its clusters the first time.
+pub fn read_profile(
+    locator: &ProfileLocator,
+    profile_source: &ProfileSource,
+) -> Result<(Vec<ClusterConfig>, NamedFilters), GetClustersError> {
+    let mut clusters = Vec::new();
+    let mut named_filters = NamedFilters::new();
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+
+    collect_profile(
+        locator,
+        profile_source,
+        &mut chain,
+        &mut seen,
+        &mut clusters,
+        &mut named_filters,
+    )?;
+
+    Ok((clusters, named_filters))
+}
+
+fn collect_profile(
+    locator: &ProfileLocator,
+    profile_source: &ProfileSource,
+    chain: &mut Vec<String>,
+    seen: &mut HashSet<PathBuf>,
+    clusters: &mut Vec<ClusterConfig>,
+    named_filters: &mut NamedFilters,
+) -> Result<(), GetClustersError> {
+    let profile_label = profile_source.label();
+
+    if chain.contains(&profile_label) {
+        let cycle = chain
+            .iter()
+            .skip_while(|p| **p != profile_label)
+            .map(String::as_str)
+            .chain(std::iter::once(profile_label.as_str()))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(GetClustersError::CircularInclude(cycle));
+    }
+
+    let profile_path = profile_source.path();
+    if !seen.insert(profile_path.to_path_buf()) {
+        return Ok(());
+    }
+
+    let including_profile = chain.last().cloned();
+
+    if !profile_path.exists() {
+        return Err(match including_profile {
+            Some(parent) => GetClustersError::IncludedProfileDoesntExist(profile_label, parent),
+            None => GetClustersError::ProfileDoesntExist,
+        });
+    }
+
+    let config_bytes =
+        std::fs::read_to_string(profile_path).map_err(GetClustersError::CouldntReadProfileFile)?;
+    let config: Config = toml::from_str(&config_bytes).map_err(|e| match including_profile {
+        Some(_) => GetClustersError::IncludedProfileInvalid(profile_label.clone(), e),
+        None => GetClustersError::ConfigFileInvalid(e),
+    })?;
+
+    for entry in config.clusters {
+        let cluster = entry
+            .resolve(config.defaults.as_ref())
+            .map_err(|e| GetClustersError::ClusterConfigInvalid(profile_label.clone(), e))?;
+        clusters.push(cluster);
+    }
+
+    // filters defined closer to the profile being used take precedence over included ones
+    for (name, expr) in config.filters {
+        named_filters.entry(name).or_insert(expr);
+    }
+
+    for included in &config.include {
+        // names end up in paths, so they can't be allowed to point outside the profile directories
+        Profile::try_from(included.as_str()).map_err(|e| {
+            GetClustersError::IncludedProfileNameInvalid(included.clone(), profile_label.clone(), e)
+        })?;
+    }
+
+    chain.push(profile_label);
+    for included in &config.include {
+        collect_profile(
+            locator,
+            &locator.find(included),
+            chain,
+            seen,
+            clusters,
+            named_filters,
+        )?;
+    }
+    chain.pop();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigSource;
+    use tempfile::{TempDir, tempdir};
+
+    fn write_profile(dir: &TempDir, name: &str, contents: &str) {
+        let path = get_profile_path(dir.path(), name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("profiles directory should've been created");
+        }
+        std::fs::write(path, contents).expect("profile should've been written");
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn included_profiles_contribute_their_clusters() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        write_profile(
+            &dir,
+            "payments",
+            r#"
+[defaults]
+config_source = "profile:payments"
+
+[[clusters]]
+keys = ["prod"]
+arn = "arn:aws:ecs:eu-central-1:111111111111:cluster/payments"
+services = ["payments-api"]
+"#,
+        );
+        write_profile(
+            &dir,
+            "search",
+            r#"
+include = ["payments"]
+
+[[clusters]]
+keys = ["prod"]
+arn = "arn:aws:ecs:eu-central-1:222222222222:cluster/search"
+services = ["search-api"]
+config_source = "env"
+"#,
+        );
+        write_profile(&dir, "everything", r#"include = ["payments", "search"]"#);
+        let locator = ProfileLocator::new(dir.path(), None);
+
+        // WHEN
+        let (clusters, _) = read_profile(&locator, &locator.find("everything"))
+            .expect("clusters should've been read");
+
+        // THEN
+        let arns = clusters.iter().map(|c| c.arn.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            arns,
+            vec![
+                "arn:aws:ecs:eu-central-1:111111111111:cluster/payments",
+                "arn:aws:ecs:eu-central-1:222222222222:cluster/search",
+            ]
+        );
+        assert_eq!(
+            clusters[0].config_source,
+            ConfigSource::Profile {
+                name: "payments".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn named_filters_from_the_including_profile_take_precedence() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        write_profile(
+            &dir,
+            "shared",
+            r#"
+[filters]
+failing = "failed>0"
+degraded = "running<desired"
+"#,
+        );
+        write_profile(
+            &dir,
+            "prod",
+            r#"
+include = ["shared"]
+
+[filters]
+failing = "key=prod and failed>0"
+"#,
+        );
+        let locator = ProfileLocator::new(dir.path(), None);
+
+        // WHEN
+        let (_, named_filters) =
+            read_profile(&locator, &locator.find("prod")).expect("profile should've been read");
+
+        // THEN
+        assert_eq!(
+            named_filters,
+            NamedFilters::from([
+                ("degraded".to_string(), "running<desired".to_string()),
+                ("failing".to_string(), "key=prod and failed>0".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn profiles_in_a_project_directory_take_precedence() {
+        // GIVEN
+        let config_dir = tempdir().expect("temporary directory should've been created");
+        let project_dir = tempdir().expect("temporary directory should've been created");
+        write_profile(&config_dir, "payments", "");
+        let project_profile = project_dir.path().join("ecscope").join("payments.toml");
+        std::fs::create_dir_all(project_dir.path().join("ecscope"))
+            .expect("project profiles directory should've been created");
+        std::fs::write(&project_profile, "").expect("profile should've been written");
+        let cwd = project_dir.path().join("src").join("nested");
+        std::fs::create_dir_all(&cwd).expect("nested directory should've been created");
+        let locator = ProfileLocator::new(config_dir.path(), Some(cwd));
+
+        // WHEN
+        let from_project = locator.find("payments");
+        let from_config_dir = locator.find("search");
+
+        // THEN
+        assert_eq!(
+            from_project,
+            ProfileSource::ProjectDir {
+                name: "payments".to_string(),
+                path: project_profile,
+            }
+        );
+        assert_eq!(
+            from_config_dir,
+            ProfileSource::ConfigDir {
+                name: "search".to_string(),
+                path: get_profile_path(config_dir.path(), "search"),
+            }
+        );
+    }
+
+    #[test]
+    fn project_file_is_used_when_no_profile_is_provided() {
+        // GIVEN
+        let config_dir = tempdir().expect("temporary directory should've been created");
+        let project_dir = tempdir().expect("temporary directory should've been created");
+        let project_file = project_dir.path().join(".ecscope.toml");
+        std::fs::write(&project_file, "").expect("project file should've been written");
+        let cwd = project_dir.path().join("nested");
+        std::fs::create_dir_all(&cwd).expect("nested directory should've been created");
+        let locator = ProfileLocator {
+            config_dir: config_dir.path().to_path_buf(),
+            cwd: Some(cwd),
+            profile_from_env: None,
+        };
+
+        // WHEN
+        let result = locator.locate(None, None);
+
+        // THEN
+        assert_eq!(
+            result.expect("profile should've been located"),
+            ProfileSource::ProjectFile(project_file)
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn circular_includes_are_rejected() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        write_profile(&dir, "a", r#"include = ["b"]"#);
+        write_profile(&dir, "b", r#"include = ["a"]"#);
+        let locator = ProfileLocator::new(dir.path(), None);
+
+        // WHEN
+        let result = read_profile(&locator, &locator.find("a"));
+
+        // THEN
+        match result {
+            Err(GetClustersError::CircularInclude(cycle)) => assert_eq!(cycle, "a -> b -> a"),
+            other => panic!("expected a circular include error, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn including_a_missing_profile_fails() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        write_profile(&dir, "a", r#"include = ["missing"]"#);
+        let locator = ProfileLocator::new(dir.path(), None);
+
+        // WHEN
+        let result = read_profile(&locator, &locator.find("a"));
+
+        // THEN
+        assert!(matches!(
+            result,
+            Err(GetClustersError::IncludedProfileDoesntExist(..))
+        ));
+    }
+
+    #[test]
+    fn including_a_profile_outside_the_profiles_directory_fails() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        write_profile(&dir, "a", r#"include = ["../elsewhere"]"#);
+        std::fs::write(dir.path().join("elsewhere.toml"), "clusters = []")
+            .expect("profile outside the profiles directory should've been written");
+        let locator = ProfileLocator::new(dir.path(), None);
+
+        // WHEN
+        let result = read_profile(&locator, &locator.find("a"));
+
+        // THEN
+        let error = result.expect_err("reading the profile should've failed");
+        assert_eq!(
+            error.to_string(),
+            r#"profile "../elsewhere" (included by "a") has an invalid name; valid regex: ^[a-z0-9_-]{1,20}$"#
+        );
+    }
 }
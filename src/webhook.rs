@@ -0,0 +1,57 @@
+use reqwest::{Client, RequestBuilder, StatusCode};
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RESPONSE_LEN: usize = 300;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PostToWebhookError {
+    #[error("couldn't send message to webhook: {0}")]
+    CouldntSend(reqwest::Error),
+    #[error("webhook responded with {0}: {1}")]
+    Rejected(StatusCode, String),
+}
+
+impl PostToWebhookError {
+    /// Whether sending the same message again might succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PostToWebhookError::CouldntSend(_) => true,
+            PostToWebhookError::Rejected(status, _) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+pub fn webhook_client() -> Result<Client, PostToWebhookError> {
+    Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| PostToWebhookError::CouldntSend(e.without_url()))
+}
+
+/// Sends a request to a webhook, treating responses other than 2xx as errors.
+pub async fn send_to_webhook(request: RequestBuilder) -> Result<(), PostToWebhookError> {
+    // webhook URLs are secrets, so they're left out of errors
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PostToWebhookError::CouldntSend(e.without_url()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let body = body.trim();
+        let body = if body.chars().count() > MAX_RESPONSE_LEN {
+            let mut truncated = body.chars().take(MAX_RESPONSE_LEN - 1).collect::<String>();
+            truncated.push('…');
+            truncated
+        } else {
+            body.to_string()
+        };
+        return Err(PostToWebhookError::Rejected(status, body));
+    }
+
+    Ok(())
+}
@@ -29,6 +29,7 @@ impl Fixture {
 
     pub fn base_cmd(&self) -> Command {
         let mut cmd = Command::new(&self._bin_path);
+        cmd.env_remove("ECSCOPE_PROFILE");
         cmd.args(["--config-dir", &self.config_dir_path]);
         cmd
     }
@@ -39,10 +40,18 @@ impl Fixture {
         S: AsRef<OsStr>,
     {
         let mut command = Command::new(&self._bin_path);
+        command.env_remove("ECSCOPE_PROFILE");
         command.args(args);
         command.args(["--config-dir", &self.config_dir_path]);
         command
     }
+
+    /// Writes a file to ecscope's directory in the config directory.
+    pub fn write_config_file(&self, file_name: &str, contents: &str) {
+        let dir = PathBuf::from(&self.config_dir_path).join("ecscope");
+        std::fs::create_dir_all(&dir).expect("config directory should've been created");
+        std::fs::write(dir.join(file_name), contents).expect("config file should've been written");
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,245 @@
+#[macro_use]
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn html_report_can_be_written_to_a_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "html",
+        "--output",
+        "report.html",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                     List Deployments
+    profile:                     profile
+    profile file:                <not provided>
+    service name filter:         <not provided>
+    key filter:                  <not provided>
+    tag filters:                 <not provided>
+    where:                       <not provided>
+    state:                       <not provided>
+    format:                      html
+    output file:                 report.html
+    post to:                     <not provided>
+    mode:                        default
+    skip opening web results:    false
+    web cache ttl:               5s
+    web cache max stale:         30s
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn webhook_urls_are_redacted_in_debug_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "slack",
+        "--post-to",
+        "https://hooks.slack.com/services/T000/B000/XXXX",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                     List Deployments
+    profile:                     profile
+    profile file:                <not provided>
+    service name filter:         <not provided>
+    key filter:                  <not provided>
+    tag filters:                 <not provided>
+    where:                       <not provided>
+    state:                       <not provided>
+    format:                      slack
+    output file:                 <not provided>
+    post to:                     https://hooks.slack.com/<redacted>
+    mode:                        default
+    skip opening web results:    false
+    web cache ttl:               5s
+    web cache max stale:         30s
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn posting_to_a_webhook_needs_the_slack_format() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "markdown",
+        "--post-to",
+        "https://hooks.slack.com/services/T000/B000/XXXX",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: results can only be posted to a webhook with --format slack
+    ");
+}
+
+#[test]
+fn posting_to_a_webhook_needs_the_default_mode() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "slack",
+        "--mode",
+        "web",
+        "--post-to",
+        "https://hooks.slack.com/services/T000/B000/XXXX",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: results can only be posted to a webhook with --mode default
+    ");
+}
+
+#[test]
+fn webhook_urls_need_to_be_http_or_https() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "slack",
+        "--post-to",
+        "ftp://hooks.example.com/webhook",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'ftp://hooks.example.com/webhook' for '--post-to <WEBHOOK_URL>': webhook URL is not valid; it should be an http(s) URL
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn results_cant_be_both_posted_and_written_to_a_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "deps",
+        "profile",
+        "--format",
+        "slack",
+        "--post-to",
+        "https://hooks.slack.com/services/T000/B000/XXXX",
+        "--output",
+        "report.json",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--post-to <WEBHOOK_URL>' cannot be used with '--output <PATH>'
+
+    Usage: ecscope deps --format <STRING> --post-to <WEBHOOK_URL> --config-dir <PATH> <PROFILE>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn unknown_formats_are_rejected() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["deps", "profile", "--format", "pdf"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'pdf' for '--format <STRING>'
+      [possible values: delimited, html, json, markdown, plain, slack]
+
+    For more information, try '--help'.
+    ");
+}
@@ -27,6 +27,8 @@ fn shows_help() {
       deps      List ECS deployments
       profiles  Manage ecscope's profiles
       monitor   Open monitoring TUI
+      serve     Serve the deployments of one or more profiles over HTTP
+      notify    Send changes in deployments to webhooks, as they happen
       help      Print this message or the help of the given subcommand(s)
 
     Options:
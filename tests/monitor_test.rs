@@ -26,11 +26,16 @@ fn using_regex_for_search_filter_works() {
     [your arguments]
     command:                Monitor resources
     profile:                profile
+    profile file:           <not provided>
     service name filter:    .*-service
     key filter:             <not provided>
+    tag filters:            <not provided>
+    where:                  <not provided>
+    fresh session:          false
 
     [computed config]
     config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
 
     ----- stderr -----
     ");
@@ -54,11 +59,202 @@ fn using_regex_for_key_filter_works() {
     [your arguments]
     command:                Monitor resources
     profile:                profile
+    profile file:           <not provided>
     service name filter:    <not provided>
     key filter:             qa|staging
+    tag filters:            <not provided>
+    where:                  <not provided>
+    fresh session:          false
 
     [computed config]
     config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn profile_can_be_provided_via_env_var() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["monitor", "--debug"]);
+    cmd.env("ECSCOPE_PROFILE", "from-env");
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Monitor resources
+    profile:                <not provided>
+    profile file:           <not provided>
+    service name filter:    <not provided>
+    key filter:             <not provided>
+    tag filters:            <not provided>
+    where:                  <not provided>
+    fresh session:          false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn profile_file_takes_precedence_over_env_var() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "monitor",
+        "--profile-file",
+        "path/to/profile.toml",
+        "--debug",
+    ]);
+    cmd.env("ECSCOPE_PROFILE", "from-env");
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Monitor resources
+    profile:                <not provided>
+    profile file:           path/to/profile.toml
+    service name filter:    <not provided>
+    key filter:             <not provided>
+    tag filters:            <not provided>
+    where:                  <not provided>
+    fresh session:          false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      profile file (path/to/profile.toml)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn using_tag_filters_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "monitor",
+        "profile",
+        "-t",
+        "team=payments",
+        "--tag",
+        "tier=1",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Monitor resources
+    profile:                profile
+    profile file:           <not provided>
+    service name filter:    <not provided>
+    key filter:             <not provided>
+    tag filters:            team=payments, tier=1
+    where:                  <not provided>
+    fresh session:          false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn using_a_where_filter_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "monitor",
+        "profile",
+        "--where",
+        "key=prod and running<desired",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Monitor resources
+    profile:                profile
+    profile file:           <not provided>
+    service name filter:    <not provided>
+    key filter:             <not provided>
+    tag filters:            <not provided>
+    where:                  key=prod and running<desired
+    fresh session:          false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn starting_a_fresh_session_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["monitor", "profile", "--fresh", "--debug"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Monitor resources
+    profile:                profile
+    profile file:           <not provided>
+    service name filter:    <not provided>
+    key filter:             <not provided>
+    tag filters:            <not provided>
+    where:                  <not provided>
+    fresh session:          true
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
 
     ----- stderr -----
     ");
@@ -68,6 +264,74 @@ fn using_regex_for_key_filter_works() {
 //  FAILURES  //
 //------------//
 
+#[test]
+fn using_invalid_tag_filter_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["monitor", "profile", "-t", "team", "--debug"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'team' for '--tag <KEY=VALUE>': tag filter "team" is not of the form key=value
+
+    For more information, try '--help'.
+    "#);
+}
+
+#[test]
+fn using_profile_name_and_profile_file_together_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "monitor",
+        "profile",
+        "--profile-file",
+        "path/to/profile.toml",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '[PROFILE]' cannot be used with '--profile-file <PATH>'
+
+    Usage: ecscope monitor --debug --config-dir <PATH> <PROFILE>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn not_providing_a_profile_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let cwd = tempfile::tempdir().expect("temporary directory should've been created");
+    let mut cmd = fx.cmd(["monitor"]);
+    cmd.current_dir(cwd.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: no profile provided; pass one as an argument, set ECSCOPE_PROFILE, use --profile-file, or add a .ecscope.toml to your project
+    ");
+}
+
 #[test]
 fn using_invalid_regex_for_search_filter_fails() {
     // GIVEN
@@ -113,3 +377,69 @@ fn using_invalid_regex_for_key_filter_fails() {
     For more information, try '--help'.
     "#);
 }
+
+#[test]
+fn using_a_where_filter_with_fields_services_dont_have_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["monitor", "profile", "--where", "failed>0", "--debug"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'failed>0' for '--where <EXPR>': filter "failed>0" is not valid: field "failed" isn't available for services
+
+    For more information, try '--help'.
+    "#);
+}
+
+#[test]
+fn using_an_invalid_tui_config_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file(
+        "tui.toml",
+        r#"
+[keys]
+refresh = ["x"]
+search = ["x"]
+"#,
+    );
+    let mut cmd = fx.cmd(["monitor", "profile"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: tui.toml has invalid keys: key "x" is bound to both "refresh" and "search"
+    "#);
+}
+
+#[test]
+fn using_an_invalid_where_filter_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["monitor", "profile", "-w", "failed>many", "--debug"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'failed>many' for '--where <EXPR>': filter "failed>many" is not valid: operator ">" needs a number or a numeric field on its right hand side
+
+    For more information, try '--help'.
+    "#);
+}
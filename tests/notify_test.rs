@@ -0,0 +1,155 @@
+#[macro_use]
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn debug_output_shows_notify_arguments() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "notify",
+        "profile",
+        "--key-filter",
+        "prod",
+        "--notify-file",
+        "hooks.toml",
+        "--interval",
+        "10",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Notify about deployments
+    profile:                profile
+    profile file:           <not provided>
+    service name filter:    <not provided>
+    key filter:             prod
+    tag filters:            <not provided>
+    notify file:            hooks.toml
+    interval:               10s
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+    profile source:      config directory ([TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn notifying_fails_without_a_notify_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["notify", "profile"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: [TEMP_FILE] doesn't exist; add webhooks to be notified to it
+    ");
+}
+
+#[test]
+fn notifying_fails_with_an_invalid_notify_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file(
+        "notify.toml",
+        r#"
+[[webhooks]]
+name = "slack"
+url = "https://hooks.slack.com/services/T000/B000/XXXX"
+events = ["finished"]
+"#,
+    );
+    let mut cmd = fx.cmd(["notify", "profile"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: [TEMP_FILE] is invalid: TOML parse error at line 5, column 11
+      |
+    5 | events = ["finished"]
+      |           ^^^^^^^^^^
+    unknown variant `finished`, expected one of `started`, `completed`, `failing`, `unreachable`
+    "#);
+}
+
+#[test]
+fn notifying_fails_with_templates_that_dont_result_in_json() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file(
+        "notify.toml",
+        r#"
+[[webhooks]]
+name = "slack"
+url = "https://hooks.slack.com/services/T000/B000/XXXX"
+template = '{"text": "{{message}}", "running": {{running}}}'
+"#,
+    );
+    let mut cmd = fx.cmd(["notify", "profile"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: webhook "slack" has an invalid template: template doesn't result in valid JSON for "unreachable" events: expected value at line 1 column 130
+    "#);
+}
+
+#[test]
+fn notifying_fails_for_an_interval_of_zero() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["notify", "profile", "--interval", "0"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '0' for '--interval <SECONDS>': 0 is not in 1..18446744073709551615
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,240 @@
+#[macro_use]
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn serving_with_defaults_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "prod", "--debug"]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Serve deployments
+    profiles:               prod
+    bind address:           127.0.0.1
+    port:                   4500
+    cache ttl:              5s
+    cache max stale:        30s
+    min refresh interval:   1s
+    rate limit:             300 requests/minute
+    token file:             <not provided>
+    htpasswd file:          <not provided>
+    trusted user header:    <not provided>
+    cors origins:           <not provided>
+    redact:                 false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn serving_several_profiles_on_a_custom_address_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "serve",
+        "prod",
+        "qa",
+        "--bind",
+        "0.0.0.0",
+        "--port",
+        "8080",
+        "--cache-ttl",
+        "10",
+        "--cache-max-stale",
+        "60",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Serve deployments
+    profiles:               prod, qa
+    bind address:           0.0.0.0
+    port:                   8080
+    cache ttl:              10s
+    cache max stale:        60s
+    min refresh interval:   1s
+    rate limit:             300 requests/minute
+    token file:             <not provided>
+    htpasswd file:          <not provided>
+    trusted user header:    <not provided>
+    cors origins:           <not provided>
+    redact:                 false
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn serving_with_access_control_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "serve",
+        "prod",
+        "--htpasswd",
+        "/etc/ecscope/htpasswd",
+        "--cors-origin",
+        "https://tools.example.com",
+        "--cors-origin",
+        "http://localhost:3000",
+        "--redact",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    apply_common_filters!();
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO:
+
+    [your arguments]
+    command:                Serve deployments
+    profiles:               prod
+    bind address:           127.0.0.1
+    port:                   4500
+    cache ttl:              5s
+    cache max stale:        30s
+    min refresh interval:   1s
+    rate limit:             300 requests/minute
+    token file:             <not provided>
+    htpasswd file:          /etc/ecscope/htpasswd
+    trusted user header:    <not provided>
+    cors origins:           https://tools.example.com, http://localhost:3000
+    redact:                 true
+
+    [computed config]
+    config directory:    [TEMP_FILE]
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn serving_fails_without_a_profile() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the following required arguments were not provided:
+      <PROFILE>...
+
+    Usage: ecscope serve --config-dir <PATH> <PROFILE>...
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn serving_fails_for_profile_names_that_cant_be_used_in_paths() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "prod/eu", "--debug"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'prod/eu' for '<PROFILE>...': valid regex: ^[a-z0-9_-]{1,20}$
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn serving_fails_with_more_than_one_way_of_authenticating() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "serve",
+        "prod",
+        "--token-file",
+        "token",
+        "--trusted-user-header",
+        "X-Forwarded-User",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--token-file <PATH>' cannot be used with '--trusted-user-header <HEADER>'
+
+    Usage: ecscope serve --token-file <PATH> --config-dir <PATH> <PROFILE>...
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn serving_fails_for_invalid_cors_origins() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "prod", "--cors-origin", "*"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '*' for '--cors-origin <ORIGIN>': origin "*" is not valid; it should look like https://tools.example.com
+
+    For more information, try '--help'.
+    "#);
+}